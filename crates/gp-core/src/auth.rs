@@ -0,0 +1,168 @@
+use git2::{Config, Cred, CredentialType, Error};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::tr;
+
+/// 认证失败后向用户索取凭据的方式，由调用方（如 `gp` 二进制）注册；没有注册时不做交互
+pub trait CredentialPrompt: Send + Sync {
+    /// HTTPS 的用户名和密码 / token，用户放弃时返回 `None`
+    fn user_pass(&self, url: &str, username: Option<&str>) -> Option<(String, String)>;
+    /// 加密 SSH 私钥的口令，用户放弃时返回 `None`
+    fn passphrase(&self, key: &Path) -> Option<String>;
+}
+
+static SSH_KEYS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+static PROMPT: OnceLock<Box<dyn CredentialPrompt>> = OnceLock::new();
+
+/// 设置 ssh-agent 之后依次尝试的私钥文件，只在第一次调用时生效
+pub fn use_ssh_keys(keys: Vec<PathBuf>) {
+    let _ = SSH_KEYS.set(keys);
+}
+
+/// 注册交互式索取凭据的方式，只在第一次调用时生效
+pub fn use_prompt(prompt: Box<dyn CredentialPrompt>) {
+    let _ = PROMPT.set(prompt);
+}
+
+/// 未配置 `ssh_keys` 时尝试的私钥：`~/.ssh/id_ed25519`、`id_ecdsa`、`id_rsa`
+pub fn default_ssh_keys() -> Vec<PathBuf> {
+    let home = match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home),
+        None => return Vec::new(),
+    };
+    ["id_ed25519", "id_ecdsa", "id_rsa"]
+        .iter()
+        .map(|name| home.join(".ssh").join(name))
+        .collect()
+}
+
+/// 一次远端操作中的认证状态。libgit2 每次认证失败都会再次调用回调，
+/// 这里按 ssh-agent → 私钥文件、credential helper → 交互输入的顺序逐个尝试，全部用完后报错
+pub(crate) struct Authenticator {
+    config: Option<Config>,
+    interactive: bool,
+    agent_tried: bool,
+    next_key: usize,
+    tried_keys: Vec<String>,
+    helper_tried: bool,
+    prompted: bool,
+}
+
+impl Authenticator {
+    /// `interactive` 为 false 时不调用注册的 `CredentialPrompt`，用于后台探测远端等不该打扰用户的场合
+    pub(crate) fn new(config: Option<Config>, interactive: bool) -> Self {
+        Self {
+            config,
+            interactive,
+            agent_tried: false,
+            next_key: 0,
+            tried_keys: Vec::new(),
+            helper_tried: false,
+            prompted: false,
+        }
+    }
+
+    pub(crate) fn credentials(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, Error> {
+        let user = username_from_url.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(user);
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return self.ssh_key(user);
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return self.user_pass(url, username_from_url);
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        Err(Error::from_str(tr!("git.auth_failed")))
+    }
+
+    fn ssh_key(&mut self, user: &str) -> Result<Cred, Error> {
+        if !self.agent_tried {
+            self.agent_tried = true;
+            if std::env::var_os("SSH_AUTH_SOCK").is_some() {
+                return Cred::ssh_key_from_agent(user);
+            }
+        }
+
+        let keys = SSH_KEYS.get_or_init(default_ssh_keys);
+        while let Some(key) = keys.get(self.next_key) {
+            self.next_key += 1;
+            if !key.is_file() {
+                continue;
+            }
+            let passphrase = if ssh_key_encrypted(key) {
+                match self.prompt().and_then(|p| p.passphrase(key)) {
+                    Some(passphrase) => Some(passphrase),
+                    None => continue,
+                }
+            } else {
+                None
+            };
+            self.tried_keys.push(key.display().to_string());
+            return Cred::ssh_key(user, None, key, passphrase.as_deref());
+        }
+
+        let tried = if self.tried_keys.is_empty() {
+            tr!("auth.no_key_files").to_string()
+        } else {
+            self.tried_keys.join(", ")
+        };
+        Err(Error::from_str(&tr!("auth.ssh_failed", tried)))
+    }
+
+    fn user_pass(&mut self, url: &str, username: Option<&str>) -> Result<Cred, Error> {
+        if !self.helper_tried {
+            self.helper_tried = true;
+            if let Some(config) = &self.config {
+                if let Ok(cred) = Cred::credential_helper(config, url, username) {
+                    return Ok(cred);
+                }
+            }
+        }
+        if !self.prompted {
+            self.prompted = true;
+            if let Some((user, secret)) = self.prompt().and_then(|p| p.user_pass(url, username)) {
+                return Cred::userpass_plaintext(&user, &secret);
+            }
+        }
+        Err(Error::from_str(&tr!("auth.https_failed", url)))
+    }
+
+    fn prompt(&self) -> Option<&'static dyn CredentialPrompt> {
+        if !self.interactive {
+            return None;
+        }
+        PROMPT.get().map(|p| p.as_ref())
+    }
+}
+
+/// 判断私钥是否有口令保护。PEM 格式看头部；OpenSSH 格式的 base64 正文开头编码了加密算法，
+/// 未加密的私钥（cipher 与 kdf 均为 none）总是以固定的前缀开始
+fn ssh_key_encrypted(key: &Path) -> bool {
+    const OPENSSH_UNENCRYPTED: &str = "b3BlbnNzaC1rZXktdjEAAAAABG5vbmUAAAAEbm9uZQ";
+    let content = match fs::read_to_string(key) {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+    if content.contains("ENCRYPTED") {
+        return true;
+    }
+    if content.contains("BEGIN OPENSSH PRIVATE KEY") {
+        let body: String = content
+            .lines()
+            .filter(|l| !l.starts_with("-----"))
+            .collect();
+        return !body.starts_with(OPENSSH_UNENCRYPTED);
+    }
+    false
+}
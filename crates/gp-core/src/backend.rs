@@ -0,0 +1,480 @@
+//! worktree 清理判定用到的仓库查询，抽象成 `GitBackend`。
+//!
+//! 真实实现直接基于 git2 的 `Repository`；`MemoryBackend` 是不碰文件系统的内存替身，
+//! 可以随意构造“有改动”“上游已删除”“领先 2 个提交”之类的状态，用来测试判定逻辑或给其它前端做演示。
+
+use anyhow::{Context, Result};
+use git2::{BranchType, Oid, Repository};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::git::{gone_upstream, is_merged_into, main_branch_commit, worktree_is_dirty};
+use crate::tr;
+use crate::worktree::{commit_is_published, dir_size, last_used, lock_state};
+
+/// 当前检出的是分支还是游离 HEAD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Head {
+    Branch { name: String, oid: Oid },
+    Detached(Oid),
+}
+
+/// 本地分支的上游
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Upstream {
+    /// 上游存在，`name` 如 `origin/main`
+    Tracking { name: String, oid: Oid },
+    /// 配置了上游，但其 ref 已不存在（`[gone]`）
+    Gone,
+    /// 没有配置上游
+    Unset,
+}
+
+/// 一个 linked worktree 的记录
+#[derive(Debug, Clone)]
+pub struct LinkedWorktree {
+    pub name: String,
+    pub path: PathBuf,
+    /// 见 `worktree::lock_state`
+    pub lock_reason: Option<String>,
+}
+
+/// worktree 判定逻辑对仓库的全部查询。主仓库和各 worktree 都实现这一 trait，
+/// `open_worktree` 从前者得到后者
+pub trait GitBackend {
+    type Worktree: GitBackend;
+
+    /// 所有 linked worktree 的名称
+    fn worktree_names(&self) -> Result<Vec<String>>;
+    fn find_worktree(&self, name: &str) -> Result<LinkedWorktree>;
+    fn open_worktree(&self, worktree: &LinkedWorktree) -> Result<Self::Worktree>;
+
+    /// 工作区是否有未提交的修改；无法读取时按有修改处理
+    fn is_dirty(&self) -> bool;
+    /// 读不到 HEAD（如尚无提交）时为 `None`
+    fn head(&self) -> Option<Head>;
+    fn upstream(&self, branch: &str) -> Upstream;
+    /// 主分支（见 `git::default_branch`）当前的提交
+    fn main_commit(&self) -> Option<Oid>;
+    /// `oid` 是否已被 `main` 包含
+    fn is_merged_into(&self, main: Oid, oid: Oid) -> bool;
+    /// `local` 相对 `upstream` 领先、落后的提交数
+    fn ahead_behind(&self, local: Oid, upstream: Oid) -> Option<(usize, usize)>;
+    /// 提交是否已被某个 tag 或远端追踪分支包含
+    fn is_published(&self, oid: Oid) -> bool;
+    /// 最近一次使用的时间戳，见 `worktree::last_used`
+    fn last_used(&self) -> Option<i64>;
+    /// 工作区占用的字节数
+    fn disk_usage(&self) -> u64;
+}
+
+impl GitBackend for Repository {
+    type Worktree = Repository;
+
+    fn worktree_names(&self) -> Result<Vec<String>> {
+        Ok(self
+            .worktrees()?
+            .iter()
+            .flatten()
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn find_worktree(&self, name: &str) -> Result<LinkedWorktree> {
+        let wt = Repository::find_worktree(self, name)?;
+        Ok(LinkedWorktree {
+            name: name.to_string(),
+            path: wt.path().to_path_buf(),
+            lock_reason: lock_state(&wt),
+        })
+    }
+
+    fn open_worktree(&self, worktree: &LinkedWorktree) -> Result<Repository> {
+        Ok(Repository::open(&worktree.path)?)
+    }
+
+    fn is_dirty(&self) -> bool {
+        worktree_is_dirty(self)
+    }
+
+    fn head(&self) -> Option<Head> {
+        let head = Repository::head(self).ok()?;
+        let oid = head.target()?;
+        if head.is_branch() {
+            let name = head.shorthand()?.to_string();
+            Some(Head::Branch { name, oid })
+        } else {
+            Some(Head::Detached(oid))
+        }
+    }
+
+    fn upstream(&self, branch: &str) -> Upstream {
+        let Ok(local) = self.find_branch(branch, BranchType::Local) else {
+            return Upstream::Unset;
+        };
+        if let Ok(upstream) = local.upstream() {
+            let name = upstream.name().ok().flatten().unwrap_or_default();
+            return match upstream.get().target() {
+                Some(oid) => Upstream::Tracking {
+                    name: name.to_string(),
+                    oid,
+                },
+                None => Upstream::Unset,
+            };
+        }
+        match local.get().name().and_then(|r| gone_upstream(self, r)) {
+            Some(_) => Upstream::Gone,
+            None => Upstream::Unset,
+        }
+    }
+
+    fn main_commit(&self) -> Option<Oid> {
+        main_branch_commit(self)
+    }
+
+    fn is_merged_into(&self, main: Oid, oid: Oid) -> bool {
+        is_merged_into(self, main, oid)
+    }
+
+    fn ahead_behind(&self, local: Oid, upstream: Oid) -> Option<(usize, usize)> {
+        self.graph_ahead_behind(local, upstream).ok()
+    }
+
+    fn is_published(&self, oid: Oid) -> bool {
+        commit_is_published(self, oid)
+    }
+
+    fn last_used(&self) -> Option<i64> {
+        last_used(self)
+    }
+
+    fn disk_usage(&self) -> u64 {
+        self.workdir().map(dir_size).unwrap_or(0)
+    }
+}
+
+/// 内存中的仓库替身：各项查询直接返回字段中预设的值。
+/// 主仓库的 `worktrees` 中每一项同时给出 worktree 的记录和它自己的状态
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    pub worktrees: Vec<(LinkedWorktree, MemoryBackend)>,
+    pub dirty: bool,
+    pub head: Option<Head>,
+    /// 本地分支 → 上游，没有列出的分支视为 `Upstream::Unset`
+    pub upstreams: HashMap<String, Upstream>,
+    pub main: Option<Oid>,
+    /// 已并入主分支的提交（主分支的提交本身总是视为已并入）
+    pub merged: HashSet<Oid>,
+    /// 已被 tag 或远端分支包含的提交
+    pub published: HashSet<Oid>,
+    /// `(local, upstream)` → 领先、落后的提交数，没有列出时视为 `(0, 0)`
+    pub ahead_behind: HashMap<(Oid, Oid), (usize, usize)>,
+    pub last_used: Option<i64>,
+    pub disk_usage: u64,
+}
+
+impl GitBackend for MemoryBackend {
+    type Worktree = MemoryBackend;
+
+    fn worktree_names(&self) -> Result<Vec<String>> {
+        Ok(self
+            .worktrees
+            .iter()
+            .map(|(wt, _)| wt.name.clone())
+            .collect())
+    }
+
+    fn find_worktree(&self, name: &str) -> Result<LinkedWorktree> {
+        self.worktrees
+            .iter()
+            .find(|(wt, _)| wt.name == name)
+            .map(|(wt, _)| wt.clone())
+            .with_context(|| tr!("backend.worktree_not_found", name))
+    }
+
+    fn open_worktree(&self, worktree: &LinkedWorktree) -> Result<MemoryBackend> {
+        self.worktrees
+            .iter()
+            .find(|(wt, _)| wt.name == worktree.name)
+            .map(|(_, state)| state.clone())
+            .with_context(|| tr!("backend.worktree_not_found", worktree.name))
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn head(&self) -> Option<Head> {
+        self.head.clone()
+    }
+
+    fn upstream(&self, branch: &str) -> Upstream {
+        self.upstreams
+            .get(branch)
+            .cloned()
+            .unwrap_or(Upstream::Unset)
+    }
+
+    fn main_commit(&self) -> Option<Oid> {
+        self.main
+    }
+
+    fn is_merged_into(&self, main: Oid, oid: Oid) -> bool {
+        oid == main || self.merged.contains(&oid)
+    }
+
+    fn ahead_behind(&self, local: Oid, upstream: Oid) -> Option<(usize, usize)> {
+        Some(
+            self.ahead_behind
+                .get(&(local, upstream))
+                .copied()
+                .unwrap_or((0, 0)),
+        )
+    }
+
+    fn is_published(&self, oid: Oid) -> bool {
+        self.published.contains(&oid)
+    }
+
+    fn last_used(&self) -> Option<i64> {
+        self.last_used
+    }
+
+    fn disk_usage(&self) -> u64 {
+        self.disk_usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keep::KeepStore;
+    use crate::worktree::{
+        clean_status, derives_from_base, plan_clean_in, CleanOptions, CleanPlan,
+        WorktreeCleanStatus,
+    };
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn on_branch(name: &str, upstream: Upstream) -> MemoryBackend {
+        MemoryBackend {
+            head: Some(Head::Branch {
+                name: name.to_string(),
+                oid: oid(1),
+            }),
+            upstreams: HashMap::from([(name.to_string(), upstream)]),
+            ..Default::default()
+        }
+    }
+
+    /// 已推送到同名追踪分支、可以清理的 worktree 状态
+    fn pushed(branch: &str) -> MemoryBackend {
+        on_branch(
+            branch,
+            Upstream::Tracking {
+                name: format!("origin/{}", branch),
+                oid: oid(1),
+            },
+        )
+    }
+
+    fn removable_names(plan: &CleanPlan) -> Vec<&str> {
+        plan.removable.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    fn linked(name: &str, state: MemoryBackend) -> (LinkedWorktree, MemoryBackend) {
+        let worktree = LinkedWorktree {
+            name: name.to_string(),
+            path: PathBuf::from("/wt").join(name),
+            lock_reason: None,
+        };
+        (worktree, state)
+    }
+
+    #[test]
+    fn worktrees_are_found_and_opened_by_name() {
+        let repo = MemoryBackend {
+            worktrees: vec![
+                linked("a", on_branch("main-a", Upstream::Unset)),
+                linked(
+                    "b",
+                    MemoryBackend {
+                        dirty: true,
+                        ..Default::default()
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(repo.worktree_names().unwrap(), ["a", "b"]);
+        let b = repo.find_worktree("b").unwrap();
+        assert_eq!(b.path, PathBuf::from("/wt/b"));
+        assert!(repo.open_worktree(&b).unwrap().is_dirty());
+        assert!(repo.find_worktree("missing").is_err());
+    }
+
+    #[test]
+    fn derives_from_base_by_name_prefix_or_upstream() {
+        let by_name = on_branch("main-20240101", Upstream::Unset);
+        assert!(derives_from_base(&by_name, "main"));
+        assert!(!derives_from_base(&by_name, "mai"));
+
+        let by_upstream = on_branch(
+            "feature",
+            Upstream::Tracking {
+                name: "origin/develop".to_string(),
+                oid: oid(1),
+            },
+        );
+        assert!(derives_from_base(&by_upstream, "develop"));
+        assert!(!derives_from_base(&by_upstream, "main"));
+
+        let detached = MemoryBackend {
+            head: Some(Head::Detached(oid(1))),
+            ..Default::default()
+        };
+        assert!(!derives_from_base(&detached, "main"));
+    }
+
+    #[test]
+    fn clean_status_reads_opened_worktree_state() {
+        let repo = MemoryBackend {
+            worktrees: vec![
+                linked(
+                    "pushed",
+                    on_branch(
+                        "main-a",
+                        Upstream::Tracking {
+                            name: "origin/main-a".to_string(),
+                            oid: oid(1),
+                        },
+                    ),
+                ),
+                linked(
+                    "dirty",
+                    MemoryBackend {
+                        dirty: true,
+                        ..Default::default()
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+        let status = |name: &str| {
+            let wt = repo.find_worktree(name).unwrap();
+            clean_status(&repo.open_worktree(&wt).unwrap(), CleanOptions::default())
+        };
+        assert_eq!(status("pushed"), WorktreeCleanStatus::Removable);
+        assert!(matches!(status("dirty"), WorktreeCleanStatus::Skip(_)));
+    }
+
+    #[test]
+    fn plan_clean_skips_kept_locked_and_unclean() {
+        let mut locked = linked("locked", pushed("main-b"));
+        locked.0.lock_reason = Some("usb disk".to_string());
+        let repo = MemoryBackend {
+            worktrees: vec![
+                linked(
+                    "ok",
+                    MemoryBackend {
+                        disk_usage: 4096,
+                        ..pushed("main-a")
+                    },
+                ),
+                linked("kept", pushed("main-c")),
+                locked,
+                linked(
+                    "dirty",
+                    MemoryBackend {
+                        dirty: true,
+                        ..pushed("main-d")
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+        let mut keep = KeepStore::default();
+        keep.toggle("kept");
+
+        let plan = plan_clean_in(&repo, &keep, None, CleanOptions::default()).unwrap();
+        assert_eq!(removable_names(&plan), ["ok"]);
+        let ok = &plan.removable[0];
+        assert_eq!(ok.branch.as_deref(), Some("main-a"));
+        assert_eq!(ok.path, PathBuf::from("/wt/ok"));
+        assert_eq!(ok.size, 4096);
+        assert_eq!(
+            plan.skipped,
+            [
+                ("kept".to_string(), tr!("skip.kept")),
+                ("locked".to_string(), tr!("skip.locked")),
+                ("dirty".to_string(), tr!("skip.dirty")),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_clean_only_considers_worktrees_from_base() {
+        let repo = MemoryBackend {
+            worktrees: vec![
+                linked("by-name", pushed("main-a")),
+                linked(
+                    "by-upstream",
+                    on_branch(
+                        "feature",
+                        Upstream::Tracking {
+                            name: "origin/main".to_string(),
+                            oid: oid(1),
+                        },
+                    ),
+                ),
+                linked("other", pushed("develop-a")),
+                linked("other-kept", pushed("develop-b")),
+            ],
+            ..Default::default()
+        };
+        let mut keep = KeepStore::default();
+        keep.toggle("other-kept");
+
+        let plan = plan_clean_in(&repo, &keep, Some("main"), CleanOptions::default()).unwrap();
+        assert_eq!(removable_names(&plan), ["by-name", "by-upstream"]);
+        // 不是从 base 派生的 worktree 不参与判定，连跳过原因也不列出
+        assert!(plan.skipped.is_empty());
+    }
+
+    #[test]
+    fn plan_clean_older_than_skips_recently_used() {
+        let now = chrono::Utc::now().timestamp();
+        let used = |name: &str, last_used: Option<i64>| {
+            linked(
+                name,
+                MemoryBackend {
+                    last_used,
+                    ..pushed(name)
+                },
+            )
+        };
+        let repo = MemoryBackend {
+            worktrees: vec![
+                used("recent", Some(now - 60)),
+                used("stale", Some(now - 7200)),
+                used("unknown", None),
+            ],
+            ..Default::default()
+        };
+        let opts = CleanOptions {
+            older_than: Some(3600),
+            ..Default::default()
+        };
+
+        let plan = plan_clean_in(&repo, &KeepStore::default(), None, opts).unwrap();
+        assert_eq!(removable_names(&plan), ["stale", "unknown"]);
+        assert_eq!(plan.removable[0].last_used, Some(now - 7200));
+        assert_eq!(plan.skipped, [("recent".to_string(), tr!("skip.recent"))]);
+
+        let plan =
+            plan_clean_in(&repo, &KeepStore::default(), None, CleanOptions::default()).unwrap();
+        assert_eq!(removable_names(&plan), ["recent", "stale", "unknown"]);
+    }
+}
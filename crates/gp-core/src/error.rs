@@ -0,0 +1,47 @@
+use std::fmt;
+
+use crate::tr;
+
+/// 创建分支 / worktree 时可区分处理的错误，其余情况归入 `Other` 交给 anyhow 展示
+#[derive(Debug)]
+pub enum GitError {
+    /// 要创建的本地分支已存在
+    BranchExists(String),
+    /// 本地没有 `origin/<branch>` 的缓存，附带说明原因和处理办法的提示
+    RemoteRefMissing(String),
+    /// 未提交的修改与要检出的内容冲突
+    CheckoutConflict,
+    Other(anyhow::Error),
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BranchExists(name) => write!(f, "{}", tr!("err.branch_exists", name)),
+            Self::RemoteRefMissing(hint) => write!(f, "{}", hint),
+            Self::CheckoutConflict => write!(f, "{}", tr!("err.checkout_conflict")),
+            Self::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Other(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for GitError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Other(e)
+    }
+}
+
+impl From<git2::Error> for GitError {
+    fn from(e: git2::Error) -> Self {
+        Self::Other(e.into())
+    }
+}
@@ -0,0 +1,520 @@
+use anyhow::{bail, Result};
+use chrono::Utc;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::git::{common_dir, remote};
+use crate::tr;
+
+/// 设置后覆盖频率文件的位置
+pub const FREQ_FILE_ENV: &str = "GP_FREQ_FILE";
+
+/// 旧版放在仓库 `.git` 目录中的频率文件名
+const LEGACY_FILE_NAME: &str = "branch-picker-freq.json";
+
+/// 频率文件位置：`$XDG_DATA_HOME/gp/freq/<仓库标识>.json`（未设置时为 `~/.local/share/...`），
+/// 取不到数据目录时退回仓库的 `.git` 目录
+pub fn freq_path(repo: &Repository) -> PathBuf {
+    match std::env::var_os(FREQ_FILE_ENV) {
+        Some(p) if !p.is_empty() => PathBuf::from(p),
+        _ => match data_dir() {
+            Some(dir) => dir
+                .join("freq")
+                .join(format!("{}.json", file_slug(&repo_key(repo)))),
+            None => common_dir(repo).join(LEGACY_FILE_NAME),
+        },
+    }
+}
+
+/// `$XDG_DATA_HOME/gp`，未设置时为 `~/.local/share/gp`
+fn data_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(base.join("gp"))
+}
+
+/// 区分仓库的标识：有远程仓库时取去掉协议、用户名和 `.git` 后缀的 URL，同一远端的多个 clone 共用记录；
+/// 否则取主仓库 `.git` 目录的绝对路径
+fn repo_key(repo: &Repository) -> String {
+    let url = repo
+        .find_remote(remote())
+        .ok()
+        .and_then(|r| r.url().map(str::to_string))
+        .filter(|u| !u.is_empty());
+    match url {
+        Some(url) => {
+            let rest = url.split_once("://").map_or(url.as_str(), |(_, r)| r);
+            let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+            rest.trim_end_matches('/')
+                .trim_end_matches(".git")
+                .replace(':', "/")
+        }
+        None => {
+            let dir = common_dir(repo);
+            fs::canonicalize(&dir).unwrap_or(dir).display().to_string()
+        }
+    }
+}
+
+/// 把仓库标识转成文件名，字母数字和 `-` `.` 以外的字符换成 `_`
+fn file_slug(key: &str) -> String {
+    key.trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 把旧版 `.git/branch-picker-freq.json`（linked worktree 中为 `.git/worktrees/<name>/` 下）
+/// 合并进当前的频率文件后删除。频率文件仍在 `.git` 中（设置了 `GP_FREQ_FILE` 或取不到数据目录）时不做处理
+pub fn migrate_legacy(repo: &Repository) -> Result<()> {
+    let path = freq_path(repo);
+    let mut legacy = vec![common_dir(repo).join(LEGACY_FILE_NAME)];
+    if repo.is_worktree() {
+        legacy.push(repo.path().join(LEGACY_FILE_NAME));
+    }
+    legacy.retain(|l| l.is_file() && *l != path);
+    if legacy.is_empty() {
+        return Ok(());
+    }
+
+    let _lock = FileLock::acquire(&path)?;
+    let mut store = FrequencyStore::load(&path);
+    for old in &legacy {
+        store.absorb(FrequencyStore::load(old));
+    }
+    store.save(&path)?;
+    for old in &legacy {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// 等待锁的最长时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+/// 超过该时长的锁文件视为崩溃进程遗留，直接清除
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// 基于 `<freq 文件>.lock` 的独占锁，create_new 保证只有一个进程能创建成功，Drop 时释放
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    fn acquire(target: &Path) -> Result<Self> {
+        let mut name = target.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        let path = target.with_file_name(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let start = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE_AFTER);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if start.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                        bail!("{}", tr!("freq.lock_timeout", path.display()));
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 计数的动作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UseKind {
+    Branch,
+    Worktree,
+    /// `gp l` 中切换到该本地分支
+    Checkout,
+}
+
+/// 排序时两类计数的权重，对应配置文件中的 `[freq_weights]`
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct FreqWeights {
+    pub branch: u64,
+    pub worktree: u64,
+}
+
+impl Default for FreqWeights {
+    fn default() -> Self {
+        Self {
+            branch: 1,
+            worktree: 1,
+        }
+    }
+}
+
+/// 使用分数的半衰期：每过 14 天，之前的使用只算一半
+const HALF_LIFE_SECS: f64 = 14.0 * 86400.0;
+/// 计算衰减时最多按一年算，没有时间戳的旧记录也不会衰减到 0，彼此间仍按次数排序
+const MAX_DECAY_AGE_SECS: i64 = 365 * 86400;
+
+/// 距今 `age_secs` 秒的使用在分数中所占的比例
+fn decay(age_secs: i64) -> f64 {
+    0.5f64.powf(age_secs.clamp(0, MAX_DECAY_AGE_SECS) as f64 / HALF_LIFE_SECS)
+}
+
+/// 某个分支各类动作按时间衰减后的累计分数，衰减到该分支的 `last_used` 时刻
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+struct Frecency {
+    #[serde(default)]
+    branch: f64,
+    #[serde(default)]
+    worktree: f64,
+    #[serde(default)]
+    checkout: f64,
+}
+
+impl Frecency {
+    fn get(&self, kind: UseKind) -> f64 {
+        match kind {
+            UseKind::Branch => self.branch,
+            UseKind::Worktree => self.worktree,
+            UseKind::Checkout => self.checkout,
+        }
+    }
+
+    fn get_mut(&mut self, kind: UseKind) -> &mut f64 {
+        match kind {
+            UseKind::Branch => &mut self.branch,
+            UseKind::Worktree => &mut self.worktree,
+            UseKind::Checkout => &mut self.checkout,
+        }
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.branch *= factor;
+        self.worktree *= factor;
+        self.checkout *= factor;
+    }
+}
+
+/// gp 创建、删除 worktree 的记录，供 `gp stats` 统计
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct WorktreeLog {
+    #[serde(default)]
+    created: u64,
+    #[serde(default)]
+    removed: u64,
+    /// 尚未删除的 worktree 路径 → 创建时间
+    #[serde(default)]
+    open: HashMap<String, i64>,
+    /// 已删除且知道创建时间的 worktree 的存活时长之和（秒）和个数
+    #[serde(default)]
+    lifetime_secs: i64,
+    #[serde(default)]
+    lifetime_samples: u64,
+}
+
+/// worktree 记录的键，去掉末尾的 `/` 等写法差异，创建和删除时拿到的路径才能对上
+fn worktree_key(path: &Path) -> String {
+    path.components().collect::<PathBuf>().display().to_string()
+}
+
+/// 一个基点分支的使用次数，`gp stats` 用
+#[derive(Debug, Serialize)]
+pub struct BaseUsage {
+    pub branch: String,
+    pub branches: u64,
+    pub worktrees: u64,
+    /// 最近一次使用的 Unix 时间戳，旧版记录没有时为 `None`
+    pub last_used: Option<i64>,
+}
+
+/// worktree 的创建、删除统计，`gp stats` 用
+#[derive(Debug, Serialize)]
+pub struct WorktreeStats {
+    pub created: u64,
+    pub removed: u64,
+    /// 已删除的 worktree 平均存活时长（秒），没有可统计的记录时为 `None`
+    pub average_lifetime_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct FrequencyStore {
+    /// 基于该分支创建普通分支的次数
+    #[serde(default)]
+    branch_counts: HashMap<String, u64>,
+    /// 基于该分支创建 worktree 的次数
+    #[serde(default)]
+    worktree_counts: HashMap<String, u64>,
+    /// 在 `gp l` 中切换到该本地分支的次数
+    #[serde(default)]
+    checkout_counts: HashMap<String, u64>,
+    /// 旧版文件不区分动作的计数，load 时并入 `branch_counts`，不再写回
+    #[serde(default, skip_serializing)]
+    counts: HashMap<String, u64>,
+    /// 最近一次使用的 Unix 时间戳（秒），旧版文件中没有该字段
+    #[serde(default)]
+    last_used: HashMap<String, i64>,
+    /// 按时间衰减的使用分数，排序以它为准；旧版文件中没有该字段，load 时按次数补上
+    #[serde(default)]
+    frecency: HashMap<String, Frecency>,
+    #[serde(default)]
+    worktrees: WorktreeLog,
+    /// 上次选中分支时过滤框里的输入，配置 `remember_filter` 时用于预填
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_filter: Option<String>,
+}
+
+impl FrequencyStore {
+    pub fn load(path: &Path) -> Self {
+        let mut store: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        // 旧版计数无法区分动作，统一算作创建分支
+        for (branch, n) in std::mem::take(&mut store.counts) {
+            *store.branch_counts.entry(branch).or_insert(0) += n;
+        }
+        // 没有分数的旧记录按次数补上，视为都发生在最近一次使用时
+        let missing: Vec<String> = store
+            .branch_counts
+            .keys()
+            .chain(store.worktree_counts.keys())
+            .chain(store.checkout_counts.keys())
+            .filter(|b| !store.frecency.contains_key(*b))
+            .cloned()
+            .collect();
+        for branch in missing {
+            let seeded = Frecency {
+                branch: store.count(&branch, UseKind::Branch) as f64,
+                worktree: store.count(&branch, UseKind::Worktree) as f64,
+                checkout: store.count(&branch, UseKind::Checkout) as f64,
+            };
+            store.frecency.insert(branch, seeded);
+        }
+        store
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+
+        // 先写同目录下的临时文件再 rename，中途中断也不会留下半截 JSON
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// 加锁后重新读取磁盘上的最新内容再计数并写回，避免多个 gp 并发运行时互相覆盖
+    pub fn record(path: &Path, branch: &str, kind: UseKind) -> Result<()> {
+        let _lock = FileLock::acquire(path)?;
+        let mut store = Self::load(path);
+        store.increment(branch, kind);
+        store.save(path)
+    }
+
+    /// 同 `record`，加锁后只更新上次的过滤输入，空输入会清除记录
+    pub fn remember_filter(path: &Path, filter: &str) -> Result<()> {
+        let _lock = FileLock::acquire(path)?;
+        let mut store = Self::load(path);
+        store.last_filter = Some(filter.to_string()).filter(|f| !f.is_empty());
+        store.save(path)
+    }
+
+    /// 同 `record`，记下 gp 在 `worktree` 创建了 worktree
+    pub fn record_worktree_created(path: &Path, worktree: &Path) -> Result<()> {
+        let _lock = FileLock::acquire(path)?;
+        let mut store = Self::load(path);
+        store.worktrees.created += 1;
+        store
+            .worktrees
+            .open
+            .insert(worktree_key(worktree), Utc::now().timestamp());
+        store.save(path)
+    }
+
+    /// 同 `record`，记下 `worktree` 被 gp 删除；是 gp 创建的则计入存活时长
+    pub fn record_worktree_removed(path: &Path, worktree: &Path) -> Result<()> {
+        let _lock = FileLock::acquire(path)?;
+        let mut store = Self::load(path);
+        let log = &mut store.worktrees;
+        log.removed += 1;
+        if let Some(created_at) = log.open.remove(&worktree_key(worktree)) {
+            log.lifetime_secs += (Utc::now().timestamp() - created_at).max(0);
+            log.lifetime_samples += 1;
+        }
+        store.save(path)
+    }
+
+    /// 同 `record`，`from` 处由 gp 创建的 worktree 被移到了 `to`，创建时间跟着走
+    pub fn record_worktree_moved(path: &Path, from: &Path, to: &Path) -> Result<()> {
+        let _lock = FileLock::acquire(path)?;
+        let mut store = Self::load(path);
+        let log = &mut store.worktrees;
+        if let Some(created_at) = log.open.remove(&worktree_key(from)) {
+            log.open.insert(worktree_key(to), created_at);
+        }
+        store.save(path)
+    }
+
+    /// 用来创建过分支或 worktree 的基点分支，按总次数降序，次数相同时最近用过的在前
+    pub fn base_usage(&self) -> Vec<BaseUsage> {
+        let mut usage: Vec<BaseUsage> = self
+            .branch_counts
+            .keys()
+            .chain(self.worktree_counts.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|b| BaseUsage {
+                branch: b.clone(),
+                branches: self.count(b, UseKind::Branch),
+                worktrees: self.count(b, UseKind::Worktree),
+                last_used: Some(self.last_used(b)).filter(|t| *t > 0),
+            })
+            .filter(|u| u.branches + u.worktrees > 0)
+            .collect();
+        usage.sort_by(|a, b| {
+            (b.branches + b.worktrees)
+                .cmp(&(a.branches + a.worktrees))
+                .then(b.last_used.cmp(&a.last_used))
+        });
+        usage
+    }
+
+    pub fn worktree_stats(&self) -> WorktreeStats {
+        let log = &self.worktrees;
+        WorktreeStats {
+            created: log.created,
+            removed: log.removed,
+            average_lifetime_secs: (log.lifetime_samples > 0)
+                .then(|| log.lifetime_secs / log.lifetime_samples as i64),
+        }
+    }
+
+    pub fn last_filter(&self) -> Option<&str> {
+        self.last_filter.as_deref()
+    }
+
+    pub fn increment(&mut self, branch: &str, kind: UseKind) {
+        let counts = match kind {
+            UseKind::Branch => &mut self.branch_counts,
+            UseKind::Worktree => &mut self.worktree_counts,
+            UseKind::Checkout => &mut self.checkout_counts,
+        };
+        *counts.entry(branch.to_string()).or_insert(0) += 1;
+
+        // 先把之前的分数衰减到现在，再记上这一次
+        let now = Utc::now().timestamp();
+        let age = now - self.last_used(branch);
+        let frecency = self.frecency.entry(branch.to_string()).or_default();
+        frecency.scale(decay(age));
+        *frecency.get_mut(kind) += 1.0;
+        self.last_used.insert(branch.to_string(), now);
+    }
+
+    pub fn count(&self, branch: &str, kind: UseKind) -> u64 {
+        let counts = match kind {
+            UseKind::Branch => &self.branch_counts,
+            UseKind::Worktree => &self.worktree_counts,
+            UseKind::Checkout => &self.checkout_counts,
+        };
+        counts.get(branch).copied().unwrap_or(0)
+    }
+
+    /// 某类动作衰减到现在的分数，用于 `gp l` 排序
+    pub fn frecency(&self, branch: &str, kind: UseKind) -> f64 {
+        self.frecency
+            .get(branch)
+            .map_or(0.0, |f| f.get(kind) * self.decay_to_now(branch))
+    }
+
+    /// 按权重合计创建分支、创建 worktree 两类分数并衰减到现在，用于排序。
+    /// 很久以前用得多的分支会被最近刚用过的分支超过
+    pub fn score(&self, branch: &str, weights: FreqWeights) -> f64 {
+        self.frecency.get(branch).map_or(0.0, |f| {
+            (f.branch * weights.branch as f64 + f.worktree * weights.worktree as f64)
+                * self.decay_to_now(branch)
+        })
+    }
+
+    fn decay_to_now(&self, branch: &str) -> f64 {
+        decay(Utc::now().timestamp() - self.last_used(branch))
+    }
+
+    /// 合并另一份记录：次数和 worktree 统计相加，分数衰减到较晚的使用时间后相加，上次的过滤输入以本记录为准
+    fn absorb(&mut self, other: FrequencyStore) {
+        for (mine, theirs) in [
+            (&mut self.branch_counts, other.branch_counts),
+            (&mut self.worktree_counts, other.worktree_counts),
+            (&mut self.checkout_counts, other.checkout_counts),
+        ] {
+            for (branch, n) in theirs {
+                *mine.entry(branch).or_insert(0) += n;
+            }
+        }
+        for (branch, mut theirs) in other.frecency {
+            let their_time = other.last_used.get(&branch).copied().unwrap_or(0);
+            let my_time = self.last_used(&branch);
+            let latest = my_time.max(their_time);
+            theirs.scale(decay(latest - their_time));
+            let mine = self.frecency.entry(branch.clone()).or_default();
+            mine.scale(decay(latest - my_time));
+            mine.branch += theirs.branch;
+            mine.worktree += theirs.worktree;
+            mine.checkout += theirs.checkout;
+            self.last_used.insert(branch, latest);
+        }
+        let log = &mut self.worktrees;
+        log.created += other.worktrees.created;
+        log.removed += other.worktrees.removed;
+        log.open.extend(other.worktrees.open);
+        log.lifetime_secs += other.worktrees.lifetime_secs;
+        log.lifetime_samples += other.worktrees.lifetime_samples;
+        if self.last_filter.is_none() {
+            self.last_filter = other.last_filter;
+        }
+    }
+
+    pub fn last_used(&self, branch: &str) -> i64 {
+        self.last_used.get(branch).copied().unwrap_or(0)
+    }
+}
@@ -0,0 +1,1313 @@
+use anyhow::{bail, Context, Result};
+use git2::build::CheckoutBuilder;
+use git2::{
+    Branch, BranchType, Commit, Direction, ErrorCode, FetchOptions, FetchPrune, Oid, PushOptions,
+    RemoteCallbacks, Repository, Signature, StashFlags,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread::ScopedJoinHandle;
+use tracing::{debug, trace};
+
+use crate::auth::Authenticator;
+use crate::error::GitError;
+use crate::progress::{checkout_progress, clear_progress_line, fetch_progress, Spinner};
+use crate::tr;
+
+/// 未指定路径时从当前目录向上查找仓库
+pub fn open_repo(path: Option<&Path>) -> Result<Repository> {
+    let repo = match path {
+        Some(p) => {
+            Repository::discover(p).with_context(|| tr!("git.repo_not_found_at", p.display()))?
+        }
+        None => Repository::discover(".").context(tr!("git.not_in_repo"))?,
+    };
+    debug!(gitdir = %repo.path().display(), worktree = repo.is_worktree(), "opened repository");
+    Ok(repo)
+}
+
+/// 主仓库的 `.git` 目录；在 linked worktree 中 `repo.path()` 指向 `.git/worktrees/<name>`
+pub fn common_dir(repo: &Repository) -> PathBuf {
+    if repo.is_worktree() {
+        if let Ok(rel) = fs::read_to_string(repo.path().join("commondir")) {
+            return repo.path().join(rel.trim());
+        }
+    }
+    repo.path().to_path_buf()
+}
+
+/// 计算 worktree 布局时作为“仓库根目录”的路径：
+/// 在 linked worktree 中运行时取主仓库的工作区（避免 worktree 套娃），裸仓库则取仓库目录本身
+pub fn layout_root(repo: &Repository) -> Result<PathBuf> {
+    let main_repo = open_main_repo(repo)?;
+    let repo = main_repo.as_ref().unwrap_or(repo);
+
+    match repo.workdir() {
+        Some(workdir) => Ok(workdir.to_path_buf()),
+        None => Ok(repo.path().components().collect()),
+    }
+}
+
+/// 在 linked worktree 中打开主仓库，其它情况返回 `None`（直接用 `repo` 即可）
+fn open_main_repo(repo: &Repository) -> Result<Option<Repository>> {
+    if !repo.is_worktree() {
+        return Ok(None);
+    }
+    let main_repo = Repository::open(common_dir(repo)).context(tr!("git.open_main_failed"))?;
+    Ok(Some(main_repo))
+}
+
+/// `path` 是否已被主仓库的忽略规则（.gitignore、info/exclude、core.excludesFile）覆盖；
+/// 裸仓库或 `path` 不在主仓库工作区内时返回 `None`
+pub fn worktree_path_ignored(repo: &Repository, path: &Path) -> Result<Option<bool>> {
+    let main_repo = open_main_repo(repo)?;
+    let repo = main_repo.as_ref().unwrap_or(repo);
+    let Some(workdir) = repo.workdir() else {
+        return Ok(None);
+    };
+    match path.strip_prefix(workdir) {
+        Ok(rel) if !rel.as_os_str().is_empty() => Ok(Some(repo.is_path_ignored(rel)?)),
+        _ => Ok(None),
+    }
+}
+
+/// 未配置 `remote` 时使用的远程仓库
+pub const DEFAULT_REMOTE: &str = "origin";
+
+static REMOTE: OnceLock<String> = OnceLock::new();
+
+/// 指定本进程读取分支、推送、拉取时使用的远程仓库（配置项 `remote`），只有第一次调用生效
+pub fn use_remote(name: &str) {
+    let _ = REMOTE.set(name.to_string());
+}
+
+/// 当前使用的远程仓库名，未指定时为 `origin`
+pub fn remote() -> &'static str {
+    REMOTE.get().map_or(DEFAULT_REMOTE, String::as_str)
+}
+
+/// 远端分支在本地的 remote-tracking ref，如 `refs/remotes/origin/main`
+pub fn remote_ref(branch: &str) -> String {
+    format!("refs/remotes/{}/{}", remote(), branch)
+}
+
+/// 带远程仓库名的分支名，如 `origin/main`，用于提示
+pub fn remote_branch_label(branch: &str) -> String {
+    format!("{}/{}", remote(), branch)
+}
+
+/// 列出远程仓库（见 `remote`）的分支；该 remote 不存在时返回空列表，原因交给 `diagnose_empty_remote` 判断
+pub fn list_remote_branches(repo: &Repository) -> Result<Vec<String>> {
+    if repo.find_remote(remote()).is_err() {
+        return Ok(Vec::new());
+    }
+
+    let mut branches = Vec::new();
+    for item in repo.branches(Some(BranchType::Remote))? {
+        let (branch, _) = item?;
+        if let Some(name) = branch.name()? {
+            let short = name
+                .strip_prefix(remote())
+                .and_then(|rest| rest.strip_prefix('/'));
+            if let Some(short) = short {
+                if short != "HEAD" {
+                    branches.push(short.to_string());
+                }
+            }
+        }
+    }
+    Ok(branches)
+}
+
+/// 远程仓库（见 `remote`）下没有可选分支的原因
+pub enum EmptyRemote {
+    /// 仓库没有配置任何 remote
+    NoRemote,
+    /// 有其它 remote，但没有配置使用的那个
+    NoOrigin(Vec<String>),
+    /// 该 remote 没有配置 URL
+    OriginWithoutUrl,
+    /// 从未 fetch 过该 remote
+    NeverFetched,
+    /// fetch 过，但远端没有任何分支；附带当前分支名，便于建议首次推送
+    RemoteEmpty(Option<String>),
+}
+
+pub fn diagnose_empty_remote(repo: &Repository) -> EmptyRemote {
+    let remotes: Vec<String> = repo
+        .remotes()
+        .map(|names| names.iter().flatten().map(str::to_string).collect())
+        .unwrap_or_default();
+    if remotes.is_empty() {
+        return EmptyRemote::NoRemote;
+    }
+    let origin = match repo.find_remote(remote()) {
+        Ok(r) => r,
+        Err(_) => return EmptyRemote::NoOrigin(remotes),
+    };
+    if origin.url().is_none_or(str::is_empty) {
+        return EmptyRemote::OriginWithoutUrl;
+    }
+
+    let has_tracking_refs = repo
+        .references_glob(&remote_ref("*"))
+        .map(|mut refs| refs.next().is_some())
+        .unwrap_or(false);
+    // 远端为空时 fetch 不会留下任何 remote-tracking ref，只能靠 FETCH_HEAD 判断是否 fetch 过
+    if !has_tracking_refs && !common_dir(repo).join("FETCH_HEAD").exists() {
+        return EmptyRemote::NeverFetched;
+    }
+    let current = repo
+        .head()
+        .ok()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(str::to_string));
+    EmptyRemote::RemoteEmpty(current)
+}
+
+/// `default_branch` 的结果，按仓库的 `.git` 目录缓存
+static DEFAULT_BRANCH_CACHE: OnceLock<Mutex<HashMap<PathBuf, Option<String>>>> = OnceLock::new();
+
+/// 本进程是否已成功 fetch 过配置的 remote；只有这时才允许为推断主分支再连一次远端
+static REMOTE_REACHED: AtomicBool = AtomicBool::new(false);
+
+/// 推断仓库的主分支（不含 `origin/` 前缀）：依次看 origin/HEAD 的指向、远端报告的 HEAD、
+/// 本地已有的 origin/main 或 origin/master（origin 为配置的 remote）。
+/// 询问远端 HEAD 需要联网，只在本进程成功 fetch 过之后才做，dry-run 和离线时不会发起连接。
+/// 同一进程内按仓库缓存结果，fetch 后失效
+pub fn default_branch(repo: &Repository) -> Option<String> {
+    let cache = DEFAULT_BRANCH_CACHE.get_or_init(Default::default);
+    let key = common_dir(repo);
+    if let Some(hit) = cache.lock().ok().and_then(|c| c.get(&key).cloned()) {
+        return hit;
+    }
+    let result = resolve_default_branch(repo);
+    if let Ok(mut c) = cache.lock() {
+        c.insert(key, result.clone());
+    }
+    result
+}
+
+fn resolve_default_branch(repo: &Repository) -> Option<String> {
+    let symbolic = repo
+        .find_reference(&remote_ref("HEAD"))
+        .ok()
+        .and_then(|r| r.symbolic_target().map(str::to_string));
+    if let Some(name) = symbolic
+        .as_deref()
+        .and_then(|t| t.strip_prefix(remote_ref("").as_str()))
+    {
+        debug!(branch = name, "main branch from {}", remote_ref("HEAD"));
+        return Some(name.to_string());
+    }
+    if REMOTE_REACHED.load(Ordering::Relaxed) {
+        if let Some(name) = remote_reported_head(repo) {
+            debug!(branch = %name, "main branch reported by remote");
+            return Some(name);
+        }
+    }
+    let fallback = ["main", "master"]
+        .into_iter()
+        .find(|c| repo.find_reference(&remote_ref(c)).is_ok())
+        .map(str::to_string);
+    debug!(branch = ?fallback, "main branch from main / master fallback");
+    fallback
+}
+
+/// 连接远程仓库询问它的 HEAD；离线、认证失败或远端为空时返回 `None`
+fn remote_reported_head(repo: &Repository) -> Option<String> {
+    let mut remote = repo.find_remote(remote()).ok()?;
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(silent_remote_callbacks(repo)), None)
+        .ok()?;
+    let head = connection.default_branch().ok()?;
+    head.as_str()?
+        .strip_prefix("refs/heads/")
+        .map(str::to_string)
+}
+
+/// 解析 `origin/<branch>` 指向的提交；找不到时区分“从未 fetch 过”和“分支不存在”给出提示
+fn remote_branch_commit<'r>(
+    repo: &'r Repository,
+    remote_branch: &str,
+) -> Result<Commit<'r>, GitError> {
+    match repo.find_reference(&remote_ref(remote_branch)) {
+        Ok(reference) => {
+            let commit = reference.peel_to_commit().context(tr!("git.bad_commit"))?;
+            debug!(reference = %remote_ref(remote_branch), oid = %commit.id(), "resolved ref");
+            Ok(commit)
+        }
+        Err(_) => Err(GitError::RemoteRefMissing(missing_remote_branch_hint(
+            repo,
+            remote_branch,
+        ))),
+    }
+}
+
+/// 创建本地分支，同名分支已存在时返回 `BranchExists`
+fn create_branch<'r>(
+    repo: &'r Repository,
+    name: &str,
+    commit: &Commit<'r>,
+) -> Result<Branch<'r>, GitError> {
+    debug!(branch = name, oid = %commit.id(), "creating branch");
+    repo.branch(name, commit, false).map_err(|e| {
+        if e.code() == ErrorCode::Exists {
+            GitError::BranchExists(name.to_string())
+        } else {
+            GitError::Other(anyhow::Error::new(e).context(tr!("git.create_branch_failed", name)))
+        }
+    })
+}
+
+fn missing_remote_branch_hint(repo: &Repository, remote_branch: &str) -> String {
+    let ever_fetched = repo
+        .references_glob(&remote_ref("*"))
+        .map(|mut refs| refs.next().is_some())
+        .unwrap_or(false);
+
+    let mut hint = if ever_fetched {
+        tr!(
+            "git.remote_branch_stale",
+            remote_branch_label(remote_branch),
+            remote()
+        )
+    } else {
+        tr!(
+            "git.never_fetched",
+            remote_branch_label(remote_branch),
+            remote(),
+            remote()
+        )
+    };
+
+    if repo.find_branch(remote_branch, BranchType::Local).is_ok() {
+        hint.push_str(&tr!(
+            "git.local_fallback_hint",
+            remote_branch,
+            remote_branch
+        ));
+    }
+    hint
+}
+
+/// 主分支（见 `default_branch`）在远程仓库上的提交
+pub fn main_branch_commit(repo: &Repository) -> Option<Oid> {
+    let name = default_branch(repo)?;
+    repo.find_reference(&remote_ref(&name)).ok()?.target()
+}
+
+/// `oid` 是否已被主分支包含（与主分支相同或是其祖先）
+pub fn is_merged_into(repo: &Repository, main_oid: Oid, oid: Oid) -> bool {
+    oid == main_oid || repo.graph_descendant_of(main_oid, oid).unwrap_or(false)
+}
+
+/// 各远端分支是否已合并进主分支，结果与 `branches` 一一对应；
+/// 主分支自身及无法推断主分支时均为 `false`
+pub fn merged_remote_branches(repo: &Repository, branches: &[String]) -> Vec<bool> {
+    let main = default_branch(repo);
+    let main_oid = main_branch_commit(repo);
+    branches
+        .iter()
+        .map(|b| {
+            let main_oid = match main_oid {
+                Some(oid) if main.as_deref() != Some(b.as_str()) => oid,
+                _ => return false,
+            };
+            repo.find_reference(&remote_ref(b))
+                .ok()
+                .and_then(|r| r.target())
+                .is_some_and(|oid| is_merged_into(repo, main_oid, oid))
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct CommitInfo {
+    pub summary: String,
+    pub author: String,
+}
+
+pub fn last_commit(repo: &Repository, remote_branch: &str) -> Result<CommitInfo> {
+    let commit = remote_branch_commit(repo, remote_branch)?;
+
+    let author = commit.author();
+    Ok(CommitInfo {
+        summary: commit.summary().unwrap_or("").to_string(),
+        author: author.name().unwrap_or("").to_string(),
+    })
+}
+
+/// 分支数少于此值时逐个读取，开线程反而更慢
+const PARALLEL_THRESHOLD: usize = 64;
+
+/// 批量读取各远端分支的末次提交，结果与 `branches` 一一对应，读取失败的为 `None`
+pub fn last_commits(repo: &Repository, branches: &[String]) -> Vec<Option<CommitInfo>> {
+    per_branch(repo, branches, |repo, b| last_commit(repo, b).ok())
+}
+
+/// 远端分支的末次提交时间和相对主分支的领先 / 落后提交数
+#[derive(Clone, Copy)]
+pub struct BranchStats {
+    pub committed_at: i64,
+    /// 主分支自身为 `(0, 0)`，无法推断主分支时为 `None`
+    pub ahead_behind: Option<(usize, usize)>,
+}
+
+/// 批量读取各远端分支的 `BranchStats`，结果与 `branches` 一一对应，读取失败的为 `None`
+pub fn branch_stats(repo: &Repository, branches: &[String]) -> Vec<Option<BranchStats>> {
+    let main_oid = main_branch_commit(repo);
+    per_branch(repo, branches, |repo, b| {
+        let commit = remote_branch_commit(repo, b).ok()?;
+        let ahead_behind =
+            main_oid.and_then(|main| repo.graph_ahead_behind(commit.id(), main).ok());
+        Some(BranchStats {
+            committed_at: commit.time().seconds(),
+            ahead_behind,
+        })
+    })
+}
+
+/// 对每个远端分支执行 `f`，结果与 `branches` 一一对应；线程内打开仓库失败的为 `T::default()`，
+/// 工作线程 panic 时在调用线程重新抛出，不会把整段结果吞成空。
+///
+/// git2 的对象不能跨线程，分支较多时每个线程各自打开一个 Repository，
+/// 按连续区间分摊分支后再按原顺序拼接
+fn per_branch<T, F>(repo: &Repository, branches: &[String], f: F) -> Vec<T>
+where
+    T: Send + Default,
+    F: Fn(&Repository, &str) -> T + Sync,
+{
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(8);
+    if branches.len() < PARALLEL_THRESHOLD || workers < 2 {
+        return branches.iter().map(|b| f(repo, b)).collect();
+    }
+
+    let repo_path = repo.path();
+    let chunk_size = branches.len().div_ceil(workers);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<ScopedJoinHandle<Vec<T>>> = branches
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || match Repository::open(repo_path) {
+                    Ok(local) => chunk.iter().map(|b| f(&local, b)).collect(),
+                    Err(_) => chunk.iter().map(|_| T::default()).collect(),
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// 各远端分支末次提交的时间（Unix 时间戳），结果与 `branches` 一一对应，读取失败的为 0
+pub fn commit_times(repo: &Repository, branches: &[String]) -> Vec<i64> {
+    branches
+        .iter()
+        .map(|b| {
+            remote_branch_commit(repo, b)
+                .map(|c| c.time().seconds())
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+pub struct CommitLine {
+    pub short_id: String,
+    pub author: String,
+    pub time: i64,
+    pub summary: String,
+}
+
+/// 从远端分支的 tip 往回取最多 `limit` 条提交
+pub fn recent_commits(
+    repo: &Repository,
+    remote_branch: &str,
+    limit: usize,
+) -> Result<Vec<CommitLine>> {
+    let tip = remote_branch_commit(repo, remote_branch)?.id();
+    let mut walk = repo.revwalk()?;
+    walk.push(tip)?;
+
+    let mut commits = Vec::new();
+    for oid in walk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        let id = commit.id().to_string();
+        commits.push(CommitLine {
+            short_id: id[..7.min(id.len())].to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            time: commit.time().seconds(),
+            summary: commit.summary().unwrap_or("").to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+/// 基于 `origin/<remote_branch>` 创建新分支并检出，`upstream` 含义同 `create_worktree`。
+/// 检出失败时删除刚建的分支，调用方可以在处理冲突后直接重试
+pub fn create_and_checkout(
+    repo: &Repository,
+    remote_branch: &str,
+    new_name: &str,
+    upstream: Option<&str>,
+) -> Result<(), GitError> {
+    let commit = remote_branch_commit(repo, remote_branch)?;
+    check_upstream(repo, new_name, upstream)?;
+    let branch = create_branch(repo, new_name, &commit)?;
+    checkout_new_branch(repo, &commit, branch, new_name)?;
+    match upstream {
+        Some(upstream) => Ok(set_tracking(repo, new_name, upstream)?),
+        None => Ok(()),
+    }
+}
+
+/// 基于任意提交创建新分支并检出，不设置上游；检出失败时同样删除刚建的分支
+pub fn create_and_checkout_at(
+    repo: &Repository,
+    commit_oid: Oid,
+    new_name: &str,
+) -> Result<(), GitError> {
+    let commit = repo.find_commit(commit_oid)?;
+    let branch = create_branch(repo, new_name, &commit)?;
+    checkout_new_branch(repo, &commit, branch, new_name)
+}
+
+fn checkout_new_branch(
+    repo: &Repository,
+    commit: &Commit,
+    mut branch: Branch,
+    new_name: &str,
+) -> Result<(), GitError> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.progress(checkout_progress());
+    debug!(branch = new_name, oid = %commit.id(), "checking out");
+    if let Err(e) = repo.checkout_tree(commit.as_object(), Some(&mut checkout)) {
+        debug!(branch = new_name, error = %e, "checkout failed, deleting branch");
+        let _ = branch.delete();
+        if e.code() == ErrorCode::Conflict {
+            return Err(GitError::CheckoutConflict);
+        }
+        return Err(anyhow::Error::new(e)
+            .context(tr!("git.checkout_failed"))
+            .into());
+    }
+    repo.set_head(branch.get().name().context(tr!("git.bad_ref_name"))?)?;
+    Ok(())
+}
+
+/// 在 `repo` 的工作区中切换到已存在的本地分支
+pub fn checkout_local_branch(repo: &Repository, name: &str) -> Result<()> {
+    let branch = repo
+        .find_branch(name, BranchType::Local)
+        .with_context(|| tr!("git.local_branch_not_found", name))?;
+    let obj = branch.get().peel(git2::ObjectType::Commit)?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.progress(checkout_progress());
+    debug!(branch = name, oid = %obj.id(), "checking out local branch");
+    repo.checkout_tree(&obj, Some(&mut checkout))
+        .context(tr!("git.checkout_dirty"))?;
+    repo.set_head(branch.get().name().context(tr!("git.bad_ref_name"))?)?;
+    Ok(())
+}
+
+/// 基于 `origin/<remote_branch>` 创建新分支和 worktree。`upstream` 为新分支追踪的远端分支
+/// （通常就是 `remote_branch`，也可以是新分支自己的名字），为 `None` 时不写追踪配置
+pub fn create_worktree(
+    repo: &Repository,
+    remote_branch: &str,
+    new_name: &str,
+    worktree_path: &Path,
+    upstream: Option<&str>,
+) -> Result<(), GitError> {
+    let commit_oid = remote_branch_commit(repo, remote_branch)?.id();
+    check_upstream(repo, new_name, upstream)?;
+    add_worktree(repo, commit_oid, new_name, worktree_path, upstream)
+}
+
+/// 要追踪的远端分支须已存在；追踪同名远端分支时它要到首次推送才创建，不做检查
+fn check_upstream(
+    repo: &Repository,
+    new_name: &str,
+    upstream: Option<&str>,
+) -> Result<(), GitError> {
+    match upstream {
+        Some(upstream) if upstream != new_name => remote_branch_commit(repo, upstream).map(drop),
+        _ => Ok(()),
+    }
+}
+
+/// 基于任意提交创建新分支和 worktree，不设置上游
+pub fn create_worktree_at(
+    repo: &Repository,
+    commit_oid: Oid,
+    new_name: &str,
+    worktree_path: &Path,
+) -> Result<(), GitError> {
+    add_worktree(repo, commit_oid, new_name, worktree_path, None)
+}
+
+fn add_worktree(
+    repo: &Repository,
+    commit_oid: Oid,
+    new_name: &str,
+    worktree_path: &Path,
+    upstream: Option<&str>,
+) -> Result<(), GitError> {
+    {
+        let commit = repo.find_commit(commit_oid)?;
+        create_branch(repo, new_name, &commit)?;
+    }
+
+    let worktree_existed = repo.find_worktree(new_name).is_ok();
+    let result = (|| -> Result<()> {
+        let branch_ref = repo.find_reference(&format!("refs/heads/{}", new_name))?;
+        let mut opts = git2::WorktreeAddOptions::new();
+        opts.reference(Some(&branch_ref));
+        // libgit2 的 worktree add 不暴露 checkout 进度，只能显示转圈提示
+        prepare_worktree_dir(worktree_path)?;
+        debug!(name = new_name, path = %worktree_path.display(), "adding worktree");
+        let spinner = Spinner::start(tr!("git.checking_out"));
+        repo.worktree(new_name, worktree_path, Some(&opts))
+            .context(tr!("git.add_worktree_failed"))?;
+        drop(spinner);
+
+        match upstream {
+            Some(remote_branch) => set_tracking(repo, new_name, remote_branch),
+            None => Ok(()),
+        }
+    })();
+
+    result.map_err(|e| {
+        let failures = rollback_worktree(repo, new_name, worktree_existed);
+        with_rollback_failures(e, failures).into()
+    })
+}
+
+/// libgit2 只会创建 worktree 目录本身，多级布局需要先建好父目录；
+/// 它也不接受已存在的目录，事先准备好的空目录要先删掉
+fn prepare_worktree_dir(worktree_path: &Path) -> Result<()> {
+    if worktree_path.is_dir() {
+        fs::remove_dir(worktree_path)
+            .with_context(|| tr!("git.dir_not_empty", worktree_path.display()))?;
+    }
+    if let Some(parent) = worktree_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| tr!("git.create_dir_failed", parent.display()))?;
+    }
+    Ok(())
+}
+
+fn set_tracking(repo: &Repository, new_name: &str, remote_branch: &str) -> Result<()> {
+    debug!(
+        branch = new_name,
+        upstream = %remote_ref(remote_branch),
+        "writing branch.{}.remote / merge",
+        new_name
+    );
+    let mut config = repo.config()?;
+    config.set_str(&format!("branch.{}.remote", new_name), remote())?;
+    config.set_str(
+        &format!("branch.{}.merge", new_name),
+        &format!("refs/heads/{}", remote_branch),
+    )?;
+    Ok(())
+}
+
+/// 撤销 create_worktree 已完成的步骤：追踪配置、worktree（仅限本次新建的）和本地分支。
+/// 回滚本身尽力而为，返回没能撤销的步骤，由调用方附在原始错误上
+fn rollback_worktree(repo: &Repository, new_name: &str, worktree_existed: bool) -> Vec<String> {
+    debug!(
+        name = new_name,
+        worktree_existed, "rolling back worktree creation"
+    );
+    if let Ok(mut config) = repo.config() {
+        let _ = config.remove(&format!("branch.{}.remote", new_name));
+        let _ = config.remove(&format!("branch.{}.merge", new_name));
+    }
+
+    let mut failures = Vec::new();
+    if !worktree_existed {
+        discard_worktree(repo, new_name, &mut failures);
+    }
+
+    match repo.find_branch(new_name, BranchType::Local) {
+        Ok(mut branch) => {
+            if let Err(e) = branch.delete() {
+                failures.push(tr!("git.rollback_delete_branch_failed", new_name, e));
+            }
+        }
+        Err(e) => failures.push(tr!("git.rollback_branch_not_found", new_name, e)),
+    }
+    failures
+}
+
+/// 删除本次新建的 worktree 目录并清理 `.git/worktrees/<name>` 记录，失败的步骤记入 `failures`
+fn discard_worktree(repo: &Repository, name: &str, failures: &mut Vec<String>) {
+    if let Ok(wt) = repo.find_worktree(name) {
+        let _ = fs::remove_dir_all(wt.path());
+        if let Err(e) = wt.prune(None) {
+            failures.push(tr!("git.rollback_prune_failed", e));
+        }
+    }
+}
+
+/// 把回滚中没能撤销的步骤附在原始错误上，没有时原样返回
+fn with_rollback_failures(error: anyhow::Error, failures: Vec<String>) -> anyhow::Error {
+    if failures.is_empty() {
+        error
+    } else {
+        error.context(tr!("git.rollback_incomplete", failures.join("; ")))
+    }
+}
+
+/// 创建 HEAD 游离在 `rev` 所指提交上的 worktree，不留下任何本地分支
+pub fn create_detached_worktree(
+    repo: &Repository,
+    rev: &str,
+    name: &str,
+    worktree_path: &Path,
+) -> Result<()> {
+    let commit = repo
+        .revparse_single(rev)
+        .with_context(|| tr!("git.rev_not_found", rev))?
+        .peel_to_commit()
+        .context(tr!("git.bad_commit"))?;
+
+    // libgit2 创建 worktree 必须关联一个分支，这里先借用临时分支，检出后再游离 HEAD 并删除它
+    let tmp_branch = format!("gp-detached/{}", name);
+    let mut branch = repo
+        .branch(&tmp_branch, &commit, false)
+        .with_context(|| tr!("git.create_tmp_branch_failed", tmp_branch))?;
+
+    let worktree_existed = repo.find_worktree(name).is_ok();
+    let result = (|| -> Result<()> {
+        let mut opts = git2::WorktreeAddOptions::new();
+        opts.reference(Some(branch.get()));
+        prepare_worktree_dir(worktree_path)?;
+        debug!(name, rev, oid = %commit.id(), path = %worktree_path.display(), "adding detached worktree");
+        let _spinner = Spinner::start(tr!("git.checking_out"));
+        repo.worktree(name, worktree_path, Some(&opts))
+            .context(tr!("git.add_worktree_failed"))?;
+        let wt_repo =
+            Repository::open(worktree_path).context(tr!("git.open_new_worktree_failed"))?;
+        wt_repo.set_head_detached(commit.id())?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        // 失败时新 worktree 可能还检出着临时分支，先删掉 worktree 再尽力删分支，始终返回原始错误
+        let mut failures = Vec::new();
+        if !worktree_existed {
+            discard_worktree(repo, name, &mut failures);
+        }
+        if let Err(e) = branch.delete() {
+            failures.push(tr!("git.rollback_delete_branch_failed", tmp_branch, e));
+        }
+        return Err(with_rollback_failures(e, failures));
+    }
+    branch.delete().context(tr!("git.delete_tmp_branch_failed"))
+}
+
+/// 远端操作的认证回调：SSH 依次尝试 ssh-agent 和私钥文件，HTTPS 先走 git 的 credential helper，
+/// 都失败时通过 `auth::use_prompt` 注册的方式向用户索取
+pub fn remote_callbacks(repo: &Repository) -> RemoteCallbacks<'static> {
+    callbacks_with_auth(repo, true)
+}
+
+/// 同 `remote_callbacks`，但认证失败时不向用户索取凭据
+fn silent_remote_callbacks(repo: &Repository) -> RemoteCallbacks<'static> {
+    callbacks_with_auth(repo, false)
+}
+
+fn callbacks_with_auth(repo: &Repository, interactive: bool) -> RemoteCallbacks<'static> {
+    let mut auth = Authenticator::new(repo.config().ok(), interactive);
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        auth.credentials(url, username_from_url, allowed)
+    });
+    callbacks
+}
+
+/// `fetch_remote` 的结果：更新和被清理的 remote-tracking 分支数
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FetchStats {
+    pub updated: usize,
+    pub pruned: usize,
+}
+
+/// 从配置的远程仓库拉取全部分支，相当于 `git fetch <remote> --prune`，
+/// 远端已删除的分支对应的 remote-tracking ref 会被清理；TTY 下显示传输进度
+pub fn fetch_remote(repo: &Repository) -> Result<FetchStats> {
+    let mut origin = repo
+        .find_remote(remote())
+        .with_context(|| tr!("git.no_origin", remote()))?;
+
+    let mut stats = FetchStats::default();
+    {
+        let mut callbacks = remote_callbacks(repo);
+        callbacks.transfer_progress(fetch_progress());
+        callbacks.update_tips(|refname, old, new| {
+            debug!(reference = refname, %old, %new, "updated tip");
+            if new.is_zero() {
+                stats.pruned += 1;
+            } else {
+                stats.updated += 1;
+            }
+            true
+        });
+        let mut opts = FetchOptions::new();
+        opts.remote_callbacks(callbacks);
+        opts.prune(FetchPrune::On);
+        debug!(remote = remote(), "fetching with prune");
+        origin
+            .fetch::<&str>(&[], Some(&mut opts), None)
+            .with_context(|| tr!("git.fetch_from_failed", remote()))?;
+    }
+    clear_progress_line();
+    debug!(
+        updated = stats.updated,
+        pruned = stats.pruned,
+        "fetch finished"
+    );
+    REMOTE_REACHED.store(true, Ordering::Relaxed);
+    if let Some(Ok(mut cache)) = DEFAULT_BRANCH_CACHE.get().map(Mutex::lock) {
+        cache.remove(&common_dir(repo));
+    }
+    Ok(stats)
+}
+
+/// 把本地分支推送到远程仓库的同名分支，并把上游改为新推送的远端分支
+pub fn push_branch(repo: &Repository, branch: &str) -> Result<()> {
+    let mut origin = repo
+        .find_remote(remote())
+        .with_context(|| tr!("git.no_origin", remote()))?;
+
+    let mut rejection: Option<String> = None;
+    {
+        let mut callbacks = remote_callbacks(repo);
+        callbacks.push_update_reference(|_refname, status| {
+            if let Some(msg) = status {
+                rejection = Some(msg.to_string());
+            }
+            Ok(())
+        });
+        let mut opts = PushOptions::new();
+        opts.remote_callbacks(callbacks);
+
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+        debug!(remote = remote(), %refspec, "pushing");
+        origin
+            .push(&[refspec.as_str()], Some(&mut opts))
+            .with_context(|| tr!("git.push_failed", branch))?;
+    }
+    if let Some(msg) = rejection {
+        bail!("{}", tr!("git.push_rejected", msg));
+    }
+
+    debug!(
+        branch,
+        "writing branch.{}.remote / merge after push", branch
+    );
+    let mut config = repo.config()?;
+    config.set_str(&format!("branch.{}.remote", branch), remote())?;
+    config.set_str(
+        &format!("branch.{}.merge", branch),
+        &format!("refs/heads/{}", branch),
+    )?;
+    Ok(())
+}
+
+/// 推送删除 refspec（`:refs/heads/<branch>`）删除远端分支，成功后去掉本地的远端追踪分支
+pub fn delete_remote_branch(repo: &Repository, branch: &str) -> Result<()> {
+    let mut origin = repo
+        .find_remote(remote())
+        .with_context(|| tr!("git.no_origin", remote()))?;
+
+    let mut rejection: Option<String> = None;
+    {
+        let mut callbacks = remote_callbacks(repo);
+        callbacks.push_update_reference(|_refname, status| {
+            if let Some(msg) = status {
+                rejection = Some(msg.to_string());
+            }
+            Ok(())
+        });
+        let mut opts = PushOptions::new();
+        opts.remote_callbacks(callbacks);
+
+        let refspec = format!(":refs/heads/{}", branch);
+        debug!(remote = remote(), %refspec, "deleting remote branch");
+        origin
+            .push(&[refspec.as_str()], Some(&mut opts))
+            .with_context(|| tr!("git.delete_remote_failed", remote(), branch))?;
+    }
+    if let Some(msg) = rejection {
+        bail!("{}", tr!("git.push_rejected", msg));
+    }
+
+    // 推送通常已顺带更新追踪分支，这里兜底
+    if let Ok(mut tracking) = repo.find_reference(&remote_ref(branch)) {
+        debug!(branch, "deleting remote-tracking ref");
+        let _ = tracking.delete();
+    }
+    Ok(())
+}
+
+/// 本地分支及其与上游的差异
+#[derive(Debug, Clone)]
+pub struct LocalBranch {
+    pub name: String,
+    /// 上游分支名，如 `origin/main`；未设置或上游已不存在时为 `None`
+    pub upstream: Option<String>,
+    /// 相对上游领先、落后的提交数
+    pub ahead_behind: Option<(usize, usize)>,
+    /// 是否为当前工作区检出的分支
+    pub is_head: bool,
+    /// `branch.<name>.description`，见 `branch_description`
+    pub description: Option<String>,
+}
+
+/// 列出所有本地分支，按名称排序
+pub fn list_local_branches(repo: &Repository) -> Result<Vec<LocalBranch>> {
+    let head = repo
+        .head()
+        .ok()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(str::to_string));
+
+    let mut branches = Vec::new();
+    for item in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = item?;
+        let name = match branch.name()? {
+            Some(n) => n.to_string(),
+            None => continue,
+        };
+        let upstream = branch.upstream().ok();
+        let ahead_behind = upstream.as_ref().and_then(|u| {
+            let local_oid = branch.get().target()?;
+            let upstream_oid = u.get().target()?;
+            repo.graph_ahead_behind(local_oid, upstream_oid).ok()
+        });
+        branches.push(LocalBranch {
+            is_head: head.as_deref() == Some(name.as_str()),
+            description: branch_description(repo, &name),
+            upstream: upstream.and_then(|u| u.name().ok().flatten().map(str::to_string)),
+            ahead_behind,
+            name,
+        });
+    }
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(branches)
+}
+
+/// 分支的说明，即 git 配置 `branch.<name>.description`（`git branch --edit-description` 写的也是它），
+/// 只取第一个非空行；没有时为 `None`
+pub fn branch_description(repo: &Repository, branch: &str) -> Option<String> {
+    let config = repo.config().ok()?;
+    let text = config
+        .get_string(&format!("branch.{}.description", branch))
+        .ok()?;
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// 写入分支说明，`description` 为空时删除该项
+pub fn set_branch_description(repo: &Repository, branch: &str, description: &str) -> Result<()> {
+    let key = format!("branch.{}.description", branch);
+    let description = description.trim();
+    debug!(branch, description, "setting branch description");
+    let mut config = repo.config()?;
+    let result = if description.is_empty() {
+        config.remove(&key).or_else(|e| match e.code() {
+            ErrorCode::NotFound => Ok(()),
+            _ => Err(e),
+        })
+    } else {
+        config.set_str(&key, description)
+    };
+    result.with_context(|| tr!("git.write_description_failed", branch))
+}
+
+/// 上游已被删除的本地分支（`git branch -vv` 中的 `[gone]`）
+#[derive(Debug, Clone)]
+pub struct GoneBranch {
+    pub name: String,
+    /// 原来追踪的远端分支，如 `origin/feature`
+    pub upstream: String,
+    /// 分支的提交是否都已并入主分支；无法推断主分支时为 `false`
+    pub merged: bool,
+}
+
+/// 本地分支（完整 ref 名）配置了上游、但上游的 ref 已不存在时，返回上游的完整 ref 名
+pub fn gone_upstream(repo: &Repository, refname: &str) -> Option<String> {
+    // 没有配置上游时 branch_upstream_name 报错；配置了则按 refspec 算出名称，不要求 ref 存在
+    let upstream = repo
+        .branch_upstream_name(refname)
+        .ok()?
+        .as_str()?
+        .to_string();
+    match repo.find_reference(&upstream) {
+        Ok(_) => None,
+        Err(_) => {
+            trace!(reference = refname, %upstream, "upstream is gone");
+            Some(upstream)
+        }
+    }
+}
+
+/// 找出配置了上游、但上游的 remote-tracking ref 已不存在的本地分支（通常是 fetch --prune 后）
+pub fn gone_branches(repo: &Repository) -> Result<Vec<GoneBranch>> {
+    let main_oid = main_branch_commit(repo);
+    let mut gone = Vec::new();
+    for item in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = item?;
+        let refname = match branch.get().name() {
+            Some(n) => n.to_string(),
+            None => continue,
+        };
+        let upstream = match gone_upstream(repo, &refname) {
+            Some(u) => u,
+            None => continue,
+        };
+        let name = branch.name()?.unwrap_or_default().to_string();
+        let merged = match (main_oid, branch.get().target()) {
+            (Some(main_oid), Some(oid)) => is_merged_into(repo, main_oid, oid),
+            _ => false,
+        };
+        let upstream = upstream
+            .strip_prefix("refs/remotes/")
+            .or_else(|| upstream.strip_prefix("refs/heads/"))
+            .unwrap_or(&upstream)
+            .to_string();
+        gone.push(GoneBranch {
+            name,
+            upstream,
+            merged,
+        });
+    }
+    gone.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(gone)
+}
+
+/// 本地分支相对上游领先（未推送）的提交数；没有上游时返回 `None`
+pub fn unpushed_commits(repo: &Repository, branch: &str) -> Option<usize> {
+    let local = repo.find_branch(branch, BranchType::Local).ok()?;
+    let local_oid = local.get().target()?;
+    let upstream_oid = local.upstream().ok()?.get().target()?;
+    let (ahead, _behind) = repo.graph_ahead_behind(local_oid, upstream_oid).ok()?;
+    Some(ahead)
+}
+
+pub fn delete_local_branch(repo: &Repository, branch: &str) -> Result<()> {
+    debug!(branch, "deleting local branch");
+    repo.find_branch(branch, BranchType::Local)
+        .with_context(|| tr!("git.local_branch_not_found", branch))?
+        .delete()
+        .with_context(|| tr!("git.delete_branch_failed", branch))
+}
+
+/// 工作区是否有未提交的修改（含未跟踪文件，不含被忽略的文件）；无法读取状态时按有修改处理
+pub fn worktree_is_dirty(wt_repo: &Repository) -> bool {
+    let mut status_opts = git2::StatusOptions::new();
+    status_opts
+        .include_untracked(true)
+        .include_ignored(false)
+        .include_unmodified(false);
+
+    match wt_repo.statuses(Some(&mut status_opts)) {
+        Ok(s) => !s.is_empty(),
+        Err(_) => true,
+    }
+}
+
+/// 在 worktree 自己的仓库中拉取上游并快进，返回更新的提交数（0 表示已是最新）。
+/// 工作区有修改或与上游分叉时拒绝执行
+pub fn pull_fast_forward(wt_repo: &Repository) -> Result<usize> {
+    if worktree_is_dirty(wt_repo) {
+        bail!("{}", tr!("git.pull_dirty"));
+    }
+    let head = wt_repo.head().context(tr!("git.read_head_failed"))?;
+    if !head.is_branch() {
+        bail!("{}", tr!("git.pull_detached"));
+    }
+    let refname = head.name().context(tr!("git.bad_ref_name"))?.to_string();
+    let branch_name = head
+        .shorthand()
+        .context(tr!("git.bad_branch_name"))?
+        .to_string();
+
+    let remote_name = wt_repo
+        .branch_upstream_remote(&refname)
+        .with_context(|| tr!("git.no_upstream", branch_name))?;
+    let remote_name = remote_name
+        .as_str()
+        .context(tr!("git.bad_upstream_remote"))?;
+    // 上游是本地分支时（remote = "."）无需 fetch
+    if remote_name != "." {
+        let mut remote = wt_repo
+            .find_remote(remote_name)
+            .with_context(|| tr!("git.remote_not_found", remote_name))?;
+        let mut opts = FetchOptions::new();
+        opts.remote_callbacks(remote_callbacks(wt_repo));
+        let _spinner = Spinner::start(tr!("git.pulling"));
+        remote
+            .fetch::<&str>(&[], Some(&mut opts), None)
+            .with_context(|| tr!("git.fetch_from_failed", remote_name))?;
+    }
+
+    let local_oid = head.target().context(tr!("git.head_no_target"))?;
+    let upstream = wt_repo
+        .find_branch(&branch_name, BranchType::Local)?
+        .upstream()
+        .with_context(|| tr!("git.upstream_not_found", branch_name))?;
+    let upstream_oid = upstream
+        .get()
+        .target()
+        .context(tr!("git.upstream_no_target"))?;
+
+    let (ahead, behind) = wt_repo.graph_ahead_behind(local_oid, upstream_oid)?;
+    debug!(branch = %branch_name, %local_oid, %upstream_oid, ahead, behind, "pull compared with upstream");
+    if behind == 0 {
+        return Ok(0);
+    }
+    if ahead > 0 {
+        bail!("{}", tr!("git.diverged", ahead, behind));
+    }
+
+    let target = wt_repo.find_object(upstream_oid, None)?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe().progress(checkout_progress());
+    wt_repo
+        .checkout_tree(&target, Some(&mut checkout))
+        .context(tr!("git.update_worktree_failed"))?;
+    wt_repo
+        .find_reference(&refname)?
+        .set_target(upstream_oid, "gp: fast-forward pull")?;
+    Ok(behind)
+}
+
+fn signature(repo: &Repository) -> Result<Signature<'static>> {
+    repo.signature()
+        .or_else(|_| Signature::now("gp", "gp@localhost"))
+        .context(tr!("git.signature_failed"))
+}
+
+/// 切换到 `branch` 前自动暂存时的 stash 说明
+pub fn autostash_message(branch: &str) -> String {
+    format!("gp: autostash before switching to {}", branch)
+}
+
+/// 把当前工作区的未提交改动存入 stash，`message` 为 `git stash list` 中显示的说明，
+/// 返回是否真的有改动被暂存
+pub fn stash_changes(
+    repo: &mut Repository,
+    message: &str,
+    include_untracked: bool,
+) -> Result<bool> {
+    let sig = signature(repo)?;
+    let flags = if include_untracked {
+        StashFlags::INCLUDE_UNTRACKED
+    } else {
+        StashFlags::DEFAULT
+    };
+    match repo.stash_save(&sig, message, Some(flags)) {
+        Ok(oid) => {
+            debug!(%oid, message, include_untracked, "stashed changes");
+            Ok(true)
+        }
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(false),
+        Err(e) => Err(e).context(tr!("git.stash_failed")),
+    }
+}
+
+/// 在新 worktree 中应用最新的 stash（stash 引用在所有 worktree 间共享）
+pub fn apply_stash_in(worktree_path: &Path) -> Result<()> {
+    let mut wt_repo =
+        Repository::open(worktree_path).context(tr!("git.open_new_worktree_failed"))?;
+    debug!(path = %worktree_path.display(), "applying stash@{{0}}");
+    wt_repo
+        .stash_apply(0, None)
+        .context(tr!("git.stash_apply_failed"))?;
+    Ok(())
+}
+
+pub fn drop_stash(repo: &mut Repository) -> Result<()> {
+    debug!("dropping stash@{{0}}");
+    repo.stash_drop(0).context(tr!("git.stash_drop_failed"))
+}
+
+/// autostash 切换分支后，把最新的 stash 重新应用到当前工作区，返回发生冲突的文件。
+/// 与 `git stash pop` 一样，冲突写入工作区和 index，此时保留 stash，没有冲突才删除它
+pub fn reapply_stash(repo: &mut Repository) -> Result<Vec<String>> {
+    debug!("re-applying stash@{{0}}");
+    repo.stash_apply(0, None)
+        .context(tr!("git.stash_restore_failed"))?;
+    let conflicts: Vec<String> = repo
+        .index()?
+        .conflicts()?
+        .filter_map(|c| c.ok())
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect();
+    if conflicts.is_empty() {
+        drop_stash(repo)?;
+    } else {
+        debug!(?conflicts, "stash conflicts, keeping it");
+    }
+    Ok(conflicts)
+}
+
+/// 将最新的 stash 恢复回当前工作区并删除该 stash
+pub fn restore_stash(repo: &mut Repository) -> Result<()> {
+    debug!("popping stash@{{0}}");
+    repo.stash_pop(0, None)
+        .context(tr!("git.stash_restore_failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的临时仓库，离开作用域时删除目录
+    struct TempRepo {
+        dir: PathBuf,
+        repo: Repository,
+    }
+
+    impl TempRepo {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("gp-test-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            let repo = Repository::init(&dir).unwrap();
+            Self { dir, repo }
+        }
+
+        /// 提交一个空树，`update_ref` 为要指向它的 ref
+        fn commit(&self, update_ref: Option<&str>) -> Oid {
+            let sig = Signature::now("gp", "gp@example.com").unwrap();
+            let tree_id = self.repo.treebuilder(None).unwrap().write().unwrap();
+            let tree = self.repo.find_tree(tree_id).unwrap();
+            self.repo
+                .commit(update_ref, &sig, &sig, "init", &tree, &[])
+                .unwrap()
+        }
+
+        /// 让 `refs/remotes/origin/<branch>` 指向一个新提交
+        fn remote_branches(&self, branches: &[&str]) {
+            let oid = self.commit(None);
+            for b in branches {
+                self.repo
+                    .reference(&remote_ref(b), oid, true, "test")
+                    .unwrap();
+            }
+        }
+
+        fn remote_head(&self, branch: &str) {
+            self.repo
+                .reference_symbolic(&remote_ref("HEAD"), &remote_ref(branch), true, "test")
+                .unwrap();
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn many_branches() -> Vec<String> {
+        (0..PARALLEL_THRESHOLD * 2)
+            .map(|i| format!("b{}", i))
+            .collect()
+    }
+
+    #[test]
+    fn per_branch_keeps_order_and_length() {
+        let t = TempRepo::new("per-branch");
+        let branches = many_branches();
+        let result = per_branch(&t.repo, &branches, |_, b| b.to_string());
+        assert_eq!(result, branches);
+    }
+
+    #[test]
+    #[should_panic(expected = "boom at b100")]
+    fn per_branch_propagates_worker_panic() {
+        let t = TempRepo::new("per-branch-panic");
+        per_branch(&t.repo, &many_branches(), |_, b| {
+            if b == "b100" {
+                panic!("boom at {}", b);
+            }
+            b.len()
+        });
+    }
+
+    #[test]
+    fn failed_detached_worktree_keeps_the_original_error_and_cleans_up() {
+        let t = TempRepo::new("detached");
+        t.commit(Some("refs/heads/main"));
+        let taken = t.dir.with_extension("taken");
+        fs::create_dir_all(&taken).unwrap();
+        fs::write(taken.join("file"), "").unwrap();
+
+        let err = create_detached_worktree(&t.repo, "main", "wt", &taken).unwrap_err();
+        let _ = fs::remove_dir_all(&taken);
+        assert_eq!(err.to_string(), tr!("git.dir_not_empty", taken.display()));
+        assert!(t.repo.find_worktree("wt").is_err());
+        assert!(t
+            .repo
+            .find_branch("gp-detached/wt", BranchType::Local)
+            .is_err());
+    }
+
+    #[test]
+    fn default_branch_prefers_remote_head_symref() {
+        let t = TempRepo::new("symref");
+        t.remote_branches(&["main", "master", "develop"]);
+        t.remote_head("develop");
+        assert_eq!(resolve_default_branch(&t.repo).as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn default_branch_falls_back_to_main_then_master() {
+        let both = TempRepo::new("both");
+        both.remote_branches(&["master", "main"]);
+        assert_eq!(resolve_default_branch(&both.repo).as_deref(), Some("main"));
+
+        let master = TempRepo::new("master");
+        master.remote_branches(&["master", "develop"]);
+        assert_eq!(
+            resolve_default_branch(&master.repo).as_deref(),
+            Some("master")
+        );
+
+        let none = TempRepo::new("none");
+        none.remote_branches(&["develop"]);
+        assert_eq!(resolve_default_branch(&none.repo), None);
+    }
+
+    #[test]
+    fn default_branch_does_not_ask_remote_before_fetch() {
+        // 远端的 HEAD 指向 trunk；没有 fetch 过时不应连接它，只按本地的 origin/main 推断
+        let upstream = TempRepo::new("upstream");
+        upstream.commit(Some("refs/heads/trunk"));
+        upstream.repo.set_head("refs/heads/trunk").unwrap();
+        let t = TempRepo::new("local");
+        t.remote_branches(&["main"]);
+        t.repo
+            .remote(remote(), upstream.dir.to_str().unwrap())
+            .unwrap();
+        assert_eq!(resolve_default_branch(&t.repo).as_deref(), Some("main"));
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::Local;
+use unicode_width::UnicodeWidthStr;
+
+use crate::tr;
+
+/// 相对当前时间的描述，如 `3 天前`
+pub fn format_age(timestamp: i64) -> String {
+    let secs = (Local::now().timestamp() - timestamp).max(0);
+    match secs {
+        0..=59 => tr!("time.just_now").to_string(),
+        60..=3599 => tr!("time.minutes_ago", secs / 60),
+        3600..=86399 => tr!("time.hours_ago", secs / 3600),
+        _ => tr!("time.days_ago", secs / 86400),
+    }
+}
+
+/// 时长描述，如 `45 分钟`、`5.5 小时`、`2.3 天`
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        0..=3599 => tr!("duration.minutes", secs / 60),
+        3600..=86399 => tr!("duration.hours", format!("{:.1}", secs as f64 / 3600.0)),
+        _ => tr!("duration.days", format!("{:.1}", secs as f64 / 86400.0)),
+    }
+}
+
+/// 解析命令行中的时长，如 `30m`、`12h`、`14d`、`2w`，返回秒数
+pub fn parse_duration(text: &str) -> Result<i64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => bail!("{}", tr!("duration.invalid", text)),
+    };
+    let number: i64 = number
+        .parse()
+        .map_err(|_| anyhow!("{}", tr!("duration.invalid", text)))?;
+    Ok(number.saturating_mul(unit_secs))
+}
+
+/// 人类可读的字节数，如 `3.4 GB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// 按终端显示宽度（中文等全角字符占两列）在右侧补空格到 `width` 列，已超出时原样返回
+pub fn pad(text: &str, width: usize) -> String {
+    let shown = UnicodeWidthStr::width(text);
+    format!("{}{}", text, " ".repeat(width.saturating_sub(shown)))
+}
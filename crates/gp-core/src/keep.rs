@@ -0,0 +1,52 @@
+use anyhow::Result;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git::common_dir;
+
+/// 被“钉住”的 worktree 名称列表，clean 时一律跳过
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct KeepStore {
+    worktrees: BTreeSet<String>,
+}
+
+impl KeepStore {
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.worktrees.contains(name)
+    }
+
+    /// 切换钉住状态，返回切换后是否处于钉住状态
+    pub fn toggle(&mut self, name: &str) -> bool {
+        if self.worktrees.remove(name) {
+            false
+        } else {
+            self.worktrees.insert(name.to_string());
+            true
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.worktrees.remove(name);
+    }
+}
+
+/// 存放在主仓库的 `.git` 下，所有 worktree 共用同一份
+pub fn keep_path(repo: &Repository) -> PathBuf {
+    common_dir(repo).join("branch-picker-keep.json")
+}
@@ -0,0 +1,17 @@
+//! gp 的核心逻辑：远端分支枚举、创建分支 / worktree、worktree 收集与清理判定、使用频率统计。
+//!
+//! 这里的函数只返回数据，不做交互，可以直接针对临时仓库测试，也可以给其它前端复用；
+//! 唯一的输出是耗时操作期间在 stderr 上的进度提示，且只在 TTY 下出现。
+//! 中英文文案表 `messages` 也放在这里，上层 crate 通过 `tr!` 共用同一份。
+
+pub mod auth;
+pub mod backend;
+pub mod error;
+pub mod freq;
+pub mod git;
+pub mod human;
+pub mod keep;
+pub mod messages;
+pub mod meta;
+pub mod progress;
+pub mod worktree;
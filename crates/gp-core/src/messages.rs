@@ -0,0 +1,996 @@
+//! 最小的 i18n 层：按 key 取中文或英文文案，`{}` 占位符按顺序替换为参数。
+//!
+//! 语言优先级：命令行 `--lang` > 环境变量 `GP_LANG` > 配置 `lang` > `LC_ALL` / `LC_MESSAGES` / `LANG`，
+//! 都没有或为 `C` / `POSIX` 时用中文。英文缺失翻译时回退到中文，中文也没有时返回 key 本身。
+
+use serde::Deserialize;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 设置后覆盖配置和系统 locale，取值 `zh` / `en`
+pub const LANG_ENV: &str = "GP_LANG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    Zh,
+    En,
+}
+
+impl Lang {
+    /// 解析 `zh` / `en` 以及 `zh_CN.UTF-8`、`en_US` 这类 locale 写法
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if value.is_empty() || value == "c" || value == "posix" {
+            return None;
+        }
+        if value.starts_with("zh") {
+            Some(Lang::Zh)
+        } else {
+            Some(Lang::En)
+        }
+    }
+}
+
+/// 0 表示尚未确定，1 / 2 分别为中文 / 英文
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+/// 命令行 `--lang` 指定的语言，编码同 `CURRENT`
+static FORCED: AtomicU8 = AtomicU8::new(0);
+
+/// 固定使用 `lang`（命令行 `--lang`），优先于环境变量、配置和 locale
+pub fn force(lang: Lang) {
+    FORCED.store(encode(lang), Ordering::Relaxed);
+    store(lang);
+}
+
+/// 按优先级确定语言。读取配置前取过的文案按环境变量推断，读到配置后再调用一次即可修正
+pub fn init(configured: Option<Lang>) {
+    store(detect(configured));
+}
+
+pub fn current() -> Lang {
+    decode(CURRENT.load(Ordering::Relaxed)).unwrap_or_else(|| {
+        let lang = detect(None);
+        store(lang);
+        lang
+    })
+}
+
+fn store(lang: Lang) {
+    CURRENT.store(encode(lang), Ordering::Relaxed);
+}
+
+fn encode(lang: Lang) -> u8 {
+    match lang {
+        Lang::Zh => 1,
+        Lang::En => 2,
+    }
+}
+
+fn decode(value: u8) -> Option<Lang> {
+    match value {
+        1 => Some(Lang::Zh),
+        2 => Some(Lang::En),
+        _ => None,
+    }
+}
+
+fn detect(configured: Option<Lang>) -> Lang {
+    let env = |name: &str| std::env::var(name).ok().and_then(|v| Lang::parse(&v));
+    decode(FORCED.load(Ordering::Relaxed))
+        .or_else(|| env(LANG_ENV))
+        .or(configured)
+        .or_else(|| env("LC_ALL"))
+        .or_else(|| env("LC_MESSAGES"))
+        .or_else(|| env("LANG"))
+        .unwrap_or(Lang::Zh)
+}
+
+/// 当前语言下 key 对应的文案
+pub fn text(key: &'static str) -> &'static str {
+    let translated = match current() {
+        Lang::Zh => zh(key),
+        Lang::En => en(key).or_else(|| zh(key)),
+    };
+    translated.unwrap_or(key)
+}
+
+/// 把模板中的 `{}` 依次替换为参数，多余的占位符原样保留
+pub fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `tr!("key")` 返回 `&'static str`，`tr!("key", a, b)` 返回填好参数的 `String`
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::messages::text($key)
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::messages::fill($crate::messages::text($key), &[$(&$arg),+])
+    };
+}
+
+fn zh(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "common.cancelled" => "已取消。",
+        "common.warning" => "  警告：{}",
+
+        "wt.none" => "当前仓库没有任何 worktree。",
+        "local.none" => "没有可切换的本地分支（其余分支都已被 worktree 占用）。",
+        "local.dirty" => "⚠ 当前工作区有未提交的改动",
+        "local.prompt" => "切换到本地分支：",
+        "local.already_on" => "已经在分支 '{}' 上",
+        "local.dirty_refused" => "当前工作区有未提交的改动，先提交或 stash，或加 --autostash 自动暂存后再切换",
+        "local.dirty_confirm" => "⚠ 当前工作区有未提交的改动，仍然尝试切换？",
+        "local.switched" => "✓ 已切换到分支 '{}'",
+        "local.no_upstream" => "（无上游）",
+        "rm_remote.prompt" => "选择要删除的远端分支：",
+        "rm_remote.none" => "没有可以删除的远端分支（主分支和 protected_branches 中的分支受保护）。",
+        "rm_remote.protected" => "{} 受保护，不能删除（主分支或匹配配置 protected_branches）",
+        "rm_remote.not_found" => "本地没有远端分支 {} 的记录，可先 gp --fetch 更新",
+        "rm_remote.confirm" => "确定删除远端分支 {}？所有协作者都会受影响",
+        "rm_remote.deleting" => "正在删除远端分支 {} ...",
+        "rm_remote.deleted" => "✓ 已删除远端分支 {}",
+        "rm_remote.tracking_hint" => "本地分支 {} 的上游已不存在，可用 gp prune 清理",
+        "prune.none" => "没有上游已被删除的本地分支。",
+        "prune.checked_out" => "  跳过 {}：正被 worktree 检出",
+        "prune.unmerged" => " [未合并]",
+        "prune.item" => "{}  （原追踪 {}）{}",
+        "prune.prompt" => "删除以下上游已不存在的本地分支：",
+        "prune.help" => "空格勾选 / 取消  ·  → 全选  ·  ← 全不选  ·  Enter 确认  ·  Esc 取消；未合并的分支默认不勾选",
+        "prune.nothing_selected" => "未选择任何分支。",
+        "prune.deleted" => "✓ 已删除分支 {}",
+        "prune.failed" => "{} 个分支删除失败",
+        "wt.switch.none" => "没有可切换的本地分支（其余分支都已被 worktree 占用）。",
+        "wt.switch.prompt" => "切换到本地分支：",
+        "wt.switch.help" => "输入关键字过滤  ·  ↑↓ 移动  ·  Enter 确认  ·  Esc 返回",
+        "wt.switch.dirty_confirm" => "⚠ 主仓库有未提交修改，仍然尝试切换？",
+        "wt.switch.done" => "✓ 主仓库已切换到分支 '{}'",
+        "wt.select.prompt" => "选择 worktree：",
+        "wt.select.help" => "↑↓ 移动 · Enter 选择 · Esc 退出",
+        "wt.delete.confirm_dirty" => "⚠ worktree '{}' 有未提交修改，确认删除？",
+        "wt.delete.locked" => "worktree '{}' 已锁定，不会被删除；如需删除请先按 {} 解锁",
+        "wt.delete.confirm_kept" => "⚠ worktree '{}' 已钉住，确认删除？",
+        "wt.delete.confirm" => "确认删除 worktree '{}'？",
+        "wt.pre_delete_failed" => "  pre_delete 钩子失败，保留 worktree：{}",
+        "wt.delete.dir_failed" => "✗ 删除目录失败 {}：{}",
+        "wt.delete.prune_failed" => "  警告：清理 git 记录失败 {}：{}",
+        "wt.delete.done" => "✓ 已删除 worktree '{}'",
+        "wt.none_left" => "没有剩余的 worktree。",
+        "wt.keep.done" => "✓ 已钉住 worktree '{}'，gp clean 将跳过它",
+        "wt.keep.undone" => "✓ 已取消钉住 worktree '{}'",
+        "wt.open_failed" => "无法打开 worktree",
+        "wt.pull.up_to_date" => "✓ '{}' 已是最新",
+        "wt.pull.fast_forwarded" => "✓ '{}' 已快进 {} 个提交",
+        "wt.unlock.failed" => "解锁 worktree '{}' 失败",
+        "wt.unlock.done" => "✓ 已解锁 worktree '{}'",
+        "wt.lock.reason" => "锁定原因（可选，回车跳过）：",
+        "wt.lock.failed" => "锁定 worktree '{}' 失败",
+        "wt.lock.done" => "✓ 已锁定 worktree '{}'，gp clean 将跳过它",
+        "wt.multi.none" => "没有可删除的 worktree（主 worktree 不能删除）。",
+        "wt.multi.prompt" => "勾选要删除的 worktree：",
+        "wt.multi.help" => "空格勾选 / 取消  ·  → 全选  ·  ← 全不选  ·  Enter 确认  ·  Esc 取消",
+        "wt.multi.nothing_selected" => "未勾选任何 worktree。",
+        "wt.multi.dirty" => "有未提交修改",
+        "wt.multi.locked_hidden" => "已隐藏 {} 个锁定的 worktree，解锁后才能删除",
+        "wt.multi.skip_locked" => "跳过已锁定的 worktree '{}'",
+        "wt.multi.kept" => "已钉住",
+        "wt.multi.separator" => "、",
+        "wt.multi.confirm" => "确认删除以上 {} 个 worktree？",
+        "wt.multi.branches_prompt" => "勾选要一并删除的本地分支：",
+        "wt.multi.branch_unpushed" => "{}  ⚠ {} 个未推送的提交",
+        "wt.multi.branch_no_upstream" => "{}  ⚠ 没有上游",
+        "wt.delete_branch.confirm" => "是否一并删除本地分支 '{}'？",
+        "wt.delete_branch.confirm_unpushed" => "⚠ 分支 '{}' 有 {} 个未推送的提交，仍然删除？",
+        "wt.delete_branch.confirm_no_upstream" => "⚠ 分支 '{}' 没有上游，提交可能未推送，仍然删除？",
+        "wt.delete_branch.done" => "✓ 已删除本地分支 '{}'",
+
+        "status.col.branch" => "分支",
+        "status.col.state" => "状态",
+        "status.col.ahead_behind" => "领先/落后",
+        "status.col.upstream" => "追踪分支",
+        "status.col.locked" => "锁定",
+        "status.col.path" => "路径",
+
+        "clean.checking_base" => "正在检查 {} 个 worktree（仅考虑基于 '{}' 派生的）...\n",
+        "clean.checking" => "正在检查 {} 个 worktree...\n",
+        "clean.skipped" => "跳过（有改动或未推送提交）：",
+        "clean.none" => "没有可清理的 worktree。",
+        "clean.removable" => "可安全清理的 worktree：",
+        "clean.confirm" => "确认删除以上 {} 个 worktree（共 {}）？",
+        "clean.branch_deleted" => "  已删除本地分支 '{}'",
+        "clean.confirm_with_branches" => "确认删除以上 {} 个 worktree（共 {}）及其本地分支？",
+        "clean.branch_delete_failed" => "  保留本地分支 '{}'：{}",
+        "clean.branch_detached" => "  游离 HEAD，没有要删除的本地分支",
+        "clean.done_with_branches" => "\n已清理 {} 个 worktree，删除 {} 个本地分支。",
+        "clean.hook_failed" => "pre_delete 钩子执行失败",
+        "clean.some_failed" => "{} 个 worktree 未能删除",
+        "clean.dry_run" => "[dry-run] 以上 {} 个 worktree（共 {}）会被删除，本次不做改动",
+        "clean.done" => "\n已清理 {} 个 worktree。",
+
+        "temp.open_failed" => "无法打开临时 worktree",
+        "temp.kept" => "\n临时 worktree 已保留（{}）：{}",
+        "temp.kept_hint" => "  用完后可在 gp w 中删除，或执行 gp clean",
+        "temp.remove_dir_failed" => "删除目录 {} 失败",
+        "temp.removed" => "\n✓ 临时 worktree '{}' 及其分支已删除",
+
+        "config.read_failed" => "读取配置文件 {} 失败",
+        "config.parse_failed" => "解析配置文件 {} 失败",
+        "config.invalid" => "配置无效（全局 config.toml 与仓库 .gp.toml 合并后）",
+        "config.repo_key_ignored" => "⚠ 已忽略仓库 {} 中的 {}：出于安全考虑，这一项只能写在全局配置中",
+        "naming.unknown_placeholder" => "名称模板中有未知占位符 {}，可用：{}",
+        "naming.invalid" => "名称 '{}'（由模板 '{}' 生成）不是合法的分支名",
+
+        "err.branch_exists" => "分支 '{}' 已存在",
+        "err.checkout_conflict" => "切换工作区失败：未提交的修改与目标分支冲突，请先提交或暂存（git stash），或加 --autostash",
+
+        "filter.bad_glob" => "无效的 glob 模式 '{}'",
+        "filter.bad_regex" => "无效的正则表达式 '{}'",
+
+        "freq.migrate_failed" => "⚠ 迁移旧的频率文件失败，本次仍使用新位置：{}",
+        "stats.empty" => "还没有使用记录。",
+        "stats.bases" => "最常用的基点分支：",
+        "stats.base_counts" => "{} 次（分支 {} · worktree {}）",
+        "stats.worktrees" => "gp 创建了 {} 个 worktree，删除了 {} 个",
+        "stats.lifetime" => "已删除 worktree 的平均存活时长：{}",
+        "stats.lifetime_unknown" => "已删除 worktree 的平均存活时长：暂无记录",
+        "lang.invalid" => "不支持的语言 `{}`，可选 zh / en",
+        "upstream.invalid" => "不支持的上游模式 `{}`，可选 base / own / none",
+        "duration.invalid" => "无法识别的时长 `{}`，应为数字加单位 m / h / d / w，如 14d",
+        "duration.minutes" => "{} 分钟",
+        "duration.hours" => "{} 小时",
+        "duration.days" => "{} 天",
+        "freq.lock_timeout" => "等待频率文件锁超时，如确认没有其它 gp 在运行，可删除 {}",
+
+        "git.repo_not_found_at" => "在 {} 找不到 git 仓库，请检查 --repo 参数",
+        "git.not_in_repo" => "当前目录不在 git 仓库中，请进入项目目录后重试",
+        "git.open_main_failed" => "无法打开主仓库",
+        "git.bad_commit" => "无法解析提交对象",
+        "git.create_branch_failed" => "创建分支 '{}' 失败",
+        "git.remote_branch_stale" => "本地没有 '{}' 的缓存：该分支可能已在远端删除，或是上次 fetch 之后新建的；联网后执行 git fetch {} 再试",
+        "git.never_fetched" => "找不到远端分支 '{}'：当前仓库从未 fetch 过 {}，联网后执行 git fetch {} 再试",
+        "git.local_fallback_hint" => "\n提示：本地存在同名分支 '{}'，离线时可改用它作为基点：git switch -c <新分支> {}",
+        "git.checkout_failed" => "切换工作区失败",
+        "git.bad_ref_name" => "分支引用名无效",
+        "git.local_branch_not_found" => "找不到本地分支 '{}'",
+        "git.checkout_dirty" => "切换工作区失败，请先提交或暂存当前修改（git stash）",
+        "git.checking_out" => "检出中...",
+        "git.add_worktree_failed" => "创建 worktree 失败",
+        "git.dir_not_empty" => "目录 {} 不是空目录",
+        "git.create_dir_failed" => "创建目录 {} 失败",
+        "git.rollback_prune_failed" => "清理 worktree 记录失败：{}",
+        "git.rollback_delete_branch_failed" => "删除分支 '{}' 失败：{}",
+        "git.rollback_branch_not_found" => "找不到分支 '{}'：{}",
+        "git.rollback_incomplete" => "回滚未完成（{}），请手动检查",
+        "git.rev_not_found" => "找不到 '{}'，请确认 tag 或提交存在",
+        "git.create_tmp_branch_failed" => "创建临时分支 '{}' 失败",
+        "git.open_new_worktree_failed" => "无法打开新建的 worktree",
+        "git.delete_tmp_branch_failed" => "删除临时分支失败",
+        "git.auth_failed" => "认证失败，请检查 SSH 密钥或凭据配置",
+        "auth.ssh_failed" => "SSH 认证失败（已尝试 ssh-agent 和私钥：{}）。可用 ssh-add 把私钥加入 agent，或在配置 ssh_keys 中指定私钥",
+        "auth.no_key_files" => "未找到私钥文件",
+        "auth.https_failed" => "HTTPS 认证失败：{}。请配置 git credential helper，或在提示时输入用户名和 token",
+        "auth.username" => "用户名（{}）：",
+        "auth.password" => "密码 / token：",
+        "auth.passphrase" => "私钥 {} 的口令：",
+        "git.no_origin" => "未找到名为 '{}' 的远程仓库",
+        "git.push_failed" => "推送分支 '{}' 失败",
+        "git.push_rejected" => "远端拒绝了推送：{}",
+        "git.delete_branch_failed" => "删除分支 '{}' 失败",
+        "git.write_description_failed" => "写入分支 '{}' 的说明失败",
+        "git.delete_remote_failed" => "删除远端分支 {}/{} 失败",
+        "git.pull_dirty" => "工作区有未提交修改，请先提交或暂存后再 pull",
+        "git.read_head_failed" => "无法读取 HEAD",
+        "git.pull_detached" => "HEAD 处于游离状态，没有可 pull 的分支",
+        "git.bad_branch_name" => "分支名无效",
+        "git.no_upstream" => "分支 '{}' 没有设置上游",
+        "git.bad_upstream_remote" => "上游远端名无效",
+        "git.remote_not_found" => "未找到远程仓库 '{}'",
+        "git.pulling" => "拉取中...",
+        "git.fetch_from_failed" => "从 '{}' 拉取失败",
+        "git.head_no_target" => "HEAD 没有指向提交",
+        "git.upstream_not_found" => "找不到分支 '{}' 的上游",
+        "git.upstream_no_target" => "上游没有指向提交",
+        "git.diverged" => "本地与上游已分叉（领先 {}，落后 {}），无法快进，请手动 merge 或 rebase",
+        "git.update_worktree_failed" => "更新工作区失败",
+        "git.signature_failed" => "无法生成提交签名",
+        "git.stash_failed" => "暂存当前改动失败",
+        "git.stash_apply_failed" => "应用改动失败（可能存在冲突）",
+        "git.stash_drop_failed" => "删除 stash 失败",
+        "git.stash_restore_failed" => "恢复改动失败，改动仍保存在 stash@{0}，可手动执行 git stash pop",
+
+        "time.just_now" => "刚刚",
+        "time.minutes_ago" => "{} 分钟前",
+        "time.hours_ago" => "{} 小时前",
+        "time.days_ago" => "{} 天前",
+
+        "keys.unknown" => "无法识别的按键 '{}'",
+        "keys.sort_plain_char" => "按键配置错误：sort 不能绑定到普通字符 '{}'，否则无法在过滤框中输入它",
+        "keys.conflict" => "按键配置冲突：'{}' 同时绑定到了 {} 和 {}",
+
+        "layout.no_parent" => "无法获取仓库父目录",
+        "layout.no_repo_name" => "无法推断仓库名，请改用其它 worktree_layout",
+        "layout.unknown_placeholder" => "worktree_path_template 中有未知占位符 {}，可用：{}",
+        "layout.no_home" => "路径模板用到了 {home}，但没有设置 HOME 环境变量",
+        "layout.template_required" => "worktree_layout = \"custom\" 时必须配置 worktree_path_template",
+        "layout.no_cwd" => "无法获取当前目录",
+        "layout.not_a_dir" => "{} 已存在且不是目录",
+        "layout.read_dir_failed" => "读取目录 {} 失败",
+        "layout.dir_not_empty" => "目录 {} 非空，worktree 只能创建在不存在的路径或空目录中",
+        "layout.write_failed" => "写入 {} 失败",
+
+        "main.default_base_missing" => "默认基点 {} 不存在，请从列表中选择。\n",
+        "main.based_on" => "基于 {}",
+        "main.seed_copied" => "  已复制 {}",
+        "main.seed_linked" => "  已链接 {}",
+        "main.seed_failed" => "  ⚠ 复制 {} 失败：{}",
+        "main.post_create_failed" => "  worktree 已创建，但 post_create 钩子没有全部成功：{}",
+        "main.recent_commits" => "\n  {} 最近的提交：",
+        "main.bare_no_checkout" => "裸仓库没有工作区，无法切换分支，请改用创建 Worktree",
+        "main.creating_branch" => "\n正在创建分支 '{}' ...",
+        "main.switched" => "\n✓ 已切换到新分支：{}",
+        "main.tracking" => "  追踪自：{}",
+        "main.tracking_own" => "  上游：{}（首次推送时创建）",
+        "main.no_upstream" => "  基于：{}（未设置上游）",
+        "main.stash_reapplied" => "✓ 已在 {} 上恢复暂存的改动",
+        "main.stash_conflict" => "⚠ 在 {} 上恢复暂存的改动时发生冲突，冲突标记已写入以下文件：",
+        "main.stash_conflict_hint" => "  解决后执行 git add；原改动仍保存在 stash@{0}，确认无误后执行 git stash drop",
+        "main.fetching_remote" => "正在从 {} 拉取...",
+        "main.fetched" => "✓ 已更新 {} 个远端分支",
+        "main.fetched_pruned" => "✓ 已更新 {} 个远端分支，清理 {} 个远端已删除的分支",
+        "main.fetch_failed_continue" => "  继续使用本地缓存的远端分支列表",
+        "main.fetch_skipped_dry_run" => "[dry-run] 跳过从 {} 拉取",
+        "main.new_checkout_conflict" => "未提交的改动与 {} 冲突，无法切换；可加 --autostash 先暂存改动",
+        "main.worktree_name" => "Worktree 名称：",
+        "main.branch_name" => "新分支名：",
+        "main.invalid_branch_name" => "'{}' 不是合法的分支名（不能含空格、~ ^ : ? * [ \\、..，不能以 / 或 .lock 结尾等）",
+        "main.creating_worktree" => "\n正在创建 Worktree '{}'...",
+        "main.path" => "  路径：{}",
+        "main.pick_another_name" => "✗ {}，请换一个名称",
+        "main.worktree_created" => "\n✓ Worktree 已创建",
+        "main.worktree_branch" => "  分支：{}  追踪自：{}",
+        "main.worktree_branch_no_track" => "  分支：{}  基于：{}（未设置上游）",
+        "main.changes_carried" => "  已将未提交的改动搬到新 worktree，主仓库已恢复干净",
+        "main.changes_restored" => "  改动已恢复到原工作区",
+        "main.found_branches" => "找到 {} 个远端分支（按使用频率排序）\n",
+        "main.no_keyword_match" => "没有匹配 '{}' 的分支，显示全部\n",
+        "main.filter_no_match" => "没有匹配 '{}' 的远端分支。",
+        "main.pick_base" => "选择要基于的远端分支：",
+        "main.no_remotes" => "当前仓库还没有配置任何远程仓库。",
+        "main.no_remotes_hint" => "提示：先添加 {} 并拉取：",
+        "main.no_origin" => "没有名为 '{}' 的远程仓库，现有：{}",
+        "main.no_origin_hint" => "提示：gp 只读取 {}（可用配置项 remote 修改），也可以把其中一个改名为 {}：",
+        "main.origin_no_url" => "{} 没有配置 URL。",
+        "main.origin_no_url_hint" => "提示：git remote set-url {} <url>",
+        "main.never_fetched" => "还没有从 {} 拉取过任何分支。",
+        "main.never_fetched_hint" => "提示：git fetch {}",
+        "main.origin_empty" => "{} 上还没有任何分支。",
+        "main.push_current_hint" => "提示：先推送当前分支：git push -u {} {}",
+        "main.push_any_hint" => "提示：先推送一个分支：git push -u {} <分支名>",
+        "main.autostashed" => "已自动暂存当前未提交的改动",
+        "main.checkout_conflict" => "✗ 未提交的修改与 {} 冲突",
+        "main.stash_and_retry" => "是否暂存当前改动后重试？",
+        "main.stashed" => "已暂存当前未提交的改动",
+        "main.gitignore_added" => "  已将 /{}/ 加入 .gitignore",
+        "main.path_not_ignored" => "⚠ {} 位于主仓库工作区内且未被忽略，主仓库的 git status 会把它列为未跟踪目录；可把 /{}/ 加入 .gitignore",
+        "main.low_disk" => "⚠ 磁盘空间可能不足：检出约需 {}，目标分区仅剩 {}",
+        "main.continue_anyway" => "仍要继续创建？",
+        "main.branch_exists_warn" => "⚠ 本地分支 '{}' 已存在，实际执行时会失败",
+        "main.existing.checked_out" => "分支 '{}' 已在 worktree {} 中检出",
+        "main.existing.from_base" => "已有基于 {} 的 worktree：{}（{}）",
+        "main.existing.prompt" => "要怎么做？",
+        "main.existing.jump" => "进入已有的 worktree",
+        "main.existing.create" => "继续新建",
+        "main.note" => "分支说明（可选，回车跳过）：",
+        "main.push_confirm" => "是否推送到 {} 并设置上游？",
+        "main.pushing" => "\n正在推送 '{}' 到 {} ...",
+        "main.pushed" => "✓ 已推送，上游已设置为 {}",
+        "main.push_later_hint" => "  本地分支已创建，可稍后手动执行 git push -u {} {}",
+        "main.after.prompt" => "接下来：",
+        "main.after.cd" => "进入 worktree 目录（子 Shell）",
+        "main.after.tmux_window" => "在新 tmux 窗口中打开",
+        "main.after.tmux_session" => "切换到以分支命名的 tmux 会话",
+        "main.after.editor" => "在编辑器中打开（{}）",
+        "main.after.nothing" => "不用了",
+        "main.cd_confirm" => "是否切换到 worktree 目录？",
+        "main.creating_detached" => "正在基于 '{}' 创建游离 HEAD 的 Worktree...",
+        "main.detached_created" => "\n✓ Worktree 已创建（HEAD 游离于 {}）",
+        "main.fetching" => "正在从 {} 拉取 {} ...",
+        "main.worktree_branch_from" => "  分支：{}  来自：{}",
+
+        "meta.created" => "源自 {}，{}创建",
+
+        "picker.no_match" => "没有匹配的分支",
+        "picker.help" => "[输入关键字过滤  ·  ↑↓ 移动  ·  Enter 确认  ·  Esc 取消  ·  {} 排序：{}]",
+
+        "plan.stash" => "把未提交的改动存入 stash{}",
+        "plan.stash_untracked" => "（含未跟踪文件）",
+        "plan.create_branch" => "创建分支 {}，基于 {}",
+        "plan.set_upstream" => "设置追踪：branch.{}.remote = {}，branch.{}.merge = refs/heads/{}",
+        "plan.checkout" => "切换到分支 {}",
+        "plan.ignore_subdir" => "把 /.worktrees/ 写入 {}",
+        "plan.add_worktree" => "在 {} 创建 worktree '{}'",
+        "plan.apply_stash" => "在 {} 中应用 stash 并从主仓库移除",
+        "plan.restore_stash" => "在 {} 上重新应用 stash 并删除它（有冲突时保留）",
+        "plan.detach_head" => "HEAD 游离于 {}，不保留本地分支",
+        "plan.copy_file" => "复制 {} 到 {}",
+        "plan.link_file" => "以符号链接方式把 {} 放到 {}",
+        "seed.bad_pattern" => "copy_files / link_files 中的 glob 无效：{}",
+        "plan.run_hook" => "在 worktree 中执行：{}",
+        "hooks.running" => "▶ [{}] {}",
+        "hooks.spawn_failed" => "无法执行钩子命令：{}",
+        "hooks.failed" => "{} 钩子失败：`{}` 退出码 {}",
+        "hooks.killed" => "{} 钩子失败：`{}` 被信号终止",
+        "plan.prune_worktree" => "清除 worktree '{}' 的记录",
+        "plan.relink_worktree" => "重新关联 worktree '{}' 与 {}",
+        "plan.remove_worktree" => "删除 worktree {}",
+        "plan.delete_branch" => "删除本地分支 {}",
+        "plan.push" => "推送 {} 到 {}，并把上游改为 {}",
+        "plan.delete_remote_branch" => "推送 :refs/heads/{} 到 {}，删除远端分支 {}",
+        "plan.fetch" => "从 {} 拉取 {} 到 {}",
+
+        "pr.bad_source" => "无法使用拉取来源 '{}'",
+        "pr.list_unsupported" => "{} 不是 GitHub 或 GitLab 仓库，无法列出 PR / MR，请直接指定编号：gp pr <N>",
+        "pr.none_open" => "{} 没有打开的 PR / MR",
+        "pr.pick_prompt" => "选择要检出的 PR / MR：",
+        "pr.draft" => " [草稿]",
+        "pr.checkout_conflict" => "检出 '{}' 会覆盖未提交的修改，请先提交或暂存",
+        "api.bad_response" => "无法解析 {} API 的响应：{}",
+        "api.status" => "{} API 返回 {}：{}",
+        "api.hint_bad_token" => "（token 无效或已过期，请检查 {}）",
+        "api.hint_rate_limit" => "（可能触发了频率限制，请配置 token 后重试：{}）",
+        "api.hint_no_token" => "（私有仓库需要 token：{}）",
+        "api.request_failed" => "请求 {} API 失败：{}",
+        "issues.not_github" => "{} 不是 GitHub 仓库，无法读取 GitHub Issues；使用 Jira 请在配置 [issues] 中设置 provider = \"jira\"",
+        "issues.jira_url_missing" => "使用 Jira 时需要在全局配置 [issues] 中设置 jira_url",
+        "issues.jira_url_insecure" => "jira_url 必须以 https:// 开头，token 不会以明文发送：{}",
+        "issues.jira_token_missing" => "使用 Jira 时需要设置环境变量 JIRA_API_TOKEN",
+        "issues.none" => "没有分配给你的未完成工单",
+        "issues.pick_prompt" => "选择工单：",
+        "pr.template_missing_n" => "ref 模板 '{}' 中缺少 {n} 占位符",
+        "pr.unknown_host" => "无法从 {} 地址 '{}' 识别托管平台，请用 --ref-template 指定，如 --ref-template 'refs/pull/{n}/head'",
+        "pr.fetch_failed" => "拉取 '{}' 失败",
+        "pr.not_on_remote" => "远端没有 '{}'，请确认编号是否正确",
+        "pr.not_direct" => "'{}' 不是直接引用",
+
+        "progress.checking_out" => "\r  检出中 {}/{} 文件 ({}%)",
+        "progress.receiving" => "\r  接收对象 {}/{} ({}%)，{}",
+        "progress.resolving" => "\r\x1b[2K  解析增量 {}/{}",
+
+        "sort.frequency" => "使用频率",
+        "sort.name" => "名称",
+        "sort.recent" => "最近提交",
+
+        "mark.merged" => " [已合并]",
+
+        "action.prompt" => "  [{}] 创建分支  ·  [{}] 创建 Worktree  ·  [{}] 预览提交  ·  [{}] 取消：",
+
+        "repair.none" => "没有发现损坏的 worktree。",
+        "repair.locked_missing" => "跳过已锁定的 worktree '{}'：目录 {} 不存在（可能在未挂载的磁盘上），解锁后才会清除记录",
+        "repair.item.missing" => "{}  目录已不存在：{}  → 清除记录",
+        "repair.item.stale" => "{}  {} 中的 .git 文件没有指回本仓库  → 重新关联",
+        "repair.item.moved" => "{}  已被移到 {}  → 更新记录中的路径",
+        "repair.prompt" => "勾选要修复的 worktree：",
+        "repair.nothing_selected" => "未选择任何 worktree。",
+        "repair.pruned" => "✓ 已清除 worktree '{}' 的记录",
+        "repair.relinked" => "✓ 已重新关联 worktree '{}'：{}",
+        "repair.some_failed" => "{} 个 worktree 修复失败",
+        "repair.not_a_worktree" => "{} 不是 linked worktree（没有指向 git 记录的 .git 文件）",
+        "repair.unknown_worktree" => "{} 的 .git 文件指向的记录不属于本仓库",
+        "repair.prune_failed" => "清除 worktree '{}' 的记录失败",
+        "repair.relink_failed" => "重新关联 worktree '{}' 失败",
+
+        "editor.not_configured" => "没有可用的编辑器：请在配置中设置 editor（如 editor = \"code -n {path}\"），或设置 $VISUAL / $EDITOR",
+        "editor.empty" => "编辑器命令为空",
+        "editor.spawn_failed" => "无法启动编辑器 `{}`",
+        "editor.failed" => "编辑器命令 `{}` 执行失败",
+
+        "move.prompt" => "把 worktree '{}' 移到：",
+        "move.same_path" => "新路径与当前路径相同，未移动",
+        "move.done" => "✓ 已将 worktree '{}' 移到 {}",
+        "backend.worktree_not_found" => "找不到 worktree '{}'",
+        "move.not_found" => "找不到 worktree '{}'",
+        "move.locked" => "worktree '{}' 已锁定，不能移动",
+        "move.locked_hint" => "worktree '{}' 已锁定，不能移动；如需移动请先按 {} 解锁",
+        "move.failed" => "无法将 {} 移到 {}（不能跨文件系统移动）",
+        "move.relink_failed" => "更新 worktree '{}' 的 gitdir 指针失败，已把目录移回原处",
+
+        "wt_action.cd" => "[{}] 切换",
+        "wt_action.editor" => "[{}] 编辑器中打开",
+        "wt_action.switch_branch" => "[{}] 切换分支",
+        "wt_action.delete" => "[{}] 删除",
+        "wt_action.multi_delete" => "[{}] 批量删除",
+        "wt_action.pull" => "[{}] 快进拉取",
+        "wt_action.unkeep" => "取消钉住",
+        "wt_action.keep" => "钉住",
+        "wt_action.unlock" => "解锁",
+        "wt_action.lock" => "锁定",
+        "wt_action.move" => "[{}] 移动",
+        "wt_action.back" => "[{}] 返回",
+        "wt_action.prompt" => "  {}：",
+
+        "tmux.not_inside" => "不在 tmux 中，改为打开子 Shell",
+        "tmux.window_opened" => "✓ 已在 tmux 窗口 '{}' 中打开",
+        "tmux.session_opened" => "✓ 已切换到 tmux 会话 '{}'",
+        "tmux.spawn_failed" => "无法执行 tmux",
+        "tmux.failed" => "tmux 命令执行失败：tmux {}",
+        "completions.exe_failed" => "无法确定 gp 可执行文件的路径",
+        "completions.write_failed" => "无法输出补全脚本",
+        "shell.redirect_failed" => "无法把标准输出改到 stderr",
+        "shell.enter" => "\n进入 {} ...",
+        "shell.hint" => "（子 Shell 中，输入 exit 可返回原目录）\n",
+        "shell.failed" => "启动 Shell 失败",
+
+        "dry_run.header" => "\n[dry-run] 以下改动不会真正执行：",
+
+        "rmdir.cwd_inside" => "当前所在目录位于其中，请先离开该目录（如 cd 到主仓库）再删除",
+        "rmdir.readonly" => "目录中有只读文件，去掉只读属性后仍无法删除，请检查目录权限后手动删除",
+        "rmdir.permission" => "权限不足，目录中可能有属于其他用户的文件（如用 sudo 创建的），请检查权限后手动删除",
+
+        "skip.dirty" => "有未提交的修改",
+        "skip.no_head" => "无 HEAD",
+        "skip.detached_unreachable" => "HEAD 游离且提交未被任何 tag 或远端分支包含",
+        "skip.gone_unmerged" => "远端分支已删除，但未合并进主分支",
+        "skip.gone_no_main" => "远端分支已删除，且无法确定主分支",
+        "skip.no_upstream_unmerged" => "无追踪分支且未合并进主分支",
+        "skip.no_upstream_no_main" => "无追踪分支，且无法确定主分支",
+        "skip.no_upstream" => "无追踪分支",
+        "skip.ahead_behind_failed" => "无法比较分支进度",
+        "skip.unpushed" => "有未推送的提交",
+        "skip.load_failed" => "无法加载",
+        "skip.open_failed" => "无法打开仓库",
+        "skip.recent" => "最近用过（未超过 --older-than）",
+        "skip.kept" => "已钉住",
+        "skip.locked" => "已锁定",
+        _ => return None,
+    })
+}
+
+fn en(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "common.cancelled" => "Cancelled.",
+        "common.warning" => "  Warning: {}",
+
+        "wt.none" => "This repository has no worktrees.",
+        "local.none" => "No local branch to switch to (all others are checked out in worktrees).",
+        "local.dirty" => "⚠ The current worktree has uncommitted changes",
+        "local.prompt" => "Switch to local branch:",
+        "local.already_on" => "Already on branch '{}'",
+        "local.dirty_refused" => "The current worktree has uncommitted changes: commit or stash them first, or pass --autostash to stash them automatically",
+        "local.dirty_confirm" => "⚠ The current worktree has uncommitted changes. Switch anyway?",
+        "local.switched" => "✓ Switched to branch '{}'",
+        "local.no_upstream" => "(no upstream)",
+        "rm_remote.prompt" => "Pick a remote branch to delete:",
+        "rm_remote.none" => "No remote branches can be deleted (the main branch and protected_branches are protected).",
+        "rm_remote.protected" => "{} is protected and cannot be deleted (main branch or matches protected_branches in the config)",
+        "rm_remote.not_found" => "Remote branch {} is not known locally; run gp --fetch first",
+        "rm_remote.confirm" => "Delete remote branch {}? This affects every collaborator",
+        "rm_remote.deleting" => "Deleting remote branch {} ...",
+        "rm_remote.deleted" => "✓ Deleted remote branch {}",
+        "rm_remote.tracking_hint" => "The upstream of local branch {} is gone; clean it up with gp prune",
+        "prune.none" => "No local branches with a deleted upstream.",
+        "prune.checked_out" => "  Skipping {}: checked out in a worktree",
+        "prune.unmerged" => " [unmerged]",
+        "prune.item" => "{}  (tracked {}){}",
+        "prune.prompt" => "Delete these local branches whose upstream is gone:",
+        "prune.help" => "space toggle  ·  → all  ·  ← none  ·  Enter confirm  ·  Esc cancel; unmerged branches start unchecked",
+        "prune.nothing_selected" => "No branches selected.",
+        "prune.deleted" => "✓ Deleted branch {}",
+        "prune.failed" => "Failed to delete {} branches",
+        "wt.switch.none" => "No local branch to switch to (all others are checked out in worktrees).",
+        "wt.switch.prompt" => "Switch to local branch:",
+        "wt.switch.help" => "type to filter  ·  ↑↓ move  ·  Enter confirm  ·  Esc back",
+        "wt.switch.dirty_confirm" => "⚠ The main repository has uncommitted changes. Switch anyway?",
+        "wt.switch.done" => "✓ Main repository switched to branch '{}'",
+        "wt.select.prompt" => "Select a worktree:",
+        "wt.select.help" => "↑↓ move · Enter select · Esc quit",
+        "wt.delete.confirm_dirty" => "⚠ Worktree '{}' has uncommitted changes. Delete it?",
+        "wt.delete.locked" => "Worktree '{}' is locked and will not be deleted; press {} to unlock it first",
+        "wt.delete.confirm_kept" => "⚠ Worktree '{}' is pinned. Delete it?",
+        "wt.delete.confirm" => "Delete worktree '{}'?",
+        "wt.pre_delete_failed" => "  pre_delete hook failed; keeping the worktree: {}",
+        "wt.delete.dir_failed" => "✗ Failed to remove directory {}: {}",
+        "wt.delete.prune_failed" => "  Warning: failed to prune git metadata for {}: {}",
+        "wt.delete.done" => "✓ Deleted worktree '{}'",
+        "wt.none_left" => "No worktrees left.",
+        "wt.keep.done" => "✓ Pinned worktree '{}'; gp clean will skip it",
+        "wt.keep.undone" => "✓ Unpinned worktree '{}'",
+        "wt.open_failed" => "Failed to open worktree",
+        "wt.pull.up_to_date" => "✓ '{}' is up to date",
+        "wt.pull.fast_forwarded" => "✓ '{}' fast-forwarded by {} commit(s)",
+        "wt.unlock.failed" => "Failed to unlock worktree '{}'",
+        "wt.unlock.done" => "✓ Unlocked worktree '{}'",
+        "wt.lock.reason" => "Lock reason (optional, Enter to skip):",
+        "wt.lock.failed" => "Failed to lock worktree '{}'",
+        "wt.lock.done" => "✓ Locked worktree '{}'; gp clean will skip it",
+        "wt.multi.none" => "No worktrees to delete (the main worktree cannot be deleted).",
+        "wt.multi.prompt" => "Select worktrees to delete:",
+        "wt.multi.help" => "space toggle  ·  → all  ·  ← none  ·  Enter confirm  ·  Esc cancel",
+        "wt.multi.nothing_selected" => "No worktree selected.",
+        "wt.multi.dirty" => "uncommitted changes",
+        "wt.multi.locked_hidden" => "{} locked worktree(s) hidden; unlock them to delete",
+        "wt.multi.skip_locked" => "Skipping locked worktree '{}'",
+        "wt.multi.kept" => "pinned",
+        "wt.multi.separator" => ", ",
+        "wt.multi.confirm" => "Delete the {} worktree(s) above?",
+        "wt.multi.branches_prompt" => "Select local branches to delete as well:",
+        "wt.multi.branch_unpushed" => "{}  ⚠ {} unpushed commit(s)",
+        "wt.multi.branch_no_upstream" => "{}  ⚠ no upstream",
+        "wt.delete_branch.confirm" => "Also delete local branch '{}'?",
+        "wt.delete_branch.confirm_unpushed" => "⚠ Branch '{}' has {} unpushed commit(s). Delete anyway?",
+        "wt.delete_branch.confirm_no_upstream" => "⚠ Branch '{}' has no upstream and may have unpushed commits. Delete anyway?",
+        "wt.delete_branch.done" => "✓ Deleted local branch '{}'",
+
+        "status.col.branch" => "Branch",
+        "status.col.state" => "State",
+        "status.col.ahead_behind" => "Ahd/Bhd",
+        "status.col.upstream" => "Upstream",
+        "status.col.locked" => "Lock",
+        "status.col.path" => "Path",
+
+        "clean.checking_base" => "Checking {} worktree(s) (only those derived from '{}')...\n",
+        "clean.checking" => "Checking {} worktree(s)...\n",
+        "clean.skipped" => "Skipped (changes or unpushed commits):",
+        "clean.none" => "Nothing to clean.",
+        "clean.removable" => "Worktrees safe to remove:",
+        "clean.confirm" => "Delete the {} worktree(s) above ({} total)?",
+        "clean.branch_deleted" => "  Deleted local branch '{}'",
+        "clean.confirm_with_branches" => "Delete the {} worktree(s) above ({} total) and their local branches?",
+        "clean.branch_delete_failed" => "  Kept local branch '{}': {}",
+        "clean.branch_detached" => "  Detached HEAD, no local branch to delete",
+        "clean.done_with_branches" => "\nRemoved {} worktree(s) and {} local branch(es).",
+        "clean.hook_failed" => "pre_delete hook failed",
+        "clean.some_failed" => "{} worktree(s) could not be removed",
+        "clean.dry_run" => "[dry-run] The {} worktrees above ({} total) would be removed; nothing was changed",
+        "clean.done" => "\nRemoved {} worktree(s).",
+
+        "temp.open_failed" => "Failed to open the temporary worktree",
+        "temp.kept" => "\nTemporary worktree kept ({}): {}",
+        "temp.kept_hint" => "  Remove it later from gp w, or run gp clean",
+        "temp.remove_dir_failed" => "Failed to remove directory {}",
+        "temp.removed" => "\n✓ Temporary worktree '{}' and its branch were removed",
+
+        "config.read_failed" => "Failed to read config file {}",
+        "config.parse_failed" => "Failed to parse config file {}",
+        "config.invalid" => "Invalid configuration (global config.toml merged with the repository's .gp.toml)",
+        "config.repo_key_ignored" => "⚠ Ignored the repository's {} setting {}: for security it is only read from the global config",
+        "naming.unknown_placeholder" => "Unknown placeholder {} in the name template; available: {}",
+        "naming.invalid" => "Name '{}' (from template '{}') is not a valid branch name",
+
+        "err.branch_exists" => "Branch '{}' already exists",
+        "err.checkout_conflict" => "Checkout failed: uncommitted changes conflict with the target branch; commit or stash them (git stash) first, or pass --autostash",
+
+        "filter.bad_glob" => "Invalid glob pattern '{}'",
+        "filter.bad_regex" => "Invalid regular expression '{}'",
+
+        "freq.migrate_failed" => "⚠ Failed to migrate the old frequency file, using the new location anyway: {}",
+        "stats.empty" => "No usage recorded yet.",
+        "stats.bases" => "Most used base branches:",
+        "stats.base_counts" => "{} uses ({} branches · {} worktrees)",
+        "stats.worktrees" => "gp created {} worktrees and removed {}",
+        "stats.lifetime" => "Average lifetime of removed worktrees: {}",
+        "stats.lifetime_unknown" => "Average lifetime of removed worktrees: no data yet",
+        "lang.invalid" => "unsupported language `{}`: expected zh or en",
+        "upstream.invalid" => "unsupported upstream mode `{}`: expected base, own or none",
+        "duration.invalid" => "invalid duration `{}`: expected a number followed by m / h / d / w, e.g. 14d",
+        "duration.minutes" => "{} min",
+        "duration.hours" => "{} h",
+        "duration.days" => "{} days",
+        "freq.lock_timeout" => "Timed out waiting for the frequency file lock; if no other gp is running, delete {}",
+
+        "git.repo_not_found_at" => "No git repository found at {}; check the --repo argument",
+        "git.not_in_repo" => "The current directory is not inside a git repository; cd into a project and try again",
+        "git.open_main_failed" => "Failed to open the main repository",
+        "git.bad_commit" => "Failed to resolve the commit object",
+        "git.create_branch_failed" => "Failed to create branch '{}'",
+        "git.remote_branch_stale" => "No local copy of '{}': the branch may have been deleted on the remote or created after the last fetch; run git fetch {} when online and try again",
+        "git.never_fetched" => "Remote branch '{}' not found: this repository has never fetched {}; run git fetch {} when online and try again",
+        "git.local_fallback_hint" => "\nHint: a local branch '{}' exists; while offline you can base on it instead: git switch -c <new-branch> {}",
+        "git.checkout_failed" => "Checkout failed",
+        "git.bad_ref_name" => "Invalid branch reference name",
+        "git.local_branch_not_found" => "Local branch '{}' not found",
+        "git.checkout_dirty" => "Checkout failed; commit or stash your changes first (git stash)",
+        "git.checking_out" => "Checking out...",
+        "git.add_worktree_failed" => "Failed to create worktree",
+        "git.dir_not_empty" => "Directory {} is not empty",
+        "git.create_dir_failed" => "Failed to create directory {}",
+        "git.rollback_prune_failed" => "failed to prune the worktree record: {}",
+        "git.rollback_delete_branch_failed" => "failed to delete branch '{}': {}",
+        "git.rollback_branch_not_found" => "branch '{}' not found: {}",
+        "git.rollback_incomplete" => "Rollback incomplete ({}), please check manually",
+        "git.rev_not_found" => "'{}' not found; check that the tag or commit exists",
+        "git.create_tmp_branch_failed" => "Failed to create temporary branch '{}'",
+        "git.open_new_worktree_failed" => "Failed to open the new worktree",
+        "git.delete_tmp_branch_failed" => "Failed to delete the temporary branch",
+        "git.auth_failed" => "Authentication failed; check your SSH keys or credential settings",
+        "auth.ssh_failed" => "SSH authentication failed (tried ssh-agent and keys: {}). Add your key with ssh-add, or list it in the ssh_keys config",
+        "auth.no_key_files" => "no key files found",
+        "auth.https_failed" => "HTTPS authentication failed: {}. Configure a git credential helper, or enter a username and token when prompted",
+        "auth.username" => "Username ({}):",
+        "auth.password" => "Password / token:",
+        "auth.passphrase" => "Passphrase for {}:",
+        "git.no_origin" => "No remote named '{}' found",
+        "git.push_failed" => "Failed to push branch '{}'",
+        "git.push_rejected" => "The remote rejected the push: {}",
+        "git.delete_branch_failed" => "Failed to delete branch '{}'",
+        "git.write_description_failed" => "Failed to write the description of branch '{}'",
+        "git.delete_remote_failed" => "Failed to delete remote branch {}/{}",
+        "git.pull_dirty" => "The worktree has uncommitted changes; commit or stash them before pulling",
+        "git.read_head_failed" => "Failed to read HEAD",
+        "git.pull_detached" => "HEAD is detached; there is no branch to pull",
+        "git.bad_branch_name" => "Invalid branch name",
+        "git.no_upstream" => "Branch '{}' has no upstream",
+        "git.bad_upstream_remote" => "Invalid upstream remote name",
+        "git.remote_not_found" => "Remote '{}' not found",
+        "git.pulling" => "Pulling...",
+        "git.fetch_from_failed" => "Failed to fetch from '{}'",
+        "git.head_no_target" => "HEAD does not point to a commit",
+        "git.upstream_not_found" => "Upstream of branch '{}' not found",
+        "git.upstream_no_target" => "The upstream does not point to a commit",
+        "git.diverged" => "Local and upstream have diverged ({} ahead, {} behind); cannot fast-forward, merge or rebase manually",
+        "git.update_worktree_failed" => "Failed to update the worktree",
+        "git.signature_failed" => "Failed to create a commit signature",
+        "git.stash_failed" => "Failed to stash current changes",
+        "git.stash_apply_failed" => "Failed to apply changes (there may be conflicts)",
+        "git.stash_drop_failed" => "Failed to drop the stash",
+        "git.stash_restore_failed" => "Failed to restore changes; they are still in stash@{0}, run git stash pop manually",
+
+        "time.just_now" => "just now",
+        "time.minutes_ago" => "{} min ago",
+        "time.hours_ago" => "{} h ago",
+        "time.days_ago" => "{} d ago",
+
+        "keys.unknown" => "Unrecognized key '{}'",
+        "keys.sort_plain_char" => "Invalid key config: sort cannot be bound to the plain character '{}', or it could not be typed into the filter",
+        "keys.conflict" => "Key config conflict: '{}' is bound to both {} and {}",
+
+        "layout.no_parent" => "Cannot determine the repository's parent directory",
+        "layout.no_repo_name" => "Cannot infer the repository name; use another worktree_layout",
+        "layout.unknown_placeholder" => "Unknown placeholder {} in worktree_path_template; available: {}",
+        "layout.no_home" => "The path template uses {home} but the HOME environment variable is not set",
+        "layout.template_required" => "worktree_path_template is required when worktree_layout = \"custom\"",
+        "layout.no_cwd" => "Cannot determine the current directory",
+        "layout.not_a_dir" => "{} exists and is not a directory",
+        "layout.read_dir_failed" => "Failed to read directory {}",
+        "layout.dir_not_empty" => "Directory {} is not empty; a worktree can only be created at a new path or in an empty directory",
+        "layout.write_failed" => "Failed to write {}",
+
+        "main.default_base_missing" => "Default base {} does not exist; pick one from the list.\n",
+        "main.based_on" => "Based on {}",
+        "main.seed_copied" => "  Copied {}",
+        "main.seed_linked" => "  Linked {}",
+        "main.seed_failed" => "  ⚠ Failed to copy {}: {}",
+        "main.post_create_failed" => "  The worktree was created, but the post_create hooks did not all succeed: {}",
+        "main.recent_commits" => "\n  Recent commits on {}:",
+        "main.bare_no_checkout" => "A bare repository has no working tree to switch; create a worktree instead",
+        "main.creating_branch" => "\nCreating branch '{}' ...",
+        "main.switched" => "\n✓ Switched to new branch: {}",
+        "main.tracking" => "  Tracking: {}",
+        "main.tracking_own" => "  Upstream: {} (created on first push)",
+        "main.no_upstream" => "  Based on: {} (no upstream)",
+        "main.stash_reapplied" => "✓ Restored the stashed changes on {}",
+        "main.stash_conflict" => "⚠ Re-applying the stashed changes on {} caused conflicts; conflict markers were written to:",
+        "main.stash_conflict_hint" => "  Resolve them and git add; your changes are still safe in stash@{0}, run git stash drop once done",
+        "main.fetching_remote" => "Fetching from {}...",
+        "main.fetched" => "✓ Updated {} remote branches",
+        "main.fetched_pruned" => "✓ Updated {} remote branches, pruned {} deleted on the remote",
+        "main.fetch_failed_continue" => "  Continuing with the cached remote branch list",
+        "main.fetch_skipped_dry_run" => "[dry-run] Skipping fetch from {}",
+        "main.new_checkout_conflict" => "Uncommitted changes conflict with {}; add --autostash to stash them first",
+        "main.worktree_name" => "Worktree name:",
+        "main.branch_name" => "New branch name:",
+        "main.invalid_branch_name" => "'{}' is not a valid branch name (no spaces, ~ ^ : ? * [ \\ or .., must not end with / or .lock, etc.)",
+        "main.creating_worktree" => "\nCreating worktree '{}'...",
+        "main.path" => "  Path: {}",
+        "main.pick_another_name" => "✗ {}; pick another name",
+        "main.worktree_created" => "\n✓ Worktree created",
+        "main.worktree_branch" => "  Branch: {}  tracking: {}",
+        "main.worktree_branch_no_track" => "  Branch: {}  based on: {} (no upstream)",
+        "main.changes_carried" => "  Uncommitted changes moved to the new worktree; the main repository is clean again",
+        "main.changes_restored" => "  Changes restored to the original worktree",
+        "main.found_branches" => "Found {} remote branch(es) (sorted by usage)\n",
+        "main.no_keyword_match" => "No branch matches '{}'; showing all\n",
+        "main.filter_no_match" => "No remote branch matches '{}'.",
+        "main.pick_base" => "Select the remote branch to base on:",
+        "main.no_remotes" => "This repository has no remotes configured.",
+        "main.no_remotes_hint" => "Hint: add {} and fetch first:",
+        "main.no_origin" => "No remote named '{}'; existing: {}",
+        "main.no_origin_hint" => "Hint: gp only reads {} (change it with the remote config option), or rename one of them to {}:",
+        "main.origin_no_url" => "{} has no URL configured.",
+        "main.origin_no_url_hint" => "Hint: git remote set-url {} <url>",
+        "main.never_fetched" => "No branches have been fetched from {} yet.",
+        "main.never_fetched_hint" => "Hint: git fetch {}",
+        "main.origin_empty" => "{} has no branches yet.",
+        "main.push_current_hint" => "Hint: push the current branch first: git push -u {} {}",
+        "main.push_any_hint" => "Hint: push a branch first: git push -u {} <branch>",
+        "main.autostashed" => "Stashed uncommitted changes automatically",
+        "main.checkout_conflict" => "✗ Uncommitted changes conflict with {}",
+        "main.stash_and_retry" => "Stash current changes and retry?",
+        "main.stashed" => "Stashed uncommitted changes",
+        "main.gitignore_added" => "  Added /{}/ to .gitignore",
+        "main.path_not_ignored" => "⚠ {} is inside the main repository's working tree and not ignored; git status there will list it as untracked. Consider adding /{}/ to .gitignore",
+        "main.low_disk" => "⚠ Disk space may be insufficient: checkout needs about {}, only {} free on the target filesystem",
+        "main.continue_anyway" => "Create it anyway?",
+        "main.branch_exists_warn" => "⚠ Local branch '{}' already exists; the real run would fail",
+        "main.existing.checked_out" => "Branch '{}' is already checked out in worktree {}",
+        "main.existing.from_base" => "There is already a worktree based on {}: {} ({})",
+        "main.existing.prompt" => "What next?",
+        "main.existing.jump" => "Jump to the existing worktree",
+        "main.existing.create" => "Create a new one anyway",
+        "main.note" => "Branch description (optional, Enter to skip):",
+        "main.push_confirm" => "Push to {} and set upstream?",
+        "main.pushing" => "\nPushing '{}' to {} ...",
+        "main.pushed" => "✓ Pushed; upstream set to {}",
+        "main.push_later_hint" => "  The local branch was created; push it later with git push -u {} {}",
+        "main.after.prompt" => "What next?",
+        "main.after.cd" => "Enter the worktree directory (subshell)",
+        "main.after.tmux_window" => "Open in a new tmux window",
+        "main.after.tmux_session" => "Switch to a tmux session named after the branch",
+        "main.after.editor" => "Open in editor ({})",
+        "main.after.nothing" => "Nothing",
+        "main.cd_confirm" => "Switch to the worktree directory?",
+        "main.creating_detached" => "Creating a detached-HEAD worktree at '{}'...",
+        "main.detached_created" => "\n✓ Worktree created (HEAD detached at {})",
+        "main.fetching" => "Fetching from {}: {} ...",
+        "main.worktree_branch_from" => "  Branch: {}  from: {}",
+
+        "meta.created" => "from {}, created {}",
+
+        "picker.no_match" => "No matching branches",
+        "picker.help" => "[type to filter  ·  ↑↓ move  ·  Enter confirm  ·  Esc cancel  ·  {} sort: {}]",
+
+        "plan.stash" => "Stash uncommitted changes{}",
+        "plan.stash_untracked" => " (including untracked files)",
+        "plan.create_branch" => "Create branch {} from {}",
+        "plan.set_upstream" => "Set upstream: branch.{}.remote = {}, branch.{}.merge = refs/heads/{}",
+        "plan.checkout" => "Switch to branch {}",
+        "plan.ignore_subdir" => "Write /.worktrees/ to {}",
+        "plan.add_worktree" => "At {}, create worktree '{}'",
+        "plan.apply_stash" => "Apply the stash in {} and drop it from the main repository",
+        "plan.restore_stash" => "Re-apply the stash on {} and drop it (kept on conflict)",
+        "plan.detach_head" => "Detach HEAD at {} without keeping a local branch",
+        "plan.copy_file" => "Copy {} to {}",
+        "plan.link_file" => "Symlink {} at {}",
+        "seed.bad_pattern" => "Invalid glob in copy_files / link_files: {}",
+        "plan.run_hook" => "Run in the worktree: {}",
+        "hooks.running" => "▶ [{}] {}",
+        "hooks.spawn_failed" => "Failed to run hook command: {}",
+        "hooks.failed" => "{} hook failed: `{}` exited with code {}",
+        "hooks.killed" => "{} hook failed: `{}` was killed by a signal",
+        "plan.prune_worktree" => "Prune the record of worktree '{}'",
+        "plan.relink_worktree" => "Relink worktree '{}' with {}",
+        "plan.remove_worktree" => "Remove worktree {}",
+        "plan.delete_branch" => "Delete local branch {}",
+        "plan.push" => "Push {} to {} and set its upstream to {}",
+        "plan.delete_remote_branch" => "Push :refs/heads/{} to {}, deleting remote branch {}",
+        "plan.fetch" => "From {}, fetch {} into {}",
+
+        "pr.bad_source" => "Cannot fetch from '{}'",
+        "pr.list_unsupported" => "{} is not a GitHub or GitLab repository, so PRs / MRs cannot be listed; pass a number: gp pr <N>",
+        "pr.none_open" => "{} has no open PRs / MRs",
+        "pr.pick_prompt" => "Pick a PR / MR to check out:",
+        "pr.draft" => " [draft]",
+        "pr.checkout_conflict" => "Checking out '{}' would overwrite uncommitted changes; commit or stash them first",
+        "api.bad_response" => "Cannot parse the {} API response: {}",
+        "api.status" => "{} API returned {}: {}",
+        "api.hint_bad_token" => " (the token is invalid or expired; check {})",
+        "api.hint_rate_limit" => " (probably rate limited; configure a token and retry: {})",
+        "api.hint_no_token" => " (private repositories need a token: {})",
+        "api.request_failed" => "{} API request failed: {}",
+        "issues.not_github" => "{} is not a GitHub repository, so GitHub Issues cannot be read; to use Jira, set provider = \"jira\" under [issues] in the config",
+        "issues.jira_url_missing" => "Using Jira requires jira_url under [issues] in the global config",
+        "issues.jira_url_insecure" => "jira_url must start with https:// so the token is never sent in clear text: {}",
+        "issues.jira_token_missing" => "Using Jira requires the JIRA_API_TOKEN environment variable",
+        "issues.none" => "No open issues are assigned to you",
+        "issues.pick_prompt" => "Pick an issue:",
+        "pr.template_missing_n" => "The ref template '{}' lacks the {n} placeholder",
+        "pr.unknown_host" => "Cannot detect the hosting platform from {} URL '{}'; pass --ref-template, e.g. --ref-template 'refs/pull/{n}/head'",
+        "pr.fetch_failed" => "Failed to fetch '{}'",
+        "pr.not_on_remote" => "'{}' does not exist on the remote; check the number",
+        "pr.not_direct" => "'{}' is not a direct reference",
+
+        "progress.checking_out" => "\r  Checking out {}/{} files ({}%)",
+        "progress.receiving" => "\r  Receiving objects {}/{} ({}%), {}",
+        "progress.resolving" => "\r\x1b[2K  Resolving deltas {}/{}",
+
+        "sort.frequency" => "usage",
+        "sort.name" => "name",
+        "sort.recent" => "recent commits",
+
+        "mark.merged" => " [merged]",
+
+        "action.prompt" => "  [{}] create branch  ·  [{}] create worktree  ·  [{}] preview commits  ·  [{}] cancel: ",
+
+        "repair.none" => "No broken worktrees found.",
+        "repair.locked_missing" => "Skipping locked worktree '{}': directory {} is missing (maybe on an unmounted disk); unlock it to prune its record",
+        "repair.item.missing" => "{}  directory is gone: {}  → prune record",
+        "repair.item.stale" => "{}  the .git file in {} does not point back to this repository  → relink",
+        "repair.item.moved" => "{}  moved to {}  → update the recorded path",
+        "repair.prompt" => "Select the worktrees to repair:",
+        "repair.nothing_selected" => "No worktree selected.",
+        "repair.pruned" => "✓ Pruned the record of worktree '{}'",
+        "repair.relinked" => "✓ Relinked worktree '{}': {}",
+        "repair.some_failed" => "{} worktree(s) could not be repaired",
+        "repair.not_a_worktree" => "{} is not a linked worktree (no .git file pointing at git metadata)",
+        "repair.unknown_worktree" => "The .git file in {} points at a record that does not belong to this repository",
+        "repair.prune_failed" => "Failed to prune the record of worktree '{}'",
+        "repair.relink_failed" => "Failed to relink worktree '{}'",
+
+        "editor.not_configured" => "No editor available: set editor in the config (e.g. editor = \"code -n {path}\"), or set $VISUAL / $EDITOR",
+        "editor.empty" => "The editor command is empty",
+        "editor.spawn_failed" => "Failed to start editor `{}`",
+        "editor.failed" => "Editor command `{}` failed",
+
+        "move.prompt" => "Move worktree '{}' to:",
+        "move.same_path" => "The new path is the same as the current one; nothing moved",
+        "move.done" => "✓ Moved worktree '{}' to {}",
+        "backend.worktree_not_found" => "Worktree '{}' not found",
+        "move.not_found" => "Worktree '{}' not found",
+        "move.locked" => "Worktree '{}' is locked and cannot be moved",
+        "move.locked_hint" => "Worktree '{}' is locked and cannot be moved; press {} to unlock it first",
+        "move.failed" => "Failed to move {} to {} (moving across filesystems is not supported)",
+        "move.relink_failed" => "Failed to update the gitdir pointers of worktree '{}'; the directory was moved back",
+
+        "wt_action.cd" => "[{}] cd",
+        "wt_action.editor" => "[{}] open in editor",
+        "wt_action.switch_branch" => "[{}] switch branch",
+        "wt_action.delete" => "[{}] delete",
+        "wt_action.multi_delete" => "[{}] delete several",
+        "wt_action.pull" => "[{}] pull",
+        "wt_action.unkeep" => "unpin",
+        "wt_action.keep" => "pin",
+        "wt_action.unlock" => "unlock",
+        "wt_action.lock" => "lock",
+        "wt_action.move" => "[{}] move",
+        "wt_action.back" => "[{}] back",
+        "wt_action.prompt" => "  {}: ",
+
+        "tmux.not_inside" => "Not inside tmux; opening a subshell instead",
+        "tmux.window_opened" => "✓ Opened in tmux window '{}'",
+        "tmux.session_opened" => "✓ Switched to tmux session '{}'",
+        "tmux.spawn_failed" => "Failed to run tmux",
+        "tmux.failed" => "tmux command failed: tmux {}",
+        "completions.exe_failed" => "Failed to locate the gp executable",
+        "completions.write_failed" => "Failed to write the completion script",
+        "shell.redirect_failed" => "Failed to redirect stdout to stderr",
+        "shell.enter" => "\nEntering {} ...",
+        "shell.hint" => "(in a subshell; type exit to return)\n",
+        "shell.failed" => "Failed to start the shell",
+
+        "dry_run.header" => "\n[dry-run] The following changes will NOT be made:",
+
+        "rmdir.cwd_inside" => "your current directory is inside it; leave it first (e.g. cd to the main repository) and retry",
+        "rmdir.readonly" => "it contains read-only files and is still not removable after clearing the read-only flag; check the directory permissions and remove it manually",
+        "rmdir.permission" => "permission denied; it may contain files owned by another user (e.g. created with sudo); check the permissions and remove it manually",
+
+        "skip.dirty" => "uncommitted changes",
+        "skip.no_head" => "no HEAD",
+        "skip.detached_unreachable" => "detached HEAD not contained in any tag or remote branch",
+        "skip.gone_unmerged" => "remote branch deleted but not merged into the main branch",
+        "skip.gone_no_main" => "remote branch deleted and the main branch cannot be determined",
+        "skip.no_upstream_unmerged" => "no upstream and not merged into the main branch",
+        "skip.no_upstream_no_main" => "no upstream and the main branch cannot be determined",
+        "skip.no_upstream" => "no upstream",
+        "skip.ahead_behind_failed" => "cannot compare with upstream",
+        "skip.unpushed" => "unpushed commits",
+        "skip.load_failed" => "failed to load",
+        "skip.open_failed" => "cannot open repository",
+        "skip.recent" => "used recently (within --older-than)",
+        "skip.kept" => "pinned",
+        "skip.locked" => "locked",
+        _ => return None,
+    })
+}
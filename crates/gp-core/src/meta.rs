@@ -0,0 +1,95 @@
+use anyhow::Result;
+use chrono::Utc;
+use git2::{BranchType, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git::{common_dir, remote_branch_label};
+use crate::human::format_age;
+use crate::tr;
+
+/// 用 gp 创建的分支的来历
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchMeta {
+    /// 基点远端分支（不含 `origin/` 前缀）
+    pub base: String,
+    /// 创建时间，Unix 时间戳（秒）
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BranchMetaStore {
+    branches: BTreeMap<String, BranchMeta>,
+}
+
+impl BranchMetaStore {
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, branch: &str) -> Option<&BranchMeta> {
+        self.branches.get(branch)
+    }
+
+    pub fn insert(&mut self, branch: &str, base: &str, note: Option<String>) {
+        self.branches.insert(
+            branch.to_string(),
+            BranchMeta {
+                base: base.to_string(),
+                created_at: Utc::now().timestamp(),
+                note,
+            },
+        );
+    }
+
+    /// 去掉本地分支已不存在的记录，返回是否有改动
+    pub fn prune(&mut self, repo: &Repository) -> bool {
+        let before = self.branches.len();
+        self.branches
+            .retain(|name, _| repo.find_branch(name, BranchType::Local).is_ok());
+        self.branches.len() != before
+    }
+}
+
+impl BranchMeta {
+    /// 如 `源自 origin/main，3 天前创建 · 修复登录`
+    pub fn describe(&self) -> String {
+        let mut s = tr!(
+            "meta.created",
+            remote_branch_label(&self.base),
+            format_age(self.created_at)
+        );
+        if let Some(note) = &self.note {
+            s.push_str(" · ");
+            s.push_str(note);
+        }
+        s
+    }
+}
+
+pub fn meta_path(repo: &Repository) -> PathBuf {
+    common_dir(repo).join("gp-branches.json")
+}
+
+/// 读取记录并顺带清理已删除分支的条目
+pub fn load_pruned(repo: &Repository) -> BranchMetaStore {
+    let path = meta_path(repo);
+    let mut store = BranchMetaStore::load(&path);
+    if store.prune(repo) {
+        let _ = store.save(&path);
+    }
+    store
+}
@@ -0,0 +1,120 @@
+use git2::Progress;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::human::format_size;
+use crate::tr;
+
+/// checkout 进度回调：在 stderr 上原地刷新“检出中 x/y 文件”，非 TTY 时不输出
+pub fn checkout_progress() -> impl FnMut(Option<&Path>, usize, usize) {
+    let enabled = io::stderr().is_terminal();
+    let mut last_percent = None;
+    move |_path, cur, total| {
+        if !enabled || total == 0 {
+            return;
+        }
+        let percent = cur * 100 / total;
+        if last_percent == Some(percent) {
+            return;
+        }
+        last_percent = Some(percent);
+        eprint!("{}", tr!("progress.checking_out", cur, total, percent));
+        if cur == total {
+            eprintln!();
+        }
+    }
+}
+
+/// fetch 进度回调：在 stderr 上原地刷新接收对象和解析增量的进度，非 TTY 时不输出
+pub fn fetch_progress() -> impl FnMut(Progress<'_>) -> bool {
+    let enabled = io::stderr().is_terminal();
+    let mut last = None;
+    move |stats| {
+        if !enabled || stats.total_objects() == 0 {
+            return true;
+        }
+        let state = (
+            stats.received_objects(),
+            stats.indexed_deltas(),
+            stats.received_bytes() >> 16,
+        );
+        if last == Some(state) {
+            return true;
+        }
+        last = Some(state);
+        if stats.received_objects() < stats.total_objects() {
+            eprint!(
+                "{}",
+                tr!(
+                    "progress.receiving",
+                    stats.received_objects(),
+                    stats.total_objects(),
+                    stats.received_objects() * 100 / stats.total_objects(),
+                    format_size(stats.received_bytes() as u64)
+                )
+            );
+        } else if stats.total_deltas() > 0 {
+            eprint!(
+                "{}",
+                tr!(
+                    "progress.resolving",
+                    stats.indexed_deltas(),
+                    stats.total_deltas()
+                )
+            );
+        }
+        true
+    }
+}
+
+/// 擦掉进度回调留在 stderr 当前行的内容，非 TTY 时不输出
+pub fn clear_progress_line() {
+    if io::stderr().is_terminal() {
+        eprint!("\r\x1b[2K");
+    }
+}
+
+/// 无法获取具体进度的耗时操作期间显示的转圈提示，非 TTY 时不输出
+pub struct Spinner {
+    done: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    pub fn start(message: &str) -> Self {
+        let done = Arc::new(AtomicBool::new(false));
+        if !io::stderr().is_terminal() {
+            return Self { done, handle: None };
+        }
+
+        let message = message.to_string();
+        let flag = Arc::clone(&done);
+        let handle = thread::spawn(move || {
+            const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+            let mut i = 0;
+            while !flag.load(Ordering::Relaxed) {
+                eprint!("\r  {} {}", FRAMES[i % FRAMES.len()], message);
+                i += 1;
+                thread::sleep(Duration::from_millis(80));
+            }
+            eprint!("\r\x1b[2K");
+        });
+        Self {
+            done,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
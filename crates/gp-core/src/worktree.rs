@@ -0,0 +1,925 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use git2::{BranchType, Repository, Worktree, WorktreeLockStatus};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{debug, trace};
+
+use crate::backend::{GitBackend, Head, Upstream};
+use crate::git::{
+    branch_description, common_dir, default_branch, is_merged_into, main_branch_commit,
+    remote_branch_label, worktree_is_dirty,
+};
+use crate::keep::{keep_path, KeepStore};
+use crate::meta::{load_pruned, BranchMeta};
+use crate::tr;
+
+pub struct WorktreeEntry {
+    pub name: String,
+    pub branch: String,
+    pub path: PathBuf,
+    pub is_main: bool,
+    pub kept: bool,
+    /// 是否被 `git worktree lock` 锁定
+    pub locked: bool,
+    /// 锁定时填写的原因
+    pub lock_reason: Option<String>,
+    /// 分支是否已合并进主分支，只有调用 `mark_merged` 后才会计算
+    pub merged: bool,
+    pub meta: Option<BranchMeta>,
+    /// 分支说明，见 `git::branch_description`
+    pub description: Option<String>,
+}
+
+impl fmt::Display for WorktreeEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = if self.kept { "📍" } else { "  " };
+        write!(f, "{} {:<30} {}", mark, self.branch, self.path.display())?;
+        if self.locked {
+            write!(f, " 🔒")?;
+            if let Some(reason) = &self.lock_reason {
+                write!(f, " {}", reason)?;
+            }
+        }
+        if self.merged {
+            write!(f, "{}", tr!("mark.merged"))?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, "  📝 {}", description)?;
+        }
+        if let Some(meta) = &self.meta {
+            write!(f, "  ({})", meta.describe())?;
+        }
+        Ok(())
+    }
+}
+
+/// `git worktree lock` 的状态：未锁定为 `None`，锁定时为填写的原因（没有填写时为空字符串）
+pub fn lock_state(wt: &Worktree) -> Option<String> {
+    match wt.is_locked() {
+        Ok(WorktreeLockStatus::Locked(reason)) => {
+            Some(reason.unwrap_or_default().trim().to_string())
+        }
+        _ => None,
+    }
+}
+
+pub fn gather_worktrees(repo: &Repository) -> Result<Vec<WorktreeEntry>> {
+    let mut entries = Vec::new();
+    let keep = KeepStore::load(&keep_path(repo));
+    let metas = load_pruned(repo);
+
+    if let Some(workdir) = repo.workdir() {
+        let branch = repo
+            .head()
+            .ok()
+            .and_then(|h| h.shorthand().map(|s| s.to_string()))
+            .unwrap_or_else(|| "(detached)".to_string());
+        let meta = metas.get(&branch).cloned();
+        let description = branch_description(repo, &branch);
+        entries.push(WorktreeEntry {
+            name: "(main)".to_string(),
+            branch,
+            path: workdir.to_path_buf(),
+            is_main: true,
+            kept: false,
+            locked: false,
+            lock_reason: None,
+            merged: false,
+            meta,
+            description,
+        });
+    }
+
+    let wt_names = repo.worktrees()?;
+    for name_opt in wt_names.iter() {
+        let name = match name_opt {
+            Some(n) => n,
+            None => continue,
+        };
+        let wt = match repo.find_worktree(name) {
+            Ok(w) => w,
+            Err(_) => continue,
+        };
+        let wt_path = wt.path().to_path_buf();
+        let branch = if wt.validate().is_ok() {
+            linked_head_shorthand(repo, name).unwrap_or_else(|| "(detached)".to_string())
+        } else {
+            "(unknown)".to_string()
+        };
+        let meta = metas.get(&branch).cloned();
+        let description = branch_description(repo, &branch);
+        let lock = lock_state(&wt);
+        entries.push(WorktreeEntry {
+            name: name.to_string(),
+            branch,
+            path: wt_path,
+            is_main: false,
+            kept: keep.contains(name),
+            locked: lock.is_some(),
+            lock_reason: lock.filter(|r| !r.is_empty()),
+            merged: false,
+            meta,
+            description,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// 标记各 worktree 的本地分支是否已合并进主分支（主分支自身和游离 HEAD 不标记）。
+/// 要逐个做祖先判断，只在需要时调用
+pub fn mark_merged(repo: &Repository, entries: &mut [WorktreeEntry]) {
+    let main = default_branch(repo);
+    let main_oid = match main_branch_commit(repo) {
+        Some(oid) => oid,
+        None => return,
+    };
+    for entry in entries {
+        if main.as_deref() == Some(entry.branch.as_str()) {
+            continue;
+        }
+        entry.merged = repo
+            .find_branch(&entry.branch, BranchType::Local)
+            .ok()
+            .and_then(|b| b.get().target())
+            .is_some_and(|oid| is_merged_into(repo, main_oid, oid));
+    }
+}
+
+/// 直接读取 `<common dir>/worktrees/<name>/HEAD` 得到 linked worktree 的 HEAD 简称，
+/// 省去为每个 worktree 打开一次仓库。与 `Repository::head().shorthand()` 保持一致：
+/// 游离 HEAD 为 `HEAD`，分支尚无提交时返回 `None`
+fn linked_head_shorthand(repo: &Repository, name: &str) -> Option<String> {
+    let head_file = common_dir(repo).join("worktrees").join(name).join("HEAD");
+    let content = fs::read_to_string(head_file).ok()?;
+    let content = content.trim();
+    match content.strip_prefix("ref:") {
+        Some(target) => {
+            let reference = repo.find_reference(target.trim()).ok()?;
+            reference.shorthand().map(|s| s.to_string())
+        }
+        None => git2::Oid::from_str(content)
+            .ok()
+            .map(|_| "HEAD".to_string()),
+    }
+}
+
+#[derive(Serialize)]
+pub struct WorktreeStatus {
+    pub name: String,
+    pub branch: String,
+    pub path: PathBuf,
+    pub is_main: bool,
+    pub dirty: bool,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    /// 是否被钉住（`gp clean` 跳过）
+    pub kept: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<BranchMeta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+fn upstream_progress(wt_repo: &Repository) -> Option<(String, usize, usize)> {
+    let head = wt_repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let local_oid = head.target()?;
+    let branch = wt_repo
+        .find_branch(head.shorthand()?, BranchType::Local)
+        .ok()?;
+    let upstream = branch.upstream().ok()?;
+    let upstream_name = upstream.name().ok()??.to_string();
+    let upstream_oid = upstream.get().target()?;
+    let (ahead, behind) = wt_repo.graph_ahead_behind(local_oid, upstream_oid).ok()?;
+    Some((upstream_name, ahead, behind))
+}
+
+pub fn gather_status(repo: &Repository) -> Result<Vec<WorktreeStatus>> {
+    let entries = gather_worktrees(repo)?;
+    let mut statuses = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let wt_repo = Repository::open(&entry.path).ok();
+        let dirty = wt_repo.as_ref().map(worktree_is_dirty).unwrap_or(true);
+        let (upstream, ahead, behind) = match wt_repo.as_ref().and_then(upstream_progress) {
+            Some((name, ahead, behind)) => (Some(name), ahead, behind),
+            None => (None, 0, 0),
+        };
+        statuses.push(WorktreeStatus {
+            name: entry.name,
+            branch: entry.branch,
+            path: entry.path,
+            is_main: entry.is_main,
+            dirty,
+            upstream,
+            ahead,
+            behind,
+            locked: entry.locked,
+            lock_reason: entry.lock_reason,
+            kept: entry.kept,
+            created: entry.meta,
+            description: entry.description,
+        });
+    }
+
+    Ok(statuses)
+}
+
+/// 所有 worktree（含主 worktree）当前检出的分支
+pub fn checked_out_branches(repo: &Repository) -> Result<Vec<String>> {
+    Ok(gather_worktrees(repo)?
+        .into_iter()
+        .map(|e| e.branch)
+        .collect())
+}
+
+/// 创建 `branch` 之前查找已有的 worktree：检出了 `branch` 的 worktree（含主工作区）优先，
+/// 其次是由 `base` 派生（见 `derives_from_base`）的 linked worktree。当前所在的工作区不计在内
+pub fn existing_worktree_for(
+    repo: &Repository,
+    branch: &str,
+    base: &str,
+) -> Result<Option<WorktreeEntry>> {
+    let current = repo.workdir().and_then(|p| p.canonicalize().ok());
+    let (checked_out, others): (Vec<WorktreeEntry>, Vec<WorktreeEntry>) = gather_worktrees(repo)?
+        .into_iter()
+        .filter(|e| current.is_none() || e.path.canonicalize().ok() != current)
+        .partition(|e| e.branch == branch);
+    if let Some(entry) = checked_out.into_iter().next() {
+        debug!(branch, path = %entry.path.display(), "branch already checked out");
+        return Ok(Some(entry));
+    }
+    Ok(others
+        .into_iter()
+        .filter(|e| !e.is_main)
+        .find(|e| Repository::open(&e.path).is_ok_and(|wt| derives_from_base(&wt, base))))
+}
+
+/// 提交是否已被某个 tag 或远端追踪分支包含
+pub(crate) fn commit_is_published(repo: &Repository, oid: git2::Oid) -> bool {
+    let refs = match repo.references() {
+        Ok(r) => r,
+        Err(_) => return false,
+    };
+    refs.flatten()
+        .filter(|r| {
+            r.name()
+                .is_some_and(|n| n.starts_with("refs/tags/") || n.starts_with("refs/remotes/"))
+        })
+        .filter_map(|r| r.peel_to_commit().ok())
+        .any(|c| c.id() == oid || repo.graph_descendant_of(c.id(), oid).unwrap_or(false))
+}
+
+/// 分支名形如 `<base>-...`，或追踪分支为 `origin/<base>`
+pub fn derives_from_base(wt: &impl GitBackend, base: &str) -> bool {
+    let Some(Head::Branch { name, .. }) = wt.head() else {
+        return false;
+    };
+    if name.starts_with(&format!("{}-", base)) {
+        return true;
+    }
+    matches!(wt.upstream(&name), Upstream::Tracking { name, .. } if name == remote_branch_label(base))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorktreeCleanStatus {
+    Removable,
+    Skip(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CleanOptions {
+    /// 没有追踪分支时，HEAD 已被主分支包含也视为可清理
+    pub include_untracked_merged: bool,
+    /// 删除 worktree 后一并删除其本地分支
+    pub delete_branches: bool,
+    /// 只清理这么多秒内没有用过的 worktree，见 `last_used`
+    pub older_than: Option<i64>,
+}
+
+/// 判定一个已打开的 worktree 仓库能否被安全删除：工作区干净，且所有提交都已推送到追踪分支
+/// （游离 HEAD 则要求提交已被 tag 或远端分支包含）。只读取仓库状态，不做任何输出
+pub fn clean_status(wt: &impl GitBackend, opts: CleanOptions) -> WorktreeCleanStatus {
+    use WorktreeCleanStatus::{Removable, Skip};
+
+    if wt.is_dirty() {
+        return Skip(tr!("skip.dirty"));
+    }
+
+    let (branch_name, local_oid) = match wt.head() {
+        Some(Head::Branch { name, oid }) => (name, oid),
+        // 游离 HEAD（如基于 tag 的只读 worktree）只要提交已被 tag 或远端分支包含就不会丢失
+        Some(Head::Detached(oid)) if wt.is_published(oid) => return Removable,
+        Some(Head::Detached(_)) => return Skip(tr!("skip.detached_unreachable")),
+        None => return Skip(tr!("skip.no_head")),
+    };
+
+    let (upstream_name, upstream_oid) = match wt.upstream(&branch_name) {
+        Upstream::Tracking { name, oid } => (name, oid),
+        // 上游已被删除（[gone]），通常是 PR 合并后删了远端分支：已并入主分支即可清理
+        Upstream::Gone => {
+            let main_oid = wt.main_commit();
+            trace!(branch = %branch_name, main = ?main_oid, "upstream gone, checking merge into main");
+            return match main_oid {
+                Some(main_oid) if wt.is_merged_into(main_oid, local_oid) => Removable,
+                Some(_) => Skip(tr!("skip.gone_unmerged")),
+                None => Skip(tr!("skip.gone_no_main")),
+            };
+        }
+        Upstream::Unset if opts.include_untracked_merged => {
+            trace!(branch = %branch_name, "no upstream, checking merge into main");
+            return match wt.main_commit() {
+                Some(main_oid) if wt.is_merged_into(main_oid, local_oid) => Removable,
+                Some(_) => Skip(tr!("skip.no_upstream_unmerged")),
+                None => Skip(tr!("skip.no_upstream_no_main")),
+            };
+        }
+        Upstream::Unset => return Skip(tr!("skip.no_upstream")),
+    };
+
+    let (ahead, _behind) = match wt.ahead_behind(local_oid, upstream_oid) {
+        Some(r) => r,
+        None => return Skip(tr!("skip.ahead_behind_failed")),
+    };
+    trace!(
+        branch = %branch_name,
+        upstream = %upstream_name,
+        %local_oid,
+        %upstream_oid,
+        ahead,
+        "compared with upstream"
+    );
+
+    if ahead > 0 {
+        return Skip(tr!("skip.unpushed"));
+    }
+
+    Removable
+}
+
+/// 判定 `gp new --temp` 的临时 worktree 退出时能否删除：工作区干净，且 HEAD 没有超出创建时的
+/// 基点提交 `base`，与上游怎么设置无关；有了新提交时按 `clean_status` 判断，已推送的同样可以删除
+pub fn temp_clean_status(wt: &impl GitBackend, base: git2::Oid) -> WorktreeCleanStatus {
+    if wt.is_dirty() {
+        return WorktreeCleanStatus::Skip(tr!("skip.dirty"));
+    }
+    match wt.head() {
+        Some(Head::Branch { oid, .. } | Head::Detached(oid)) if wt.is_merged_into(base, oid) => {
+            WorktreeCleanStatus::Removable
+        }
+        _ => clean_status(wt, CleanOptions::default()),
+    }
+}
+
+/// worktree 最近一次被使用的时间：取其 git 目录下 `HEAD`、`index`、`logs/HEAD`
+/// 以及工作区根目录的最晚修改时间。创建、切换分支、提交、`git status` 刷新索引都会更新它们
+pub fn last_used(wt_repo: &Repository) -> Option<i64> {
+    let git_dir = wt_repo.path();
+    let mut candidates = vec![
+        git_dir.join("HEAD"),
+        git_dir.join("index"),
+        git_dir.join("logs").join("HEAD"),
+    ];
+    candidates.extend(wt_repo.workdir().map(Path::to_path_buf));
+    candidates
+        .iter()
+        .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+        .max()
+        .map(|t| DateTime::<Utc>::from(t).timestamp())
+}
+
+/// 把 linked worktree 移到 `to`，相当于 libgit2 没有提供的 `git worktree move`：
+/// 移动目录后改写 `.git/worktrees/<name>/gitdir` 和新目录中的 `.git` 文件，让两者重新互相指向。
+/// `to` 须为绝对路径，且不存在或是空目录；锁定的 worktree 拒绝移动
+pub fn move_worktree(repo: &Repository, name: &str, to: &Path) -> Result<()> {
+    let wt = repo
+        .find_worktree(name)
+        .with_context(|| tr!("move.not_found", name))?;
+    if lock_state(&wt).is_some() {
+        bail!("{}", tr!("move.locked", name));
+    }
+    let from = wt.path().to_path_buf();
+    let admin = common_dir(repo).join("worktrees").join(name);
+    let admin = admin.canonicalize().unwrap_or(admin);
+
+    if to.is_dir() {
+        // 空目录由调用方检查过；Windows 上 rename 不能覆盖已有目录
+        fs::remove_dir(to).with_context(|| tr!("move.failed", from.display(), to.display()))?;
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| tr!("git.create_dir_failed", parent.display()))?;
+    }
+    debug!(name, from = %from.display(), to = %to.display(), "moving worktree");
+    fs::rename(&from, to).with_context(|| tr!("move.failed", from.display(), to.display()))?;
+
+    if let Err(e) = write_links(&admin, to) {
+        // 指针没改好就把目录搬回去，保持 git 记录与磁盘一致
+        let _ = fs::rename(to, &from);
+        return Err(anyhow::Error::new(e).context(tr!("move.relink_failed", name)));
+    }
+    Ok(())
+}
+
+/// 让 git 记录（`<admin>/gitdir`）和 worktree 目录中的 `.git` 文件重新互相指向
+fn write_links(admin: &Path, worktree: &Path) -> io::Result<()> {
+    fs::write(
+        admin.join("gitdir"),
+        format!("{}\n", worktree.join(".git").display()),
+    )?;
+    fs::write(
+        worktree.join(".git"),
+        format!("gitdir: {}\n", admin.display()),
+    )
+}
+
+/// 读取 worktree 目录中 `.git` 文件指向的路径，相对路径相对于该目录解析；不是 linked worktree 时返回 `None`
+fn read_git_file(worktree: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(worktree.join(".git")).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(worktree.join(target))
+}
+
+/// 两个路径是否指向同一位置；都无法解析时按字面比较
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a.components().eq(b.components()),
+    }
+}
+
+/// 损坏的 worktree，见 `find_broken_worktrees`
+pub struct BrokenWorktree {
+    /// `.git/worktrees/` 下的记录名
+    pub name: String,
+    /// git 记录中的 worktree 目录；`Moved` 时为用户给出的新路径
+    pub path: PathBuf,
+    pub problem: WorktreeProblem,
+    /// 目录缺失但已锁定（可能在未挂载的移动硬盘上），不应清除记录
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorktreeProblem {
+    /// 目录已被手动删除：清除 git 中的记录
+    Missing,
+    /// 目录还在，但其 `.git` 文件没有指回本仓库（如主仓库被移动过）：改写 `.git` 文件
+    StaleGitFile,
+    /// 目录被手动移到了别处：改写 git 记录中的路径和新目录中的 `.git` 文件
+    Moved,
+}
+
+/// 找出损坏的 worktree，对应 `git worktree repair` / `git worktree prune` 会处理的情况。
+/// `moved` 为被手动移动过的 worktree 的新路径，按其 `.git` 文件找到对应的记录，不再视为目录缺失
+pub fn find_broken_worktrees(repo: &Repository, moved: &[PathBuf]) -> Result<Vec<BrokenWorktree>> {
+    use WorktreeProblem::{Missing, Moved, StaleGitFile};
+
+    let admin_root = common_dir(repo).join("worktrees");
+    let mut broken = Vec::new();
+    for path in moved {
+        let name = read_git_file(path)
+            .and_then(|admin| admin.file_name().map(|n| n.to_string_lossy().to_string()))
+            .with_context(|| tr!("repair.not_a_worktree", path.display()))?;
+        let wt = repo
+            .find_worktree(&name)
+            .with_context(|| tr!("repair.unknown_worktree", path.display()))?;
+        if same_path(wt.path(), path)
+            && read_git_file(path).is_some_and(|g| same_path(&g, &admin_root.join(&name)))
+        {
+            continue;
+        }
+        broken.push(BrokenWorktree {
+            name,
+            path: path.clone(),
+            problem: Moved,
+            locked: lock_state(&wt).is_some(),
+        });
+    }
+
+    for name in repo.worktrees()?.iter().flatten() {
+        if broken.iter().any(|b| b.name == name) {
+            continue;
+        }
+        let wt = match repo.find_worktree(name) {
+            Ok(w) => w,
+            Err(_) => continue,
+        };
+        let path = wt.path().to_path_buf();
+        let problem = if !path.is_dir() {
+            Missing
+        } else if !read_git_file(&path).is_some_and(|g| same_path(&g, &admin_root.join(name))) {
+            StaleGitFile
+        } else {
+            continue;
+        };
+        broken.push(BrokenWorktree {
+            name: name.to_string(),
+            path,
+            problem,
+            locked: lock_state(&wt).is_some(),
+        });
+    }
+    Ok(broken)
+}
+
+/// 修复一个损坏的 worktree：目录缺失的清除记录，其余的重新关联
+pub fn repair_worktree(repo: &Repository, broken: &BrokenWorktree) -> Result<()> {
+    debug!(name = %broken.name, path = %broken.path.display(), problem = ?broken.problem, "repairing worktree");
+    match broken.problem {
+        WorktreeProblem::Missing => {
+            prune_worktree(repo, &broken.name)
+                .with_context(|| tr!("repair.prune_failed", broken.name))?;
+        }
+        WorktreeProblem::StaleGitFile | WorktreeProblem::Moved => {
+            let admin = common_dir(repo).join("worktrees").join(&broken.name);
+            let admin = admin.canonicalize().unwrap_or(admin);
+            write_links(&admin, &broken.path)
+                .with_context(|| tr!("repair.relink_failed", broken.name))?;
+        }
+    }
+    Ok(())
+}
+
+/// 统计目录占用的字节数：不跟随符号链接，无权限读取的条目直接跳过，
+/// Unix 上同一 inode 的硬链接只计一次
+pub fn dir_size(path: &Path) -> u64 {
+    #[cfg(unix)]
+    let mut seen = std::collections::HashSet::new();
+    let mut total = 0;
+    let mut stack = vec![path.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let meta = match fs::symlink_metadata(entry.path()) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            if meta.is_dir() {
+                stack.push(entry.path());
+                continue;
+            }
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                if meta.nlink() > 1 && !seen.insert((meta.dev(), meta.ino())) {
+                    continue;
+                }
+            }
+            total += meta.len();
+        }
+    }
+
+    total
+}
+
+/// 清除 worktree 在 `.git/worktrees/` 下的记录（目录须已删除），相当于 `git worktree prune` 单个条目
+pub fn prune_worktree(repo: &Repository, name: &str) -> Result<(), git2::Error> {
+    debug!(name, "pruning worktree record");
+    repo.find_worktree(name)?.prune(None)
+}
+
+/// 删除 worktree 目录，目录已不存在视为成功。失败时先去掉目录内的只读属性重试一次，
+/// 仍失败则结合当前目录和权限附上可操作的原因
+pub fn remove_worktree_dir(path: &Path) -> Result<()> {
+    debug!(path = %path.display(), "removing worktree directory");
+    let err = match fs::remove_dir_all(path) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => e,
+    };
+    let cleared = clear_readonly(path);
+    if cleared > 0 && fs::remove_dir_all(path).is_ok() {
+        return Ok(());
+    }
+
+    let inside = std::env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .ok()
+        .zip(path.canonicalize().ok())
+        .is_some_and(|(cwd, target)| cwd.starts_with(target));
+    let hint = if inside {
+        Some(tr!("rmdir.cwd_inside"))
+    } else if err.kind() == io::ErrorKind::PermissionDenied && cleared > 0 {
+        Some(tr!("rmdir.readonly"))
+    } else if err.kind() == io::ErrorKind::PermissionDenied {
+        Some(tr!("rmdir.permission"))
+    } else {
+        None
+    };
+    let err = anyhow::Error::new(err);
+    Err(match hint {
+        Some(hint) => err.context(hint),
+        None => err,
+    })
+}
+
+/// 给目录下（含自身）所有只读的文件和目录加上属主写权限，不跟随符号链接，返回改动的条目数
+fn clear_readonly(path: &Path) -> usize {
+    let mut cleared = 0;
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(entry) = stack.pop() {
+        let meta = match fs::symlink_metadata(&entry) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        if meta.permissions().readonly() && make_writable(&entry, meta.permissions()).is_ok() {
+            cleared += 1;
+        }
+        if meta.is_dir() {
+            if let Ok(children) = fs::read_dir(&entry) {
+                stack.extend(children.flatten().map(|c| c.path()));
+            }
+        }
+    }
+    cleared
+}
+
+fn make_writable(path: &Path, mut perms: fs::Permissions) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        perms.set_mode(perms.mode() | 0o200);
+    }
+    #[cfg(not(unix))]
+    perms.set_readonly(false);
+    fs::set_permissions(path, perms)
+}
+
+/// 可清理的 worktree
+#[derive(Serialize)]
+pub struct CleanCandidate {
+    pub name: String,
+    /// 检出的本地分支，游离 HEAD 时为 `None`
+    pub branch: Option<String>,
+    pub path: PathBuf,
+    /// 目录占用的字节数
+    pub size: u64,
+    /// 最近一次使用的 Unix 时间戳，见 `last_used`
+    pub last_used: Option<i64>,
+}
+
+/// `gp clean` 的判定结果：可清理的条目和跳过的条目（附原因）
+#[derive(Default)]
+pub struct CleanPlan {
+    pub removable: Vec<CleanCandidate>,
+    pub skipped: Vec<(String, &'static str)>,
+}
+
+/// 逐个判定 linked worktree 能否清理；`base` 只考虑从该远端分支派生的 worktree，钉住的一律跳过
+pub fn plan_clean(repo: &Repository, base: Option<&str>, opts: CleanOptions) -> Result<CleanPlan> {
+    let keep = KeepStore::load(&keep_path(repo));
+    plan_clean_in(repo, &keep, base, opts)
+}
+
+/// 同 `plan_clean`，但仓库查询都经由 `backend`，钉住列表由调用方给出
+pub fn plan_clean_in<B: GitBackend>(
+    backend: &B,
+    keep: &KeepStore,
+    base: Option<&str>,
+    opts: CleanOptions,
+) -> Result<CleanPlan> {
+    let mut plan = CleanPlan::default();
+
+    for name in backend.worktree_names()? {
+        let name = name.as_str();
+        let wt = match backend.find_worktree(name) {
+            Ok(w) => w,
+            Err(_) => {
+                plan.skipped
+                    .push((name.to_string(), tr!("skip.load_failed")));
+                continue;
+            }
+        };
+
+        let wt_repo = match backend.open_worktree(&wt) {
+            Ok(r) => r,
+            Err(_) => {
+                plan.skipped
+                    .push((name.to_string(), tr!("skip.open_failed")));
+                continue;
+            }
+        };
+
+        if let Some(b) = base {
+            if !derives_from_base(&wt_repo, b) {
+                debug!(worktree = name, base = b, "not derived from base, ignored");
+                continue;
+            }
+        }
+
+        if keep.contains(name) {
+            plan.skipped.push((name.to_string(), tr!("skip.kept")));
+            continue;
+        }
+
+        if wt.lock_reason.is_some() {
+            plan.skipped.push((name.to_string(), tr!("skip.locked")));
+            continue;
+        }
+
+        if let WorktreeCleanStatus::Skip(reason) = clean_status(&wt_repo, opts) {
+            debug!(worktree = name, reason, "skipped");
+            plan.skipped.push((name.to_string(), reason));
+            continue;
+        }
+
+        let last_used = wt_repo.last_used();
+        if let (Some(min_age), Some(used)) = (opts.older_than, last_used) {
+            let age = Utc::now().timestamp() - used;
+            if age < min_age {
+                debug!(worktree = name, age, min_age, "used recently, skipped");
+                plan.skipped.push((name.to_string(), tr!("skip.recent")));
+                continue;
+            }
+        }
+        debug!(worktree = name, path = %wt.path.display(), "removable");
+
+        let branch = match wt_repo.head() {
+            Some(Head::Branch { name, .. }) => Some(name),
+            _ => None,
+        };
+        plan.removable.push(CleanCandidate {
+            name: name.to_string(),
+            branch,
+            size: wt_repo.disk_usage(),
+            path: wt.path,
+            last_used,
+        });
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+    use git2::Oid;
+    use std::collections::{HashMap, HashSet};
+    use WorktreeCleanStatus::{Removable, Skip};
+
+    const LOCAL: u8 = 1;
+    const UPSTREAM: u8 = 2;
+    const MAIN: u8 = 3;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn branch(upstream: Upstream) -> MemoryBackend {
+        MemoryBackend {
+            head: Some(Head::Branch {
+                name: "feature".to_string(),
+                oid: oid(LOCAL),
+            }),
+            upstreams: HashMap::from([("feature".to_string(), upstream)]),
+            ..Default::default()
+        }
+    }
+
+    fn tracking(ahead: usize, behind: usize) -> MemoryBackend {
+        let mut wt = branch(Upstream::Tracking {
+            name: "origin/feature".to_string(),
+            oid: oid(UPSTREAM),
+        });
+        wt.ahead_behind
+            .insert((oid(LOCAL), oid(UPSTREAM)), (ahead, behind));
+        wt
+    }
+
+    /// 主分支为 `MAIN`，`merged` 时本地提交已并入主分支
+    fn with_main(mut wt: MemoryBackend, merged: bool) -> MemoryBackend {
+        wt.main = Some(oid(MAIN));
+        if merged {
+            wt.merged.insert(oid(LOCAL));
+        }
+        wt
+    }
+
+    fn status(wt: &MemoryBackend) -> WorktreeCleanStatus {
+        clean_status(wt, CleanOptions::default())
+    }
+
+    fn status_untracked_merged(wt: &MemoryBackend) -> WorktreeCleanStatus {
+        let opts = CleanOptions {
+            include_untracked_merged: true,
+            ..Default::default()
+        };
+        clean_status(wt, opts)
+    }
+
+    #[test]
+    fn temp_worktree_is_judged_against_its_base() {
+        // 上游为 own / none 时追踪分支不存在，没有新提交也应能删除
+        let base = oid(LOCAL);
+        assert_eq!(temp_clean_status(&branch(Upstream::Unset), base), Removable);
+        assert_eq!(temp_clean_status(&branch(Upstream::Gone), base), Removable);
+        assert_eq!(temp_clean_status(&tracking(0, 3), base), Removable);
+
+        let mut dirty = branch(Upstream::Unset);
+        dirty.dirty = true;
+        assert_eq!(temp_clean_status(&dirty, base), Skip(tr!("skip.dirty")));
+    }
+
+    #[test]
+    fn temp_worktree_with_new_commits_needs_them_pushed() {
+        let base = oid(MAIN);
+        assert_eq!(
+            temp_clean_status(&branch(Upstream::Unset), base),
+            Skip(tr!("skip.no_upstream"))
+        );
+        assert_eq!(
+            temp_clean_status(&tracking(1, 0), base),
+            Skip(tr!("skip.unpushed"))
+        );
+        assert_eq!(temp_clean_status(&tracking(0, 0), base), Removable);
+    }
+
+    #[test]
+    fn dirty_is_skipped_before_anything_else() {
+        let mut wt = tracking(0, 0);
+        wt.dirty = true;
+        assert_eq!(status(&wt), Skip(tr!("skip.dirty")));
+    }
+
+    #[test]
+    fn missing_head_is_skipped() {
+        assert_eq!(status(&MemoryBackend::default()), Skip(tr!("skip.no_head")));
+    }
+
+    #[test]
+    fn detached_head_needs_a_published_commit() {
+        let mut wt = MemoryBackend {
+            head: Some(Head::Detached(oid(LOCAL))),
+            ..Default::default()
+        };
+        assert_eq!(status(&wt), Skip(tr!("skip.detached_unreachable")));
+        wt.published = HashSet::from([oid(LOCAL)]);
+        assert_eq!(status(&wt), Removable);
+    }
+
+    #[test]
+    fn tracking_branch_is_removable_unless_ahead() {
+        assert_eq!(status(&tracking(0, 0)), Removable);
+        assert_eq!(status(&tracking(0, 3)), Removable);
+        assert_eq!(status(&tracking(2, 0)), Skip(tr!("skip.unpushed")));
+        assert_eq!(status(&tracking(1, 1)), Skip(tr!("skip.unpushed")));
+    }
+
+    #[test]
+    fn gone_upstream_is_removable_once_merged_into_main() {
+        assert_eq!(status(&with_main(branch(Upstream::Gone), true)), Removable);
+        assert_eq!(
+            status(&with_main(branch(Upstream::Gone), false)),
+            Skip(tr!("skip.gone_unmerged"))
+        );
+        assert_eq!(
+            status(&branch(Upstream::Gone)),
+            Skip(tr!("skip.gone_no_main"))
+        );
+    }
+
+    #[test]
+    fn no_upstream_is_skipped_by_default() {
+        let merged = with_main(branch(Upstream::Unset), true);
+        assert_eq!(status(&merged), Skip(tr!("skip.no_upstream")));
+    }
+
+    #[test]
+    fn no_upstream_merged_is_removable_when_opted_in() {
+        assert_eq!(
+            status_untracked_merged(&with_main(branch(Upstream::Unset), true)),
+            Removable
+        );
+        assert_eq!(
+            status_untracked_merged(&with_main(branch(Upstream::Unset), false)),
+            Skip(tr!("skip.no_upstream_unmerged"))
+        );
+        assert_eq!(
+            status_untracked_merged(&branch(Upstream::Unset)),
+            Skip(tr!("skip.no_upstream_no_main"))
+        );
+    }
+
+    #[test]
+    fn main_commit_itself_counts_as_merged() {
+        let mut wt = branch(Upstream::Gone);
+        wt.main = Some(oid(LOCAL));
+        assert_eq!(status(&wt), Removable);
+    }
+}
@@ -1,16 +1,408 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::engine::ArgValueCandidates;
+use git_branch_picker::config::UpstreamMode;
+use git_branch_picker::human::parse_duration;
+use git_branch_picker::messages::{self, Lang};
+use git_branch_picker::tr;
+use std::path::PathBuf;
+
+use crate::complete::{local_branches, remote_branches, CompleteShell};
+use crate::shell::ShellKind;
 
 #[derive(Parser)]
 #[command(name = "gp", version, about = "交互式 Git 分支创建工具")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Command>,
+
+    /// 启动时预填进过滤框的关键字，没有匹配的分支时显示全部
+    #[arg(value_name = "KEYWORD")]
+    pub keyword: Option<String>,
+
+    /// 只列出匹配该 glob 的远端分支（须匹配完整分支名），如 `feature/*`
+    #[arg(long, value_name = "GLOB", conflicts_with = "regex")]
+    pub filter: Option<String>,
+
+    /// 只列出匹配该正则的远端分支，如 `^release-\d+`
+    #[arg(long, value_name = "RE")]
+    pub regex: Option<String>,
+
+    /// 列出分支前先从远程仓库 fetch 并清理远端已删除的分支（同配置 fetch_on_start）；
+    /// 用于 gp prune 时先更新哪些上游已被删除
+    #[arg(long, global = true)]
+    pub fetch: bool,
+
+    /// 在指定路径的仓库中操作，而不是当前目录
+    #[arg(short = 'C', long = "repo", value_name = "PATH", global = true)]
+    pub repo: Option<PathBuf>,
+
+    /// 只打印将要做的改动（创建的分支、worktree 路径、追踪配置，或 clean / prune 将删除的内容），
+    /// 不询问、不改动仓库和磁盘，也不记录使用频率
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// 直接基于该远端分支，跳过分支选择（覆盖配置中的 default_base）
+    #[arg(long, value_name = "BRANCH", add = ArgValueCandidates::new(remote_branches))]
+    pub base: Option<String>,
+
+    /// 新分支名模板，覆盖配置中的 branch_name_template，
+    /// 可用占位符：{branch} {timestamp} {date} {user} {ticket} {slug}
+    #[arg(long, value_name = "TEMPLATE")]
+    pub name_template: Option<String>,
+
+    /// 新分支的说明，写入 git 配置 branch.<name>.description，显示在 gp w、gp l 中；
+    /// 交互式创建时给出后不再询问
+    #[arg(long, value_name = "TEXT", conflicts_with = "detached")]
+    pub description: Option<String>,
+
+    /// 先从配置的工单系统（GitHub Issues 或 Jira）选一张分配给自己的工单，
+    /// 把编号和标题填进名称模板的 {ticket} {slug}
+    #[arg(long, conflicts_with = "detached")]
+    pub issue: bool,
+
+    /// 直接创建 worktree：不询问动作、名称、备注和是否切换目录
+    #[arg(long)]
+    pub worktree: bool,
+
+    /// 创建临时 worktree 并进入子 Shell，退出后若仍干净且无未推送提交则自动删除它和分支
+    #[arg(long, conflicts_with_all = ["from", "print_path"])]
+    pub temp: bool,
+
+    /// 进入 worktree 时不打开子 Shell，而是把路径打印到标准输出（其余输出改到 stderr），
+    /// 供 `gp shell-init` 生成的包装函数在当前 Shell 中 cd
+    #[arg(long, global = true)]
+    pub print_path: bool,
+
+    /// 界面语言，覆盖环境变量 GP_LANG、配置中的 lang 和系统 locale
+    #[arg(long, value_name = "LANG", global = true, value_parser = parse_lang)]
+    pub lang: Option<Lang>,
+
+    /// 在 stderr 输出调试日志：解析的 ref、创建的分支、写入的配置、prune 以及 clean 的判定依据；`-vv` 更详细
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// 过滤时同时匹配末次提交的信息和作者
+    #[arg(long)]
+    pub rich_filter: bool,
+
+    /// 标注已合并进主分支的分支（分支列表和 gp w 均适用），需要逐个计算，默认关闭
+    #[arg(long, global = true)]
+    pub show_merged: bool,
+
+    /// 创建 worktree 时把当前未提交的改动（仅已跟踪文件）搬到新 worktree
+    #[arg(long)]
+    pub carry: bool,
+
+    /// 同 --carry，但连同未跟踪文件一起搬走
+    #[arg(long)]
+    pub carry_untracked: bool,
+
+    /// 让新分支追踪 origin/<BRANCH>，而不是它的基点分支
+    #[arg(
+        long,
+        value_name = "BRANCH",
+        conflicts_with = "no_track",
+        add = ArgValueCandidates::new(remote_branches)
+    )]
+    pub track: Option<String>,
+
+    /// 不给新分支设置上游
+    #[arg(long)]
+    pub no_track: bool,
+
+    /// 新分支的上游：base 追踪基点分支，own 追踪同名远端分支（首次推送时创建），none 不设置；
+    /// 覆盖配置 upstream
+    #[arg(
+        long,
+        value_name = "MODE",
+        conflicts_with_all = ["track", "no_track"],
+        value_parser = parse_upstream
+    )]
+    pub upstream: Option<UpstreamMode>,
+
+    /// 切换分支前自动 stash 当前未提交的改动，切换后再恢复
+    #[arg(long)]
+    pub autostash: bool,
+
+    /// 创建分支 / worktree 后直接推送到 origin 并把上游设为推送后的远端分支，不再询问
+    #[arg(long, conflicts_with = "temp")]
+    pub push: bool,
+
+    /// 把 worktree 创建在指定目录（须不存在或为空），优先于布局配置
+    #[arg(long, value_name = "DIR")]
+    pub worktree_path: Option<PathBuf>,
+
+    /// 基于指定的 tag 或提交（而非远端分支）创建，需配合 --detached
+    #[arg(long, value_name = "REV", requires = "detached")]
+    pub from: Option<String>,
+
+    /// 创建 HEAD 游离的只读 worktree，不新建分支
+    #[arg(long, requires = "from")]
+    pub detached: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Command {
     /// 列出所有 worktree，支持切换和删除
-    W,
+    W {
+        /// 不进入交互，以 JSON 输出各 worktree 的名称、分支、路径、是否有修改和领先 / 落后提交数
+        #[arg(long)]
+        json: bool,
+    },
+    /// 按选择列表的顺序列出远端分支，每行一个；供脚本或编辑器插件使用
+    List {
+        /// 以 JSON 输出，附带使用次数、是否置顶和末次提交
+        #[arg(long)]
+        json: bool,
+    },
+    /// 选择本地分支并切换过去（显示与上游的领先 / 落后提交数）
+    L {
+        /// 启动时预填进过滤框的关键字
+        #[arg(value_name = "KEYWORD", add = ArgValueCandidates::new(local_branches))]
+        keyword: Option<String>,
+    },
+    /// 模糊选择本地分支并在当前工作区检出；工作区有未提交的改动时拒绝切换，加 --autostash 则先 stash
+    Switch {
+        /// 启动时预填进过滤框的关键字
+        #[arg(value_name = "KEYWORD", add = ArgValueCandidates::new(local_branches))]
+        keyword: Option<String>,
+    },
     /// 清理干净的 worktree（无修改、无未推送提交）
-    Clean,
+    Clean {
+        /// 只清理从该远端分支派生的 worktree（分支名以 `<BASE>-` 开头或追踪 origin/<BASE>）
+        #[arg(long, add = ArgValueCandidates::new(remote_branches))]
+        base: Option<String>,
+        /// 没有追踪分支的 worktree，若 HEAD 已合并进主分支（origin/HEAD 指向的分支，或 main / master）也一并清理
+        #[arg(long)]
+        include_untracked_merged: bool,
+        /// 删除 worktree 后一并删除对应的本地分支
+        #[arg(long, conflicts_with = "keep_branches")]
+        delete_branches: bool,
+        /// 保留本地分支，覆盖配置中的 `clean_delete_branches = true`
+        #[arg(long)]
+        keep_branches: bool,
+        /// 只清理超过该时长没有用过的 worktree，如 `30m`、`12h`、`14d`、`2w`
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        older_than: Option<i64>,
+        /// 以 JSON 输出可清理和跳过的 worktree，只输出不删除；与 `--yes` 同用时删除后输出删除结果
+        #[arg(long)]
+        json: bool,
+        /// 不询问直接删除，用于 cron、post-merge 钩子等自动化场景
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 不做任何询问，基于远端分支创建分支（或 worktree），标准输出只打印分支名（或 worktree 路径）
+    New {
+        /// 作为基点的远端分支
+        #[arg(add = ArgValueCandidates::new(remote_branches))]
+        branch: String,
+        /// 创建 worktree 而不是在当前工作区切换分支
+        #[arg(short, long)]
+        worktree: bool,
+        /// 新分支名，默认按名称模板生成
+        #[arg(long, value_name = "NAME")]
+        name: Option<String>,
+    },
+    /// 删除上游已被删除（[gone]）的本地分支，删除前勾选确认
+    Prune,
+    /// 删除远端分支（推送删除 refspec），主分支和配置 protected_branches 中的分支受保护；
+    /// 省略分支名时从远端分支列表中选择
+    RmRemote {
+        /// 远端分支名（不含 origin/ 前缀）
+        #[arg(add = ArgValueCandidates::new(remote_branches))]
+        branch: Option<String>,
+        /// 不询问，直接删除
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 打印 Shell 包装函数，让 gp 在当前 Shell 中 cd 到 worktree，如 `eval "$(gp shell-init bash)"`
+    ShellInit {
+        #[arg(value_enum)]
+        shell: ShellKind,
+    },
+    /// 打印 Shell 补全脚本，远端分支名等参数会按当前仓库动态补全，如 `source <(gp completions bash)`
+    Completions {
+        #[arg(value_enum)]
+        shell: CompleteShell,
+    },
+    /// 检查并修复损坏的 worktree：目录已被手动删除的清除记录，指针失效的重新关联（同 `git worktree repair`）
+    Repair {
+        /// 被手动移动过的 worktree 的新路径
+        paths: Vec<PathBuf>,
+    },
+    /// 显示使用统计：最常用的基点分支、gp 创建 / 删除的 worktree 数和平均存活时长
+    Stats {
+        /// 最多列出的基点分支数
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        top: usize,
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 汇总所有 worktree 的状态（只读）
+    Status {
+        /// 以 JSON 格式输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 按编号拉取 PR/MR 并创建 worktree（支持 GitHub、GitLab、Bitbucket）；GitHub / GitLab 仓库省略编号时列出打开的 PR / MR 供选择
+    Pr {
+        /// PR/MR 编号，省略时从 GitHub / GitLab 列出打开的 PR / MR
+        number: Option<u32>,
+        /// 在当前工作区创建并切换到分支，而不是创建 worktree
+        #[arg(short, long)]
+        branch: bool,
+        /// 无法识别托管平台时手动指定 ref 模板，`{n}` 为编号，如 `refs/pull/{n}/head`
+        #[arg(long, value_name = "TEMPLATE")]
+        ref_template: Option<String>,
+    },
+}
+
+impl Command {
+    /// 标准输出供脚本读取的命令（JSON 输出、`gp list`、`gp new`），`--print-path` 时也不改写标准输出
+    pub fn machine_readable(&self) -> bool {
+        matches!(
+            self,
+            Command::List { .. }
+                | Command::New { .. }
+                | Command::W { json: true }
+                | Command::Clean { json: true, .. }
+                | Command::Stats { json: true, .. }
+                | Command::Status { json: true }
+        )
+    }
+}
+
+fn parse_lang(value: &str) -> Result<Lang, String> {
+    match value {
+        "zh" => Ok(Lang::Zh),
+        "en" => Ok(Lang::En),
+        _ => Err(tr!("lang.invalid", value)),
+    }
+}
+
+fn parse_upstream(value: &str) -> Result<UpstreamMode, String> {
+    match value {
+        "base" => Ok(UpstreamMode::Base),
+        "own" => Ok(UpstreamMode::Own),
+        "none" => Ok(UpstreamMode::Unset),
+        _ => Err(tr!("upstream.invalid", value)),
+    }
+}
+
+/// 解析前先从命令行找出 `--lang`，让帮助和参数错误也使用该语言
+pub fn lang_arg() -> Option<Lang> {
+    let mut args = std::env::args().skip(1).take_while(|a| a != "--");
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--lang") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(str::to_string),
+            None => continue,
+        };
+        return value.and_then(|v| parse_lang(&v).ok());
+    }
+    None
+}
+
+/// 按当前语言生成的 clap 命令：中文帮助来自上面的文档注释，英文时逐项替换为 `english_help` 中的文案
+pub fn command() -> clap::Command {
+    let cmd = Cli::command();
+    match messages::current() {
+        Lang::Zh => cmd,
+        Lang::En => localize(cmd, ""),
+    }
+}
+
+fn localize(mut cmd: clap::Command, path: &str) -> clap::Command {
+    if let Some(about) = english_help(path) {
+        cmd = cmd.about(about);
+    }
+    let prefix = if path.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", path)
+    };
+    cmd = cmd.mut_args(
+        |arg| match english_help(&format!("{}--{}", prefix, arg.get_id())) {
+            Some(help) => arg.help(help),
+            None => arg,
+        },
+    );
+    cmd.mut_subcommands(|sub| {
+        let path = format!("{}{}", prefix, sub.get_name());
+        localize(sub, &path)
+    })
+}
+
+/// 命令行帮助的英文文案。key 为子命令路径（顶层为空串），参数为 `<子命令> --<参数 id>`
+fn english_help(key: &str) -> Option<&'static str> {
+    let text = match key {
+        "" => "Interactive Git branch creation tool",
+        "--keyword" => "Keyword prefilled into the filter box on start; shows all branches when nothing matches",
+        "--filter" => "Only list remote branches matching this glob (against the full branch name), e.g. `feature/*`",
+        "--regex" => "Only list remote branches matching this regex, e.g. `^release-\\d+`",
+        "--fetch" => "Fetch from the remote and prune deleted branches before listing (same as config fetch_on_start); lets gp prune see which upstreams are gone",
+        "--repo" => "Operate on the repository at this path instead of the current directory",
+        "--dry_run" => "Only print the changes that would be made (branches, worktree paths, tracking, or what clean / prune would delete) without prompting, touching the repository or disk, or recording usage",
+        "--base" => "Base directly on this remote branch and skip the branch picker (overrides config default_base)",
+        "--name_template" => "Template for the new branch name, overriding config branch_name_template; placeholders: {branch} {timestamp} {date} {user} {ticket} {slug}",
+        "--description" => "Description of the new branch, stored in git config branch.<name>.description and shown in gp w and gp l; skips the prompt when creating interactively",
+        "--issue" => "First pick an issue assigned to you from the configured tracker (GitHub Issues or Jira) and fill its key and title into the {ticket} {slug} placeholders",
+        "--worktree" => "Create a worktree right away without asking for the action, name, note or whether to enter it",
+        "--temp" => "Create a temporary worktree and open a subshell; on exit it and its branch are removed if still clean with no unpushed commits",
+        "--print_path" => "When entering a worktree, print its path to stdout instead of opening a subshell (other output goes to stderr), for the wrappers from `gp shell-init`",
+        "--lang" => "Interface language, overriding GP_LANG, config lang and the system locale",
+        "--verbose" => "Log to stderr: resolved refs, created branches, config writes, prunes and why clean kept or removed each worktree; `-vv` for more detail",
+        "--rich_filter" => "Also match the last commit's message and author when filtering",
+        "--show_merged" => "Mark branches already merged into the main branch (branch list and gp w); computed per branch, off by default",
+        "--carry" => "When creating a worktree, move the current uncommitted changes (tracked files only) into it",
+        "--carry_untracked" => "Like --carry, but also move untracked files",
+        "--track" => "Make the new branch track origin/<BRANCH> instead of its base",
+        "--no_track" => "Do not set an upstream for the new branch",
+        "--upstream" => "Upstream of the new branch: base tracks its base branch, own tracks the same-named remote branch (created on first push), none sets no upstream; overrides the upstream config",
+        "--autostash" => "Stash uncommitted changes automatically before switching branches and re-apply them afterwards",
+        "--push" => "Push the new branch or worktree branch to origin and set its upstream to the pushed branch without asking",
+        "--worktree_path" => "Create the worktree in this directory (must be missing or empty), taking precedence over the layout config",
+        "--from" => "Create from this tag or commit instead of a remote branch; requires --detached",
+        "--detached" => "Create a read-only worktree with a detached HEAD instead of a new branch",
+        "w" => "List all worktrees to enter or delete",
+        "w --json" => "Print each worktree's name, branch, path, changes and ahead / behind counts as JSON instead of the interactive list",
+        "list" => "Print remote branches one per line in picker order, for scripts and editor plugins",
+        "list --json" => "Print JSON including use count, pinned state and last commit",
+        "l" => "Pick a local branch and switch to it (shows ahead / behind counts against upstream)",
+        "l --keyword" => "Keyword prefilled into the filter box on start",
+        "switch" => "Fuzzy-pick a local branch and check it out in the current worktree; refuses when there are uncommitted changes unless --autostash is given",
+        "switch --keyword" => "Keyword prefilled into the filter box on start",
+        "clean" => "Remove clean worktrees (no changes, no unpushed commits)",
+        "clean --base" => "Only clean worktrees derived from this remote branch (branch named `<BASE>-...` or tracking origin/<BASE>)",
+        "clean --include_untracked_merged" => "Also clean worktrees without an upstream whose HEAD is merged into the main branch (origin/HEAD, or main / master)",
+        "clean --delete_branches" => "Delete the local branches after removing their worktrees",
+        "clean --keep_branches" => "Keep local branches, overriding config `clean_delete_branches = true`",
+        "clean --older_than" => "Only clean worktrees unused for longer than this, e.g. `30m`, `12h`, `14d`, `2w`",
+        "clean --json" => "Print removable and skipped worktrees as JSON without removing anything; with `--yes`, remove and print the results",
+        "clean --yes" => "Remove without asking, for cron jobs, post-merge hooks and other automation",
+        "new" => "Create a branch (or worktree) from a remote branch without any prompts; stdout only gets the branch name (or worktree path)",
+        "new --branch" => "Remote branch to base on",
+        "new --worktree" => "Create a worktree instead of switching branches in the current checkout",
+        "new --name" => "Name of the new branch; generated from the name template by default",
+        "prune" => "Delete local branches whose upstream is gone ([gone]), after confirming in a checklist",
+        "rm-remote" => "Delete a remote branch by pushing a delete refspec; the main branch and config protected_branches are protected. Without a branch name, pick one from the remote branch list",
+        "rm-remote --branch" => "Remote branch name (without the origin/ prefix)",
+        "rm-remote --yes" => "Delete without asking",
+        "shell-init" => "Print a shell wrapper so gp can cd into worktrees in the current shell, e.g. `eval \"$(gp shell-init bash)\"`",
+        "completions" => "Print a shell completion script; remote branch names and similar arguments complete from the current repository, e.g. `source <(gp completions bash)`",
+        "repair" => "Check and repair broken worktrees: forget those whose directory was deleted, relink those with stale pointers (like `git worktree repair`)",
+        "repair --paths" => "New paths of worktrees that were moved by hand",
+        "stats" => "Show usage statistics: most used base branches, worktrees created / removed by gp and their average lifetime",
+        "stats --top" => "Maximum number of base branches to list",
+        "stats --json" => "Print as JSON",
+        "status" => "Summarize the status of all worktrees (read-only)",
+        "status --json" => "Print as JSON",
+        "pr" => "Fetch a PR/MR by number and create a worktree for it (GitHub, GitLab, Bitbucket); on GitHub and GitLab, omit the number to pick from open PRs / MRs",
+        "pr --number" => "PR/MR number; omit it to list open PRs / MRs from GitHub or GitLab",
+        "pr --branch" => "Create and switch to a branch in the current checkout instead of a worktree",
+        "pr --ref_template" => "Ref template when the hosting platform cannot be detected, `{n}` is the number, e.g. `refs/pull/{n}/head`",
+        _ => return None,
+    };
+    Some(text)
 }
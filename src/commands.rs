@@ -0,0 +1,1337 @@
+use anyhow::{bail, Context, Result};
+use git2::{BranchType, Oid, Repository};
+use inquire::{Confirm, InquireError, MultiSelect, Select, Text};
+use serde::Serialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+use git_branch_picker::config::{expand_home, Config};
+use git_branch_picker::editor::{editor_command, open_in_editor};
+use git_branch_picker::freq::{freq_path, FrequencyStore, UseKind};
+use git_branch_picker::git::{
+    autostash_message, checkout_local_branch, delete_local_branch, gone_branches, layout_root,
+    list_local_branches, list_remote_branches, pull_fast_forward, remote, remote_branch_label,
+    restore_stash, stash_changes, unpushed_commits, worktree_is_dirty, GoneBranch, LocalBranch,
+};
+use git_branch_picker::hooks::{run_hooks, HookContext};
+use git_branch_picker::human::{format_age, format_duration, format_size, pad};
+use git_branch_picker::keep::{keep_path, KeepStore};
+use git_branch_picker::keymap::describe;
+use git_branch_picker::layout::{explicit_worktree_path, resolve_worktree_path};
+use git_branch_picker::meta::{meta_path, BranchMetaStore};
+use git_branch_picker::plan::Effect;
+use git_branch_picker::tr;
+use git_branch_picker::worktree::{
+    checked_out_branches, find_broken_worktrees, gather_status, gather_worktrees, lock_state,
+    mark_merged, move_worktree, plan_clean, prune_worktree, remove_worktree_dir, repair_worktree,
+    temp_clean_status, BrokenWorktree, CleanCandidate, CleanOptions, CleanPlan,
+    WorktreeCleanStatus, WorktreeEntry, WorktreeProblem,
+};
+
+use crate::outcome::Outcome;
+use crate::picker::BranchPicker;
+use crate::ui::{
+    branch_items, enter_worktree, print_effects, read_worktree_action, reapply_autostash,
+    sort_items, BranchItem, SortMode, WtAction,
+};
+
+pub fn print_status(repo: &Repository, json: bool) -> Result<()> {
+    let statuses = gather_status(repo)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&statuses)?);
+        return Ok(());
+    }
+
+    if statuses.is_empty() {
+        println!("{}", tr!("wt.none"));
+        return Ok(());
+    }
+
+    println!(
+        "{:<30} {:<6} {:<10} {:<30} {:<4} {}",
+        tr!("status.col.branch"),
+        tr!("status.col.state"),
+        tr!("status.col.ahead_behind"),
+        tr!("status.col.upstream"),
+        tr!("status.col.locked"),
+        tr!("status.col.path")
+    );
+    for st in &statuses {
+        let state = if st.dirty { "dirty" } else { "clean" };
+        let progress = match st.upstream {
+            Some(_) => format!("↑{} ↓{}", st.ahead, st.behind),
+            None => "-".to_string(),
+        };
+        let upstream = st.upstream.as_deref().unwrap_or("-");
+        let locked = if st.locked { "🔒" } else { "" };
+        println!(
+            "{:<30} {:<6} {:<10} {:<30} {:<4} {}",
+            st.branch,
+            state,
+            progress,
+            upstream,
+            locked,
+            st.path.display()
+        );
+        if let Some(reason) = &st.lock_reason {
+            println!("    🔒 {}", reason);
+        }
+        if let Some(description) = &st.description {
+            println!("    📝 {}", description);
+        }
+        if let Some(meta) = &st.created {
+            println!("    {}", meta.describe());
+        }
+    }
+
+    Ok(())
+}
+
+/// 在主 worktree 中切换到另一个本地分支，已被任一 worktree 检出的分支不在候选列表中
+fn switch_main_branch(main_path: &Path, occupied: &[String]) -> Result<()> {
+    let main_repo = Repository::open(main_path)?;
+
+    let mut candidates = Vec::new();
+    for item in main_repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = item?;
+        if let Some(name) = branch.name()? {
+            if !occupied.iter().any(|o| o == name) {
+                candidates.push(name.to_string());
+            }
+        }
+    }
+
+    if candidates.is_empty() {
+        println!("{}", tr!("wt.switch.none"));
+        return Ok(());
+    }
+
+    let target = match Select::new(tr!("wt.switch.prompt"), candidates)
+        .with_help_message(tr!("wt.switch.help"))
+        .prompt()
+    {
+        Ok(name) => name,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if worktree_is_dirty(&main_repo) {
+        let proceed = match Confirm::new(tr!("wt.switch.dirty_confirm"))
+            .with_default(false)
+            .prompt()
+        {
+            Ok(v) => v,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if !proceed {
+            return Ok(());
+        }
+    }
+
+    match checkout_local_branch(&main_repo, &target) {
+        Ok(()) => println!("{}", tr!("wt.switch.done", target)),
+        Err(e) => eprintln!("✗ {:#}", e),
+    }
+    Ok(())
+}
+
+/// 切换本地分支时，工作区有未提交改动的处理方式
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DirtyPolicy {
+    /// 先提示，选好分支后再确认一次（`gp l`）
+    Confirm,
+    /// 直接报错，不切换（`gp switch`）
+    Refuse,
+    /// 先 stash 再切换，切换后恢复（`--autostash`）
+    Autostash,
+}
+
+/// `gp l` / `gp switch`：按使用频率列出本地分支，选中后在当前工作区切换过去。
+/// 被其它 worktree 检出的分支无法切换，不列出
+pub fn pick_local_branch(
+    repo: &mut Repository,
+    config: &Config,
+    keyword: Option<&str>,
+    policy: DirtyPolicy,
+    dry_run: bool,
+) -> Result<Outcome> {
+    if repo.is_bare() {
+        bail!("{}", tr!("main.bare_no_checkout"));
+    }
+    let occupied = checked_out_branches(repo)?;
+    let branches: Vec<LocalBranch> = list_local_branches(repo)?
+        .into_iter()
+        .filter(|b| b.is_head || !occupied.contains(&b.name))
+        .collect();
+    if branches.iter().all(|b| b.is_head) {
+        println!("{}", tr!("local.none"));
+        return Ok(Outcome::Nothing);
+    }
+
+    let dirty = worktree_is_dirty(repo);
+    if dirty {
+        match policy {
+            DirtyPolicy::Confirm => println!("{}", tr!("local.dirty")),
+            DirtyPolicy::Refuse => bail!("{}", tr!("local.dirty_refused")),
+            DirtyPolicy::Autostash => {}
+        }
+    }
+
+    let freq_path = freq_path(repo);
+    let freq = FrequencyStore::load(&freq_path);
+    let mut items: Vec<BranchItem> = branches
+        .into_iter()
+        .map(|b| BranchItem {
+            label: if b.is_head {
+                format!("* {}", b.name)
+            } else {
+                b.name.clone()
+            },
+            count: freq.count(&b.name, UseKind::Checkout),
+            score: freq.frecency(&b.name, UseKind::Checkout),
+            last_used: freq.last_used(&b.name),
+            pinned: config.pinned.iter().position(|p| *p == b.name),
+            commit: None,
+            merged: false,
+            committed_at: None,
+            ahead_behind: None,
+            columns: false,
+            tracking: Some(tracking_label(&b)),
+            description: b.description,
+            name: b.name,
+        })
+        .collect();
+    sort_items(SortMode::Frequency, &mut items);
+
+    let resort = |mode: SortMode, items: &mut [BranchItem]| {
+        if mode == SortMode::Recent {
+            for item in items.iter_mut().filter(|i| i.committed_at.is_none()) {
+                item.committed_at = repo
+                    .find_branch(&item.name, BranchType::Local)
+                    .and_then(|b| b.get().peel_to_commit())
+                    .map(|c| c.time().seconds())
+                    .ok();
+            }
+        }
+        sort_items(mode, items);
+    };
+    let picker = BranchPicker {
+        prompt: tr!("local.prompt"),
+        items,
+        filter: keyword.unwrap_or_default().to_string(),
+        scorer: Select::DEFAULT_SCORER,
+        sort: SortMode::Frequency,
+        sort_keys: &config.keys.sort,
+        resort: &resort,
+    };
+    let target = match picker.prompt()? {
+        Some(p) => p.name,
+        None => {
+            println!("{}", tr!("common.cancelled"));
+            return Ok(Outcome::Cancelled);
+        }
+    };
+
+    let current = repo
+        .head()
+        .ok()
+        .and_then(|h| h.shorthand().map(str::to_string));
+    if current.as_deref() == Some(target.as_str()) {
+        println!("{}", tr!("local.already_on", target));
+        return Ok(Outcome::Nothing);
+    }
+    let autostash = dirty && policy == DirtyPolicy::Autostash;
+    if dry_run {
+        let mut effects = Vec::new();
+        if autostash {
+            effects.push(Effect::Stash {
+                include_untracked: false,
+            });
+        }
+        effects.push(Effect::Checkout {
+            branch: target.clone(),
+        });
+        if autostash {
+            effects.push(Effect::RestoreStash { branch: target });
+        }
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+    if dirty && policy == DirtyPolicy::Confirm {
+        let proceed = match Confirm::new(tr!("local.dirty_confirm"))
+            .with_default(false)
+            .prompt()
+        {
+            Ok(v) => v,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if !proceed {
+            println!("{}", tr!("common.cancelled"));
+            return Ok(Outcome::Cancelled);
+        }
+    }
+
+    let stashed = autostash && stash_changes(repo, &autostash_message(&target), false)?;
+    if let Err(e) = checkout_local_branch(repo, &target) {
+        if stashed {
+            restore_stash(repo)?;
+        }
+        return Err(e);
+    }
+    FrequencyStore::record(&freq_path, &target, UseKind::Checkout)?;
+    println!("{}", tr!("local.switched", target));
+    if stashed {
+        reapply_autostash(repo, &target)?;
+    }
+    Ok(Outcome::Done)
+}
+
+/// 列表中分支名后的上游状态，如 `origin/main ↑2 ↓1`
+fn tracking_label(branch: &LocalBranch) -> String {
+    let upstream = match &branch.upstream {
+        Some(u) => u,
+        None => return tr!("local.no_upstream").to_string(),
+    };
+    match branch.ahead_behind {
+        Some((0, 0)) | None => upstream.clone(),
+        Some((ahead, 0)) => format!("{} ↑{}", upstream, ahead),
+        Some((0, behind)) => format!("{} ↓{}", upstream, behind),
+        Some((ahead, behind)) => format!("{} ↑{} ↓{}", upstream, ahead, behind),
+    }
+}
+
+/// `gp prune`：列出上游已删除的本地分支，勾选后删除。被任一 worktree 检出的分支不会删除；
+/// 未并入主分支的分支默认不勾选
+pub fn prune_gone_branches(repo: &Repository, dry_run: bool) -> Result<Outcome> {
+    let gone = gone_branches(repo)?;
+    if gone.is_empty() {
+        println!("{}", tr!("prune.none"));
+        return Ok(Outcome::Nothing);
+    }
+
+    let occupied = checked_out_branches(repo)?;
+    let (busy, candidates): (Vec<GoneBranch>, Vec<GoneBranch>) =
+        gone.into_iter().partition(|b| occupied.contains(&b.name));
+    for b in &busy {
+        println!("{}", tr!("prune.checked_out", b.name));
+    }
+    if candidates.is_empty() {
+        return Ok(Outcome::Nothing);
+    }
+
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|b| {
+            let mark = if b.merged { "" } else { tr!("prune.unmerged") };
+            tr!("prune.item", b.name, b.upstream, mark)
+        })
+        .collect();
+    let defaults: Vec<usize> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.merged)
+        .map(|(i, _)| i)
+        .collect();
+    let selected = match MultiSelect::new(tr!("prune.prompt"), labels)
+        .with_default(&defaults)
+        .with_help_message(tr!("prune.help"))
+        .raw_prompt()
+    {
+        Ok(items) => items,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            println!("{}", tr!("common.cancelled"));
+            return Ok(Outcome::Cancelled);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if selected.is_empty() {
+        println!("{}", tr!("prune.nothing_selected"));
+        return Ok(Outcome::Nothing);
+    }
+
+    let targets: Vec<&GoneBranch> = selected.iter().map(|o| &candidates[o.index]).collect();
+    if dry_run {
+        let effects: Vec<Effect> = targets
+            .iter()
+            .map(|b| Effect::DeleteBranch {
+                name: b.name.clone(),
+            })
+            .collect();
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+
+    let mut failed = 0;
+    for b in targets {
+        match delete_local_branch(repo, &b.name) {
+            Ok(()) => println!("{}", tr!("prune.deleted", b.name)),
+            Err(e) => {
+                failed += 1;
+                eprintln!("✗ {:#}", e);
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{}", tr!("prune.failed", failed));
+    }
+    Ok(Outcome::Done)
+}
+
+pub fn interactive_worktree_list(
+    repo: &Repository,
+    config: &Config,
+    show_merged: bool,
+) -> Result<Outcome> {
+    let keys = &config.keys;
+    let load = || -> Result<Vec<WorktreeEntry>> {
+        let mut entries = gather_worktrees(repo)?;
+        if show_merged {
+            mark_merged(repo, &mut entries);
+        }
+        Ok(entries)
+    };
+    let mut entries = load()?;
+
+    if entries.is_empty() {
+        println!("{}", tr!("wt.none"));
+        return Ok(Outcome::Nothing);
+    }
+
+    loop {
+        let selected = match Select::new(tr!("wt.select.prompt"), entries)
+            .with_help_message(tr!("wt.select.help"))
+            .prompt()
+        {
+            Ok(item) => item,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+                return Ok(Outcome::Cancelled);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let action = read_worktree_action(selected.is_main, selected.kept, selected.locked, keys)?;
+
+        match action {
+            WtAction::Cd => {
+                enter_worktree(config.switch_mode, &selected.path, &selected.branch)?;
+                return Ok(Outcome::Done);
+            }
+            WtAction::OpenEditor => {
+                let command = editor_command(config.editor.as_deref())
+                    .context(tr!("editor.not_configured"))?;
+                open_in_editor(&command, &selected.path)?;
+                return Ok(Outcome::Done);
+            }
+            WtAction::SwitchBranch => {
+                switch_main_branch(&selected.path, &checked_out_branches(repo)?)?;
+                entries = load()?;
+            }
+            WtAction::Delete => {
+                let wt_name = &selected.name;
+                let wt_path = &selected.path;
+
+                if selected.locked {
+                    println!("{}", tr!("wt.delete.locked", wt_name, describe(&keys.lock)));
+                    entries = load()?;
+                    continue;
+                }
+
+                let dirty = match Repository::open(wt_path) {
+                    Ok(r) => worktree_is_dirty(&r),
+                    Err(_) => true,
+                };
+
+                let prompt = if dirty {
+                    tr!("wt.delete.confirm_dirty", wt_name)
+                } else if selected.kept {
+                    tr!("wt.delete.confirm_kept", wt_name)
+                } else {
+                    tr!("wt.delete.confirm", wt_name)
+                };
+
+                let confirm = match Confirm::new(&prompt).with_default(false).prompt() {
+                    Ok(v) => v,
+                    Err(InquireError::OperationCanceled)
+                    | Err(InquireError::OperationInterrupted) => false,
+                    Err(e) => return Err(e.into()),
+                };
+
+                if confirm && remove_worktree_entry(repo, config, &selected)? {
+                    offer_delete_branch(
+                        repo,
+                        &selected.branch,
+                        config.delete_branch_with_worktree,
+                    )?;
+                }
+
+                entries = load()?;
+                if entries.is_empty() {
+                    println!("{}", tr!("wt.none_left"));
+                    return Ok(Outcome::Done);
+                }
+            }
+            WtAction::MultiDelete => {
+                delete_many_worktrees(repo, config, load()?, &selected.name)?;
+                entries = load()?;
+                if entries.is_empty() {
+                    println!("{}", tr!("wt.none_left"));
+                    return Ok(Outcome::Done);
+                }
+            }
+            WtAction::ToggleKeep => {
+                let path = keep_path(repo);
+                let mut keep = KeepStore::load(&path);
+                if keep.toggle(&selected.name) {
+                    println!("{}", tr!("wt.keep.done", selected.name));
+                } else {
+                    println!("{}", tr!("wt.keep.undone", selected.name));
+                }
+                keep.save(&path)?;
+                entries = load()?;
+            }
+            WtAction::ToggleLock => {
+                toggle_lock(repo, &selected.name, selected.locked)?;
+                entries = load()?;
+            }
+            WtAction::Move => {
+                if selected.locked {
+                    println!(
+                        "{}",
+                        tr!("move.locked_hint", selected.name, describe(&keys.lock))
+                    );
+                } else {
+                    move_worktree_entry(repo, config, &selected)?;
+                }
+                entries = load()?;
+            }
+            WtAction::Pull => {
+                let result = Repository::open(&selected.path)
+                    .context(tr!("wt.open_failed"))
+                    .and_then(|wt_repo| pull_fast_forward(&wt_repo));
+                match result {
+                    Ok(0) => println!("{}", tr!("wt.pull.up_to_date", selected.name)),
+                    Ok(n) => println!("{}", tr!("wt.pull.fast_forwarded", selected.name, n)),
+                    Err(e) => eprintln!("✗ {:#}", e),
+                }
+                entries = load()?;
+            }
+            WtAction::Back => {
+                entries = load()?;
+            }
+            WtAction::Cancel => {
+                return Ok(Outcome::Cancelled);
+            }
+        }
+    }
+}
+
+/// 执行 `pre_delete` 钩子后删除 worktree 目录并清理 git 记录和钉住标记；
+/// 已锁定、钩子或目录删除失败时只提示并返回 false
+fn remove_worktree_entry(
+    repo: &Repository,
+    config: &Config,
+    entry: &WorktreeEntry,
+) -> Result<bool> {
+    if entry.locked {
+        eprintln!("{}", tr!("wt.multi.skip_locked", entry.name));
+        return Ok(false);
+    }
+    let branch =
+        Some(entry.branch.as_str()).filter(|b| repo.find_branch(b, BranchType::Local).is_ok());
+    if !run_pre_delete(repo, config, &entry.path, branch) {
+        return Ok(false);
+    }
+    if let Err(e) = remove_worktree_dir(&entry.path) {
+        eprintln!(
+            "{}",
+            tr!(
+                "wt.delete.dir_failed",
+                entry.path.display(),
+                format!("{:#}", e)
+            )
+        );
+        return Ok(false);
+    }
+    note_worktree_removed(repo, &entry.path);
+    if let Err(e) = prune_worktree(repo, &entry.name) {
+        eprintln!("{}", tr!("wt.delete.prune_failed", entry.name, e));
+    }
+    if entry.kept {
+        let path = keep_path(repo);
+        let mut keep = KeepStore::load(&path);
+        keep.remove(&entry.name);
+        keep.save(&path)?;
+    }
+    println!("{}", tr!("wt.delete.done", entry.name));
+    Ok(true)
+}
+
+/// `gp repair`：列出损坏的 worktree，勾选后清除记录或重新关联；`dry_run` 时不询问，只打印全部修复动作。
+/// `moved` 为被手动移动过的 worktree 的新路径，相对路径相对于当前目录
+pub fn repair_worktrees(repo: &Repository, moved: &[PathBuf], dry_run: bool) -> Result<Outcome> {
+    let cwd = std::env::current_dir().context(tr!("layout.no_cwd"))?;
+    let moved: Vec<PathBuf> = moved
+        .iter()
+        .map(|p| cwd.join(p).canonicalize().unwrap_or_else(|_| cwd.join(p)))
+        .collect();
+    let broken = find_broken_worktrees(repo, &moved)?;
+
+    let (locked, candidates): (Vec<BrokenWorktree>, Vec<BrokenWorktree>) = broken
+        .into_iter()
+        .partition(|b| b.locked && b.problem == WorktreeProblem::Missing);
+    for b in &locked {
+        println!("{}", tr!("repair.locked_missing", b.name, b.path.display()));
+    }
+    if candidates.is_empty() {
+        println!("{}", tr!("repair.none"));
+        return Ok(Outcome::Nothing);
+    }
+
+    if dry_run {
+        let effects: Vec<Effect> = candidates
+            .iter()
+            .map(|b| match b.problem {
+                WorktreeProblem::Missing => Effect::PruneWorktree {
+                    name: b.name.clone(),
+                },
+                _ => Effect::RelinkWorktree {
+                    name: b.name.clone(),
+                    path: b.path.clone(),
+                },
+            })
+            .collect();
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|b| {
+            let key = match b.problem {
+                WorktreeProblem::Missing => "repair.item.missing",
+                WorktreeProblem::StaleGitFile => "repair.item.stale",
+                WorktreeProblem::Moved => "repair.item.moved",
+            };
+            tr!(key, b.name, b.path.display())
+        })
+        .collect();
+    let defaults: Vec<usize> = (0..candidates.len()).collect();
+    let selected = match MultiSelect::new(tr!("repair.prompt"), labels)
+        .with_default(&defaults)
+        .raw_prompt()
+    {
+        Ok(items) => items,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            println!("{}", tr!("common.cancelled"));
+            return Ok(Outcome::Cancelled);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if selected.is_empty() {
+        println!("{}", tr!("repair.nothing_selected"));
+        return Ok(Outcome::Nothing);
+    }
+
+    let mut failed = 0;
+    for b in selected.iter().map(|o| &candidates[o.index]) {
+        match repair_worktree(repo, b) {
+            Ok(()) if b.problem == WorktreeProblem::Missing => {
+                println!("{}", tr!("repair.pruned", b.name))
+            }
+            Ok(()) => println!("{}", tr!("repair.relinked", b.name, b.path.display())),
+            Err(e) => {
+                eprintln!("{}", tr!("common.warning", format!("{:#}", e)));
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{}", tr!("repair.some_failed", failed));
+    }
+    Ok(Outcome::Done)
+}
+
+/// `gp w` 的批量删除：勾选多个 worktree，一次确认后全部删除，再勾选要一并删除的本地分支。
+/// `current` 为进入批量删除前选中的 worktree，默认勾选
+fn delete_many_worktrees(
+    repo: &Repository,
+    config: &Config,
+    entries: Vec<WorktreeEntry>,
+    current: &str,
+) -> Result<()> {
+    let (locked, candidates): (Vec<WorktreeEntry>, Vec<WorktreeEntry>) = entries
+        .into_iter()
+        .filter(|e| !e.is_main)
+        .partition(|e| e.locked);
+    if !locked.is_empty() {
+        println!("{}", tr!("wt.multi.locked_hidden", locked.len()));
+    }
+    if candidates.is_empty() {
+        println!("{}", tr!("wt.multi.none"));
+        return Ok(());
+    }
+
+    let labels: Vec<String> = candidates.iter().map(|e| e.to_string()).collect();
+    let defaults: Vec<usize> = candidates
+        .iter()
+        .position(|e| e.name == current)
+        .into_iter()
+        .collect();
+    let picked = match MultiSelect::new(tr!("wt.multi.prompt"), labels)
+        .with_default(&defaults)
+        .with_help_message(tr!("wt.multi.help"))
+        .raw_prompt()
+    {
+        Ok(items) => items,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if picked.is_empty() {
+        println!("{}", tr!("wt.multi.nothing_selected"));
+        return Ok(());
+    }
+
+    let targets: Vec<&WorktreeEntry> = picked.iter().map(|o| &candidates[o.index]).collect();
+    for entry in &targets {
+        let dirty = Repository::open(&entry.path)
+            .map(|r| worktree_is_dirty(&r))
+            .unwrap_or(true);
+        let mut warnings = Vec::new();
+        if dirty {
+            warnings.push(tr!("wt.multi.dirty"));
+        }
+        if entry.kept {
+            warnings.push(tr!("wt.multi.kept"));
+        }
+        if warnings.is_empty() {
+            println!("  {}  ({})", entry.name, entry.path.display());
+        } else {
+            println!(
+                "  {}  ({})  ⚠ {}",
+                entry.name,
+                entry.path.display(),
+                warnings.join(tr!("wt.multi.separator"))
+            );
+        }
+    }
+    let confirm = match Confirm::new(&tr!("wt.multi.confirm", targets.len()))
+        .with_default(false)
+        .prompt()
+    {
+        Ok(v) => v,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+        Err(e) => return Err(e.into()),
+    };
+    if !confirm {
+        println!("{}", tr!("common.cancelled"));
+        return Ok(());
+    }
+
+    let mut branches = Vec::new();
+    for entry in targets {
+        if remove_worktree_entry(repo, config, entry)? {
+            branches.push(entry.branch.clone());
+        }
+    }
+    offer_delete_branches(repo, branches, config.delete_branch_with_worktree)
+}
+
+/// 批量删除 worktree 后勾选要一并删除的本地分支；`default` 时默认勾选已全部推送的分支
+fn offer_delete_branches(repo: &Repository, branches: Vec<String>, default: bool) -> Result<()> {
+    let branches: Vec<String> = branches
+        .into_iter()
+        .filter(|b| repo.find_branch(b, BranchType::Local).is_ok())
+        .collect();
+    if branches.is_empty() {
+        return Ok(());
+    }
+
+    let unpushed: Vec<Option<usize>> = branches.iter().map(|b| unpushed_commits(repo, b)).collect();
+    let labels: Vec<String> = branches
+        .iter()
+        .zip(&unpushed)
+        .map(|(b, n)| match n {
+            Some(0) => b.clone(),
+            Some(n) => tr!("wt.multi.branch_unpushed", b, n),
+            None => tr!("wt.multi.branch_no_upstream", b),
+        })
+        .collect();
+    let defaults: Vec<usize> = if default {
+        (0..branches.len())
+            .filter(|&i| unpushed[i] == Some(0))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let picked = match MultiSelect::new(tr!("wt.multi.branches_prompt"), labels)
+        .with_default(&defaults)
+        .with_help_message(tr!("wt.multi.help"))
+        .raw_prompt()
+    {
+        Ok(items) => items,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    for option in picked {
+        let branch = &branches[option.index];
+        match delete_local_branch(repo, branch) {
+            Ok(()) => println!("{}", tr!("wt.delete_branch.done", branch)),
+            Err(e) => eprintln!("✗ {:#}", e),
+        }
+    }
+    Ok(())
+}
+
+/// 询问新路径并移动 worktree。输入框预填按当前布局为它计算的路径，相对路径相对于当前目录
+fn move_worktree_entry(repo: &Repository, config: &Config, entry: &WorktreeEntry) -> Result<()> {
+    let repo_root = layout_root(repo)?;
+    let origin_url = repo
+        .find_remote(remote())
+        .ok()
+        .and_then(|r| r.url().map(str::to_string));
+    let suggested = resolve_worktree_path(
+        &repo_root,
+        config.worktree_dir(&repo_root).as_deref(),
+        config.worktree_layout,
+        config.worktree_path_template.as_deref(),
+        origin_url.as_deref(),
+        &entry.name,
+    )
+    .ok()
+    .filter(|p| *p != entry.path)
+    .unwrap_or_else(|| entry.path.clone());
+
+    let input = match Text::new(&tr!("move.prompt", entry.name))
+        .with_initial_value(&suggested.display().to_string())
+        .prompt()
+    {
+        Ok(s) => s,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(());
+    }
+    let target = explicit_worktree_path(&expand_home(Path::new(input)))?;
+    if target.components().eq(entry.path.components()) {
+        println!("{}", tr!("move.same_path"));
+        return Ok(());
+    }
+
+    move_worktree(repo, &entry.name, &target)?;
+    if let Err(e) = FrequencyStore::record_worktree_moved(&freq_path(repo), &entry.path, &target) {
+        eprintln!("{}", tr!("common.warning", format!("{:#}", e)));
+    }
+    println!("{}", tr!("move.done", entry.name, target.display()));
+    Ok(())
+}
+
+/// 锁定时可填写原因（回车跳过），锁定后 git 不会 prune 它
+fn toggle_lock(repo: &Repository, name: &str, locked: bool) -> Result<()> {
+    let wt = repo.find_worktree(name)?;
+    if locked {
+        wt.unlock().with_context(|| tr!("wt.unlock.failed", name))?;
+        println!("{}", tr!("wt.unlock.done", name));
+        return Ok(());
+    }
+
+    let reason = match Text::new(tr!("wt.lock.reason")).prompt() {
+        Ok(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Ok(_) => None,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    wt.lock(reason.as_deref())
+        .with_context(|| tr!("wt.lock.failed", name))?;
+    println!("{}", tr!("wt.lock.done", name));
+    Ok(())
+}
+
+/// 删除 worktree 后询问是否一并删除它检出的本地分支；有未推送提交或没有上游时额外警告且默认不删
+fn offer_delete_branch(repo: &Repository, branch: &str, default: bool) -> Result<()> {
+    if repo.find_branch(branch, BranchType::Local).is_err() {
+        return Ok(());
+    }
+    let (prompt, default) = match unpushed_commits(repo, branch) {
+        Some(0) => (tr!("wt.delete_branch.confirm", branch), default),
+        Some(n) => (tr!("wt.delete_branch.confirm_unpushed", branch, n), false),
+        None => (tr!("wt.delete_branch.confirm_no_upstream", branch), false),
+    };
+    let confirm = match Confirm::new(&prompt).with_default(default).prompt() {
+        Ok(v) => v,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+        Err(e) => return Err(e.into()),
+    };
+    if confirm {
+        match delete_local_branch(repo, branch) {
+            Ok(()) => println!("{}", tr!("wt.delete_branch.done", branch)),
+            Err(e) => eprintln!("✗ {:#}", e),
+        }
+    }
+    Ok(())
+}
+
+/// `gp clean --json`：只输出判定结果，不删除。没有可清理的 worktree 时返回 `Outcome::Nothing`
+pub fn print_clean_plan_json(
+    repo: &Repository,
+    base: Option<&str>,
+    opts: CleanOptions,
+) -> Result<Outcome> {
+    let plan = plan_clean(repo, base, opts)?;
+    let skipped: Vec<_> = plan
+        .skipped
+        .iter()
+        .map(|(name, reason)| json!({ "name": name, "reason": reason }))
+        .collect();
+    let output = json!({ "removable": plan.removable, "skipped": skipped });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(if plan.removable.is_empty() {
+        Outcome::Nothing
+    } else {
+        Outcome::Done
+    })
+}
+
+/// `gp list`：按选择列表的顺序输出远端分支，文本模式每行一个分支名
+pub fn list_branches(
+    repo: &Repository,
+    config: &Config,
+    json: bool,
+    show_merged: bool,
+) -> Result<Outcome> {
+    let freq = FrequencyStore::load(&freq_path(repo));
+    let names = list_remote_branches(repo)?;
+    if names.is_empty() {
+        if json {
+            println!("[]");
+        }
+        return Ok(Outcome::Nothing);
+    }
+    let items = branch_items(repo, config, names, &freq, json, show_merged);
+
+    if !json {
+        for item in &items {
+            println!("{}", item.name);
+        }
+        return Ok(Outcome::Done);
+    }
+    let output: Vec<_> = items
+        .iter()
+        .map(|item| {
+            let mut entry = json!({
+                "name": item.name,
+                "label": item.label,
+                "pinned": item.pinned.is_some(),
+                "uses": item.count,
+                "last_used": (item.last_used > 0).then_some(item.last_used),
+                "last_commit": item.commit.as_ref().map(|c| json!({
+                    "summary": c.summary,
+                    "author": c.author,
+                })),
+            });
+            if show_merged {
+                entry["merged"] = json!(item.merged);
+            }
+            entry
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(Outcome::Done)
+}
+
+/// `gp stats`：频率文件中记录的基点分支使用次数和 worktree 统计
+pub fn print_stats(repo: &Repository, top: usize, json: bool) -> Result<Outcome> {
+    let freq = FrequencyStore::load(&freq_path(repo));
+    let mut bases = freq.base_usage();
+    bases.truncate(top);
+    let worktrees = freq.worktree_stats();
+
+    if json {
+        let output = json!({
+            "bases": bases,
+            "worktrees": worktrees,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(Outcome::Done);
+    }
+
+    if bases.is_empty() && worktrees.created == 0 && worktrees.removed == 0 {
+        println!("{}", tr!("stats.empty"));
+        return Ok(Outcome::Nothing);
+    }
+
+    if !bases.is_empty() {
+        println!("{}", tr!("stats.bases"));
+        let labels: Vec<String> = bases
+            .iter()
+            .map(|b| remote_branch_label(&b.branch))
+            .collect();
+        let width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        for (i, (base, label)) in bases.iter().zip(&labels).enumerate() {
+            let last_used = base.last_used.map(format_age).unwrap_or_default();
+            println!(
+                "  {:>2}. {}  {}  {}",
+                i + 1,
+                pad(label, width),
+                tr!(
+                    "stats.base_counts",
+                    base.branches + base.worktrees,
+                    base.branches,
+                    base.worktrees
+                ),
+                last_used
+            );
+        }
+        println!();
+    }
+
+    println!(
+        "{}",
+        tr!("stats.worktrees", worktrees.created, worktrees.removed)
+    );
+    match worktrees.average_lifetime_secs {
+        Some(secs) => println!("{}", tr!("stats.lifetime", format_duration(secs))),
+        None => println!("{}", tr!("stats.lifetime_unknown")),
+    }
+    Ok(Outcome::Done)
+}
+
+pub fn clean_worktrees(
+    repo: &Repository,
+    config: &Config,
+    base: Option<&str>,
+    opts: CleanOptions,
+    dry_run: bool,
+    yes: bool,
+) -> Result<Outcome> {
+    let total_worktrees = repo.worktrees()?.len();
+
+    if total_worktrees == 0 {
+        println!("{}", tr!("wt.none"));
+        return Ok(Outcome::Nothing);
+    }
+
+    match base {
+        Some(b) => println!("{}", tr!("clean.checking_base", total_worktrees, b)),
+        None => println!("{}", tr!("clean.checking", total_worktrees)),
+    }
+
+    let CleanPlan {
+        removable: to_remove,
+        skipped,
+    } = plan_clean(repo, base, opts)?;
+
+    if !skipped.is_empty() {
+        println!("{}", tr!("clean.skipped"));
+        for (name, reason) in &skipped {
+            println!("  ✗  {:<40} {}", name, reason);
+        }
+        println!();
+    }
+
+    if to_remove.is_empty() {
+        println!("{}", tr!("clean.none"));
+        return Ok(Outcome::Nothing);
+    }
+
+    println!("{}", tr!("clean.removable"));
+    for info in &to_remove {
+        println!(
+            "  •  {:<40} {:>9}  {}  {}",
+            info.name,
+            format_size(info.size),
+            pad(&info.last_used.map(format_age).unwrap_or_default(), 10),
+            info.path.display()
+        );
+    }
+    println!();
+
+    let total: u64 = to_remove.iter().map(|info| info.size).sum();
+    if dry_run {
+        let mut effects = Vec::new();
+        for info in &to_remove {
+            effects.extend(
+                config
+                    .hooks
+                    .pre_delete
+                    .iter()
+                    .map(|c| Effect::RunHook { command: c.clone() }),
+            );
+            effects.push(Effect::RemoveWorktree {
+                path: info.path.clone(),
+            });
+            if let Some(branch) = info.branch.as_ref().filter(|_| opts.delete_branches) {
+                effects.push(Effect::DeleteBranch {
+                    name: branch.clone(),
+                });
+            }
+        }
+        print_effects(&effects);
+        println!(
+            "{}",
+            tr!("clean.dry_run", to_remove.len(), format_size(total))
+        );
+        return Ok(Outcome::Done);
+    }
+    let prompt = if opts.delete_branches {
+        tr!(
+            "clean.confirm_with_branches",
+            to_remove.len(),
+            format_size(total)
+        )
+    } else {
+        tr!("clean.confirm", to_remove.len(), format_size(total))
+    };
+    let confirm = yes
+        || match Confirm::new(&prompt).with_default(false).prompt() {
+            Ok(v) => v,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+            Err(e) => return Err(e.into()),
+        };
+
+    if !confirm {
+        println!("{}", tr!("common.cancelled"));
+        return Ok(Outcome::Cancelled);
+    }
+
+    let report = remove_clean_candidates(repo, config, &to_remove, opts.delete_branches, true);
+    let branches_deleted = report.removed.iter().filter(|r| r.branch_deleted).count();
+    if opts.delete_branches {
+        println!(
+            "{}",
+            tr!(
+                "clean.done_with_branches",
+                report.removed.len(),
+                branches_deleted
+            )
+        );
+    } else {
+        println!("{}", tr!("clean.done", report.removed.len()));
+    }
+    report.outcome()
+}
+
+/// `gp clean --yes --json`：不询问直接删除，结束后以 JSON 输出删除、失败和跳过的条目，
+/// 进度和警告只写到 stderr
+pub fn clean_worktrees_json(
+    repo: &Repository,
+    config: &Config,
+    base: Option<&str>,
+    opts: CleanOptions,
+) -> Result<Outcome> {
+    let plan = plan_clean(repo, base, opts)?;
+    let report =
+        remove_clean_candidates(repo, config, &plan.removable, opts.delete_branches, false);
+    let skipped: Vec<_> = plan
+        .skipped
+        .iter()
+        .map(|(name, reason)| json!({ "name": name, "reason": reason }))
+        .collect();
+    let output = json!({
+        "removed": report.removed,
+        "failed": report.failed,
+        "skipped": skipped,
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    report.outcome()
+}
+
+/// 一次清理的实际结果
+#[derive(Default)]
+struct CleanReport {
+    removed: Vec<RemovedWorktree>,
+    failed: Vec<FailedWorktree>,
+}
+
+#[derive(Serialize)]
+struct RemovedWorktree {
+    name: String,
+    path: PathBuf,
+    /// 释放的字节数
+    size: u64,
+    branch: Option<String>,
+    /// 本地分支是否已一并删除
+    branch_deleted: bool,
+}
+
+#[derive(Serialize)]
+struct FailedWorktree {
+    name: String,
+    path: PathBuf,
+    reason: String,
+}
+
+impl CleanReport {
+    /// 全部删除成功为 `Done`，没有可删除的条目为 `Nothing`，有删除失败的条目时报错（退出码 1）
+    fn outcome(&self) -> Result<Outcome> {
+        if !self.failed.is_empty() {
+            bail!("{}", tr!("clean.some_failed", self.failed.len()));
+        }
+        Ok(if self.removed.is_empty() {
+            Outcome::Nothing
+        } else {
+            Outcome::Done
+        })
+    }
+}
+
+/// 逐个删除已判定可清理的 worktree；`verbose` 时在标准输出报告每一项，警告始终写到 stderr
+fn remove_clean_candidates(
+    repo: &Repository,
+    config: &Config,
+    candidates: &[CleanCandidate],
+    delete_branches: bool,
+    verbose: bool,
+) -> CleanReport {
+    let mut report = CleanReport::default();
+    for info in candidates {
+        let fail = |reason: String| FailedWorktree {
+            name: info.name.clone(),
+            path: info.path.clone(),
+            reason,
+        };
+        if !run_pre_delete(repo, config, &info.path, info.branch.as_deref()) {
+            report
+                .failed
+                .push(fail(tr!("clean.hook_failed").to_string()));
+            continue;
+        }
+        if let Err(e) = remove_worktree_dir(&info.path) {
+            eprintln!(
+                "{}",
+                tr!(
+                    "wt.delete.dir_failed",
+                    info.path.display(),
+                    format!("{:#}", e)
+                )
+            );
+            report.failed.push(fail(format!("{:#}", e)));
+            continue;
+        }
+        note_worktree_removed(repo, &info.path);
+        if let Err(e) = prune_worktree(repo, &info.name) {
+            eprintln!("{}", tr!("wt.delete.prune_failed", info.name, e));
+        }
+        if verbose {
+            println!("✓ {}  ({})", info.name, info.path.display());
+        }
+
+        // 可清理意味着没有未推送的提交，分支可以放心删除
+        let mut branch_deleted = false;
+        if delete_branches {
+            if let Some(branch) = &info.branch {
+                match delete_local_branch(repo, branch) {
+                    Ok(()) => {
+                        if verbose {
+                            println!("{}", tr!("clean.branch_deleted", branch));
+                        }
+                        branch_deleted = true;
+                    }
+                    Err(e) => eprintln!(
+                        "{}",
+                        tr!("clean.branch_delete_failed", branch, format!("{:#}", e))
+                    ),
+                }
+            } else if verbose {
+                println!("{}", tr!("clean.branch_detached"));
+            }
+        }
+        report.removed.push(RemovedWorktree {
+            name: info.name.clone(),
+            path: info.path.clone(),
+            size: info.size,
+            branch: info.branch.clone(),
+            branch_deleted,
+        });
+    }
+    report
+}
+
+/// 删除 worktree 前在其目录中执行 `pre_delete` 钩子；失败时提示并返回 false，调用方应保留该 worktree
+fn run_pre_delete(repo: &Repository, config: &Config, path: &Path, branch: Option<&str>) -> bool {
+    let commands = &config.hooks.pre_delete;
+    if commands.is_empty() {
+        return true;
+    }
+    let metas = BranchMetaStore::load(&meta_path(repo));
+    let base = branch.and_then(|b| metas.get(b)).map(|m| m.base.as_str());
+    let result = layout_root(repo).and_then(|repo_root| {
+        let ctx = HookContext {
+            worktree: path,
+            branch: branch.unwrap_or_default(),
+            base,
+            repo_root: &repo_root,
+        };
+        run_hooks("pre_delete", commands, &ctx)
+    });
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("✗ {:#}", e);
+            eprintln!("{}", tr!("wt.pre_delete_failed", path.display()));
+            false
+        }
+    }
+}
+
+/// 记下 worktree 已被删除，供 `gp stats` 统计；失败只提示，不影响删除结果
+fn note_worktree_removed(repo: &Repository, path: &Path) {
+    if let Err(e) = FrequencyStore::record_worktree_removed(&freq_path(repo), path) {
+        eprintln!("{}", tr!("common.warning", format!("{:#}", e)));
+    }
+}
+
+/// `--temp` 的收尾：子 Shell 退出后，worktree 仍干净且相对基点提交 `base` 没有未推送的新提交，
+/// 就连同分支一起删除，否则保留并说明原因
+pub fn finish_temp_worktree(
+    repo: &Repository,
+    config: &Config,
+    name: &str,
+    path: &Path,
+    base: Oid,
+) -> Result<()> {
+    let status = Repository::open(path)
+        .map(|wt_repo| temp_clean_status(&wt_repo, base))
+        .context(tr!("temp.open_failed"))?;
+    let locked = repo
+        .find_worktree(name)
+        .is_ok_and(|wt| lock_state(&wt).is_some());
+    let status = if locked {
+        WorktreeCleanStatus::Skip(tr!("skip.locked"))
+    } else {
+        status
+    };
+    if let WorktreeCleanStatus::Skip(reason) = status {
+        println!("{}", tr!("temp.kept", reason, path.display()));
+        println!("{}", tr!("temp.kept_hint"));
+        return Ok(());
+    }
+
+    if !run_pre_delete(repo, config, path, Some(name)) {
+        return Ok(());
+    }
+    remove_worktree_dir(path).with_context(|| tr!("temp.remove_dir_failed", path.display()))?;
+    note_worktree_removed(repo, path);
+    if let Err(e) = prune_worktree(repo, name) {
+        eprintln!("{}", tr!("wt.delete.prune_failed", name, e));
+    }
+    if let Err(e) = delete_local_branch(repo, name) {
+        eprintln!("{}", tr!("common.warning", format!("{:#}", e)));
+    }
+    println!("{}", tr!("temp.removed", name));
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+use std::io::Write;
+
+use git_branch_picker::config::Config;
+use git_branch_picker::git::{
+    layout_root, list_local_branches, list_remote_branches, open_repo, use_remote,
+};
+use git_branch_picker::tr;
+
+/// Shell 补全时 gp 被回调的环境变量，取值为 Shell 名
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// `gp completions` 支持的 Shell
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CompleteShell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+    Powershell,
+}
+
+/// 输出补全注册脚本。脚本在每次补全时以 `COMPLETE=<shell>` 回调当前的 gp 可执行文件，
+/// 所以分支名这类参数能按当前目录的仓库动态补全
+pub fn write_registration(shell: CompleteShell, out: &mut dyn Write) -> Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompleteShell::Bash => &Bash,
+        CompleteShell::Zsh => &Zsh,
+        CompleteShell::Fish => &Fish,
+        CompleteShell::Elvish => &Elvish,
+        CompleteShell::Powershell => &Powershell,
+    };
+    // 直接回调可执行文件的绝对路径，绕开 `gp shell-init` 定义的同名包装函数
+    let exe = std::env::current_exe().context(tr!("completions.exe_failed"))?;
+    completer
+        .write_registration(COMPLETE_VAR, "gp", "gp", &exe.to_string_lossy(), out)
+        .context(tr!("completions.write_failed"))?;
+    Ok(())
+}
+
+/// 当前目录所在仓库的远端分支，用于补全 `gp new <BRANCH>`、`--base` 等；不在仓库中时没有候选
+pub fn remote_branches() -> Vec<CompletionCandidate> {
+    let Ok(repo) = open_repo(None) else {
+        return Vec::new();
+    };
+    if let Some(name) = layout_root(&repo)
+        .ok()
+        .and_then(|root| Config::load(Some(&root)).ok())
+        .and_then(|config| config.remote)
+    {
+        use_remote(&name);
+    }
+    list_remote_branches(&repo)
+        .unwrap_or_default()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// 当前目录所在仓库的本地分支，用于补全 `gp l <KEYWORD>`
+pub fn local_branches() -> Vec<CompletionCandidate> {
+    open_repo(None)
+        .and_then(|repo| list_local_branches(&repo))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|b| CompletionCandidate::new(b.name))
+        .collect()
+}
@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::auth::default_ssh_keys;
+use crate::freq::FreqWeights;
+use crate::hooks::Hooks;
+use crate::issues::IssuesConfig;
+use crate::keymap::Keymap;
+use crate::layout::WorktreeLayout;
+use crate::messages::Lang;
+use crate::tmux::SwitchMode;
+use crate::tr;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    /// 固定显示在列表最上方的远端分支，按列表顺序排列
+    pub pinned: Vec<String>,
+    /// 列表中显示分支名时剥离的前缀（如 `users/`），多个匹配时取最长的
+    pub strip_prefixes: Vec<String>,
+    /// 默认基点远端分支，远端存在时跳过分支选择
+    pub default_base: Option<String>,
+    /// 记住上次选中分支时的过滤输入，下次启动时预填进过滤框
+    pub remember_filter: bool,
+    /// 排序时“创建分支”和“创建 worktree”两类计数的权重
+    pub freq_weights: FreqWeights,
+    /// worktree 路径布局：sibling / subdir / custom
+    pub worktree_layout: WorktreeLayout,
+    /// worktree_layout = "custom" 时使用的路径模板
+    pub worktree_path_template: Option<String>,
+    /// gp w 删除 worktree 后询问“是否一并删除本地分支”时的默认选项
+    pub delete_branch_with_worktree: bool,
+    /// gp clean 删除 worktree 后是否一并删除本地分支（命令行 --delete-branches / --keep-branches 优先）
+    pub clean_delete_branches: bool,
+    /// 远程仓库名，未设置时为 `origin`
+    pub remote: Option<String>,
+    /// 创建分支 / worktree 后直接推送新分支并把上游改为它（同 `--push`），不再询问
+    pub auto_push: bool,
+    /// 切换分支前自动 stash 未提交的改动，切换后再恢复（同 `--autostash`）
+    pub autostash: bool,
+    /// 新分支的上游：base（默认，追踪基点分支）、own（追踪同名远端分支）或 none
+    pub upstream: UpstreamMode,
+    /// 不允许 `gp rm-remote` 删除的远端分支，支持 glob，如 `release/*`；主分支总是受保护，推断不出时 main 和 master 都受保护
+    pub protected_branches: Vec<String>,
+    /// ssh-agent 之后依次尝试的 SSH 私钥，支持 `~/`；未设置时为 `~/.ssh/id_ed25519`、`id_ecdsa`、`id_rsa`
+    pub ssh_keys: Vec<PathBuf>,
+    /// 选好基点分支后、选择动作前显示的最近提交条数，0 表示不显示；未设置时为 3
+    pub preview_commits: Option<usize>,
+    /// 列出分支前先 fetch 远程仓库并清理已删除的分支（同 `--fetch`）
+    pub fetch_on_start: bool,
+    /// sibling / grouped 布局下存放 worktree 的目录，替代仓库父目录；相对路径相对于仓库根目录，支持 `~/`
+    pub worktree_dir: Option<PathBuf>,
+    /// 创建 worktree 后从主仓库工作区复制过去的未跟踪文件，支持 glob，如 `.env`
+    pub copy_files: Vec<String>,
+    /// 同 `copy_files`，但创建符号链接，各 worktree 共用同一份
+    pub link_files: Vec<String>,
+    /// `[hooks]`：创建 worktree 后执行的命令
+    pub hooks: Hooks,
+    /// `[issues]`：`--issue` 时拉取工单的来源
+    pub issues: IssuesConfig,
+    /// 新分支 / worktree 名称模板，默认 `{branch}-{timestamp}`
+    pub branch_name_template: Option<String>,
+    /// 创建 worktree 后是否进入其目录；未设置时每次询问
+    pub cd_after_create: Option<bool>,
+    /// 进入 worktree 的方式：子 Shell（默认）、新 tmux 窗口或 tmux 会话；不在 tmux 中时总是子 Shell
+    pub switch_mode: SwitchMode,
+    /// 打开 worktree 的编辑器命令，`{path}` 替换为 worktree 路径，如 `code -n {path}`；
+    /// 未设置时用 `$VISUAL` / `$EDITOR`
+    pub editor: Option<String>,
+    /// 关闭创建 worktree 前的磁盘剩余空间检查
+    pub skip_disk_space_check: bool,
+    /// 界面语言：zh / en，未设置时按 `LANG` 等环境变量推断，`--lang` 和 `GP_LANG` 优先于此项
+    pub lang: Option<Lang>,
+    /// 交互按键映射
+    pub keys: Keymap,
+    /// 仓库 `.gp.toml` 中出现、但只认全局配置而被忽略的项，见 `GLOBAL_ONLY_KEYS`
+    #[serde(skip)]
+    pub ignored_repo_keys: Vec<String>,
+}
+
+/// 只认全局配置的项：它们会执行命令、读取私钥或在仓库外建链接，而 `.gp.toml` 随仓库提交，
+/// 任何能推送到仓库的人都能改。写在 `.gp.toml` 中时忽略
+/// `issues.jira_url` 决定把 `JIRA_API_TOKEN` 发往哪里，同样只认全局配置
+pub const GLOBAL_ONLY_KEYS: &[&str] = &[
+    "hooks",
+    "editor",
+    "link_files",
+    "ssh_keys",
+    "issues.jira_url",
+];
+
+/// 新建分支 / worktree 时新分支的上游，配置项 `upstream`，`--upstream` 优先
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpstreamMode {
+    /// 追踪基点分支 `origin/<基点>`
+    #[default]
+    Base,
+    /// 追踪同名的 `origin/<新分支>`，它要到首次推送时才会创建
+    Own,
+    /// 不设置上游
+    #[serde(rename = "none")]
+    Unset,
+}
+
+impl Config {
+    /// 读取全局配置，再用仓库根目录下的 `.gp.toml`（如有）逐项覆盖，嵌套的表按键合并。
+    /// `.gp.toml` 中的 `GLOBAL_ONLY_KEYS` 不参与合并，记录在 `ignored_repo_keys` 中
+    pub fn load(repo_root: Option<&Path>) -> Result<Self> {
+        let mut merged = toml::Table::new();
+        if let Some(table) = config_path().map(|p| read_table(&p)).transpose()?.flatten() {
+            merge_tables(&mut merged, table);
+        }
+        let mut ignored = Vec::new();
+        let repo_config = repo_root.map(|root| root.join(REPO_CONFIG_NAME));
+        if let Some(mut table) = repo_config.map(|p| read_table(&p)).transpose()?.flatten() {
+            ignored = GLOBAL_ONLY_KEYS
+                .iter()
+                .filter(|key| remove_key(&mut table, key))
+                .map(|key| key.to_string())
+                .collect();
+            merge_tables(&mut merged, table);
+        }
+        let mut config: Self = toml::Value::Table(merged)
+            .try_into()
+            .context(tr!("config.invalid"))?;
+        config.keys.validate()?;
+        config.ignored_repo_keys = ignored;
+        Ok(config)
+    }
+
+    /// 解析 `worktree_dir`：展开开头的 `~/`，相对路径相对于仓库根目录
+    pub fn worktree_dir(&self, repo_root: &Path) -> Option<PathBuf> {
+        let dir = self.worktree_dir.as_deref()?;
+        Some(repo_root.join(expand_home(dir)))
+    }
+
+    /// 选择动作前预览的提交条数
+    pub fn preview_commits(&self) -> usize {
+        self.preview_commits.unwrap_or(3)
+    }
+
+    /// 远端分支是否匹配 `protected_branches`，写错的 glob 按字面比较
+    pub fn is_protected(&self, branch: &str) -> bool {
+        self.protected_branches
+            .iter()
+            .any(|p| match glob::Pattern::new(p) {
+                Ok(pattern) => pattern.matches(branch),
+                Err(_) => p == branch,
+            })
+    }
+
+    /// 要尝试的 SSH 私钥，已展开 `~/`；未配置时为默认的几个私钥
+    pub fn ssh_keys(&self) -> Vec<PathBuf> {
+        if self.ssh_keys.is_empty() {
+            return default_ssh_keys();
+        }
+        self.ssh_keys.iter().map(|k| expand_home(k)).collect()
+    }
+}
+
+/// 把开头的 `~` 展开为 `$HOME`
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// 仓库级配置文件名，放在仓库根目录
+pub const REPO_CONFIG_NAME: &str = ".gp.toml";
+
+fn read_table(path: &Path) -> Result<Option<toml::Table>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| tr!("config.read_failed", path.display()))?;
+    let table =
+        toml::from_str(&content).with_context(|| tr!("config.parse_failed", path.display()))?;
+    Ok(Some(table))
+}
+
+/// 删除 `key`（可用 `.` 指向嵌套表中的项），返回是否存在
+fn remove_key(table: &mut toml::Table, key: &str) -> bool {
+    match key.split_once('.') {
+        Some((head, rest)) => match table.get_mut(head) {
+            Some(toml::Value::Table(nested)) => remove_key(nested, rest),
+            _ => false,
+        },
+        None => table.remove(key).is_some(),
+    }
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(table)) => {
+                merge_tables(existing, table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/gp/config.toml`，未设置时为 `~/.config/gp/config.toml`
+pub fn config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("gp").join("config.toml"))
+}
@@ -0,0 +1,64 @@
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::path::Path;
+
+/// 检出前的空间预估：需要多少、目标分区还剩多少
+#[derive(Debug, Clone, Copy)]
+pub struct SpaceShortfall {
+    pub needed: u64,
+    pub available: u64,
+}
+
+/// 在 `target` 检出 `commit_oid` 前估算磁盘空间，不够时返回缺口；
+/// 估算或查询剩余空间失败时返回 `None`，不阻断创建流程
+pub fn check_space(repo: &Repository, commit_oid: Oid, target: &Path) -> Option<SpaceShortfall> {
+    let needed = checkout_size(repo, commit_oid)?;
+    let available = available_space(target)?;
+    (available < needed).then_some(SpaceShortfall { needed, available })
+}
+
+/// 提交树中所有文件大小之和，约等于检出后工作区的体积（不含子模块）
+pub fn checkout_size(repo: &Repository, commit_oid: Oid) -> Option<u64> {
+    let tree = repo.find_commit(commit_oid).ok()?.tree().ok()?;
+    let odb = repo.odb().ok()?;
+    let mut total = 0u64;
+    let mut failed = false;
+    tree.walk(TreeWalkMode::PreOrder, |_, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            match odb.read_header(entry.id()) {
+                Ok((size, _)) => total += size as u64,
+                Err(_) => {
+                    failed = true;
+                    return TreeWalkResult::Abort;
+                }
+            }
+        }
+        TreeWalkResult::Ok
+    })
+    .ok()?;
+    (!failed).then_some(total)
+}
+
+/// `path` 所在分区对当前用户可用的字节数；路径尚不存在时取最近的已存在祖先目录
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    statvfs_available(existing)
+}
+
+#[cfg(unix)]
+fn statvfs_available(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path 是合法的 C 字符串，stat 指向一块足够大的可写内存
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn statvfs_available(_path: &Path) -> Option<u64> {
+    None
+}
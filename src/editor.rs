@@ -0,0 +1,43 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::tr;
+
+/// 打开 worktree 用的编辑器命令：配置项 `editor` 优先，其次 `$VISUAL`、`$EDITOR`；都没有时返回 `None`
+pub fn editor_command(configured: Option<&str>) -> Option<String> {
+    configured
+        .map(str::to_string)
+        .into_iter()
+        .chain(
+            ["VISUAL", "EDITOR"]
+                .iter()
+                .filter_map(|v| std::env::var(v).ok()),
+        )
+        .find(|c| !c.trim().is_empty())
+}
+
+/// 用 `command` 打开 `path`。命令按空白拆分，其中的 `{path}` 替换为 worktree 路径，
+/// 没有 `{path}` 时把路径追加为最后一个参数，如 `code -n {path}`、`vim`。
+/// 等待命令退出：终端编辑器会占用当前终端，`code` 等图形编辑器会立即返回
+pub fn open_in_editor(command: &str, path: &Path) -> Result<()> {
+    let path_str = path.to_string_lossy();
+    let mut parts = command.split_whitespace();
+    let program = match parts.next() {
+        Some(p) => p,
+        None => bail!("{}", tr!("editor.empty")),
+    };
+    let mut args: Vec<String> = parts.map(|a| a.replace("{path}", &path_str)).collect();
+    if !command.contains("{path}") {
+        args.push(path_str.to_string());
+    }
+    let status = Command::new(program)
+        .args(&args)
+        .current_dir(path)
+        .status()
+        .with_context(|| tr!("editor.spawn_failed", program))?;
+    if !status.success() {
+        bail!("{}", tr!("editor.failed", command));
+    }
+    Ok(())
+}
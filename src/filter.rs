@@ -0,0 +1,38 @@
+use anyhow::{Context, Result};
+use glob::Pattern;
+use regex::Regex;
+
+use crate::tr;
+
+/// 启动时限定分支列表范围的模式：glob 须匹配完整分支名，正则只要有一处匹配即可（需要时自行加 `^` / `$`）
+pub enum BranchFilter {
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+impl BranchFilter {
+    pub fn glob(pattern: &str) -> Result<Self> {
+        let pattern = Pattern::new(pattern).with_context(|| tr!("filter.bad_glob", pattern))?;
+        Ok(Self::Glob(pattern))
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).with_context(|| tr!("filter.bad_regex", pattern))?;
+        Ok(Self::Regex(regex))
+    }
+
+    pub fn matches(&self, branch: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => pattern.matches(branch),
+            Self::Regex(regex) => regex.is_match(branch),
+        }
+    }
+
+    /// 原始模式文本，用于提示
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Glob(pattern) => pattern.as_str(),
+            Self::Regex(regex) => regex.as_str(),
+        }
+    }
+}
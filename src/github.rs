@@ -0,0 +1,235 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+
+use crate::pr::{api_get, url_host, ReviewRequest};
+
+/// remote URL 指向的 GitHub 仓库（github.com 或 GitHub Enterprise）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubRepo {
+    pub host: String,
+    pub owner: String,
+    pub name: String,
+}
+
+impl GitHubRepo {
+    /// 从 remote URL 取出 host、owner 和仓库名，支持 `https://`、`ssh://` 和 `git@host:owner/repo` 写法
+    pub fn from_url(url: &str) -> Option<Self> {
+        let host = url_host(url)?.to_ascii_lowercase();
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?.1,
+            None => url.split_once(':')?.1,
+        };
+        let mut segments = path
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .rsplit('/')
+            .filter(|s| !s.is_empty());
+        let name = segments.next()?.to_string();
+        let owner = segments.next()?.to_string();
+        Some(Self { host, owner, name })
+    }
+
+    /// `owner/repo`
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    /// 用于拉取的 HTTPS 地址
+    pub fn clone_url(&self) -> String {
+        format!("https://{}/{}/{}.git", self.host, self.owner, self.name)
+    }
+
+    fn api_base(&self) -> String {
+        if self.host == "github.com" {
+            "https://api.github.com".to_string()
+        } else {
+            format!("https://{}/api/v3", self.host)
+        }
+    }
+}
+
+/// 一个打开状态的 PR
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequest {
+    pub number: u32,
+    pub title: String,
+    pub user: PullRequestUser,
+    pub head: PullRequestHead,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+impl From<PullRequest> for ReviewRequest {
+    fn from(pr: PullRequest) -> Self {
+        Self {
+            number: pr.number,
+            title: pr.title,
+            author: pr.user.login,
+            source: pr.head.label,
+            draft: pr.draft,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestUser {
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestHead {
+    /// 形如 `someone:feature-x`，来自 fork 的 PR 前缀是 fork 的 owner
+    pub label: String,
+}
+
+#[derive(Deserialize)]
+struct RepoInfo {
+    #[serde(default)]
+    fork: bool,
+    parent: Option<RepoParent>,
+}
+
+#[derive(Deserialize)]
+struct RepoParent {
+    full_name: String,
+}
+
+/// PR 所在的仓库：`origin` 是 fork 时 PR 通常开在它的上游（parent）仓库，否则就是 `origin` 本身
+pub fn review_repo(origin: &GitHubRepo) -> Result<GitHubRepo> {
+    let info: RepoInfo = get(origin, &format!("/repos/{}", origin.full_name()))?;
+    let parent = info.parent.filter(|_| info.fork);
+    match parent.and_then(|p| {
+        p.full_name
+            .split_once('/')
+            .map(|(o, n)| (o.into(), n.into()))
+    }) {
+        Some((owner, name)) => {
+            debug!(fork = %origin.full_name(), parent = %format!("{}/{}", owner, name), "origin is a fork, using parent");
+            Ok(GitHubRepo {
+                host: origin.host.clone(),
+                owner,
+                name,
+            })
+        }
+        None => Ok(origin.clone()),
+    }
+}
+
+/// 列出仓库中打开的 PR，按创建时间从新到旧，最多 100 个
+pub fn open_pull_requests(repo: &GitHubRepo) -> Result<Vec<PullRequest>> {
+    get(
+        repo,
+        &format!(
+            "/repos/{}/pulls?state=open&sort=created&direction=desc&per_page=100",
+            repo.full_name()
+        ),
+    )
+}
+
+/// 按编号取一个 PR
+pub fn pull_request(repo: &GitHubRepo, number: u32) -> Result<PullRequest> {
+    get(
+        repo,
+        &format!("/repos/{}/pulls/{}", repo.full_name(), number),
+    )
+}
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+/// 仓库中分配给当前 token 用户的打开的 issue（不含 PR），返回编号和标题，按更新时间从新到旧，最多 100 个
+pub fn assigned_issues(repo: &GitHubRepo) -> Result<Vec<(u32, String)>> {
+    #[derive(Deserialize)]
+    struct Issue {
+        number: u32,
+        title: String,
+        // issues 接口也会返回 PR，PR 带有这个字段
+        pull_request: Option<serde::de::IgnoredAny>,
+    }
+
+    let user: User = get(repo, "/user")?;
+    let issues: Vec<Issue> = get(
+        repo,
+        &format!(
+            "/repos/{}/issues?state=open&assignee={}&sort=updated&per_page=100",
+            repo.full_name(),
+            user.login
+        ),
+    )?;
+    Ok(issues
+        .into_iter()
+        .filter(|i| i.pull_request.is_none())
+        .map(|i| (i.number, i.title))
+        .collect())
+}
+
+fn get<T: DeserializeOwned>(repo: &GitHubRepo, path: &str) -> Result<T> {
+    let url = format!("{}{}", repo.api_base(), path);
+    let auth = token(&repo.host).map(|t| ("Authorization", format!("Bearer {}", t)));
+    api_get(
+        "GitHub",
+        &url,
+        &[("Accept", "application/vnd.github+json")],
+        auth,
+        "GH_TOKEN / gh auth login",
+    )
+}
+
+/// 访问 `host` 用的 token：依次看环境变量 `GH_TOKEN`、`GITHUB_TOKEN`（GitHub Enterprise 为
+/// `GH_ENTERPRISE_TOKEN`、`GITHUB_ENTERPRISE_TOKEN`），再看 gh CLI 的 `hosts.yml`；都没有时匿名访问
+pub fn token(host: &str) -> Option<String> {
+    let vars: &[&str] = if host == "github.com" {
+        &["GH_TOKEN", "GITHUB_TOKEN"]
+    } else {
+        &["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+    vars.iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|t| !t.trim().is_empty())
+        .or_else(|| gh_config_token(host))
+}
+
+/// gh CLI 配置目录：`$GH_CONFIG_DIR`，否则 `$XDG_CONFIG_HOME/gh`、`~/.config/gh`（Windows 为 `%AppData%\GitHub CLI`）
+fn gh_config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GH_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("gh"));
+    }
+    if cfg!(windows) {
+        if let Some(dir) = std::env::var_os("AppData") {
+            return Some(PathBuf::from(dir).join("GitHub CLI"));
+        }
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("gh"))
+}
+
+/// 从 `hosts.yml` 中读出 `<host>:` 下的 `oauth_token`。gh 新版本默认把 token 存进系统钥匙串，
+/// 这时文件里没有 token，需要用环境变量提供
+fn gh_config_token(host: &str) -> Option<String> {
+    let content = fs::read_to_string(gh_config_dir()?.join("hosts.yml")).ok()?;
+    let mut in_host = false;
+    for line in content.lines() {
+        if !line.starts_with([' ', '\t']) {
+            in_host = line.trim_end().trim_end_matches(':') == host;
+            continue;
+        }
+        if !in_host {
+            continue;
+        }
+        if let Some(value) = line.trim().strip_prefix("oauth_token:") {
+            let value = value.trim().trim_matches(['"', '\'']);
+            if !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+
+use crate::pr::{api_get, url_host, ReviewRequest};
+
+/// remote URL 指向的 GitLab 项目（gitlab.com 或自建实例），`path` 含完整的组路径
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLabProject {
+    pub host: String,
+    /// 形如 `group/subgroup/project`
+    pub path: String,
+}
+
+impl GitLabProject {
+    /// 从 remote URL 取出 host 和项目路径，支持 `https://`、`ssh://` 和 `git@host:group/project` 写法
+    pub fn from_url(url: &str) -> Option<Self> {
+        let host = url_host(url)?.to_ascii_lowercase();
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?.1,
+            None => url.split_once(':')?.1,
+        };
+        let path = path.trim_matches('/').trim_end_matches(".git");
+        // 项目至少属于一个用户或组
+        if !path.contains('/') || path.split('/').any(str::is_empty) {
+            return None;
+        }
+        Some(Self {
+            host,
+            path: path.to_string(),
+        })
+    }
+
+    /// 用于拉取的 HTTPS 地址
+    pub fn clone_url(&self) -> String {
+        format!("https://{}/{}.git", self.host, self.path)
+    }
+
+    /// API 中的项目 id：URL 编码后的项目路径
+    fn api_base(&self) -> String {
+        format!(
+            "https://{}/api/v4/projects/{}",
+            self.host,
+            self.path.replace('/', "%2F")
+        )
+    }
+}
+
+/// 一个打开状态的 MR，`iid` 是项目内的编号
+#[derive(Debug, Clone, Deserialize)]
+pub struct MergeRequest {
+    pub iid: u32,
+    pub title: String,
+    pub author: MergeRequestAuthor,
+    pub source_branch: String,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MergeRequestAuthor {
+    pub username: String,
+}
+
+impl From<MergeRequest> for ReviewRequest {
+    fn from(mr: MergeRequest) -> Self {
+        Self {
+            number: mr.iid,
+            title: mr.title,
+            author: mr.author.username,
+            source: mr.source_branch,
+            draft: mr.draft,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ProjectInfo {
+    forked_from_project: Option<ForkParent>,
+}
+
+#[derive(Deserialize)]
+struct ForkParent {
+    path_with_namespace: String,
+}
+
+/// MR 所在的项目：`origin` 是 fork 时 MR 通常开在它的上游项目，否则就是 `origin` 本身
+pub fn review_project(origin: &GitLabProject) -> Result<GitLabProject> {
+    let info: ProjectInfo = get(origin, "")?;
+    match info.forked_from_project {
+        Some(parent) => {
+            debug!(fork = %origin.path, parent = %parent.path_with_namespace, "origin is a fork, using parent");
+            Ok(GitLabProject {
+                host: origin.host.clone(),
+                path: parent.path_with_namespace,
+            })
+        }
+        None => Ok(origin.clone()),
+    }
+}
+
+/// 列出项目中打开的 MR，按创建时间从新到旧，最多 100 个
+pub fn open_merge_requests(project: &GitLabProject) -> Result<Vec<MergeRequest>> {
+    get(
+        project,
+        "/merge_requests?state=opened&order_by=created_at&sort=desc&per_page=100",
+    )
+}
+
+/// 按编号取一个 MR
+pub fn merge_request(project: &GitLabProject, iid: u32) -> Result<MergeRequest> {
+    get(project, &format!("/merge_requests/{}", iid))
+}
+
+fn get<T: DeserializeOwned>(project: &GitLabProject, path: &str) -> Result<T> {
+    let url = format!("{}{}", project.api_base(), path);
+    let auth = token(&project.host).map(|t| ("PRIVATE-TOKEN", t));
+    api_get("GitLab", &url, &[], auth, "GITLAB_TOKEN / glab auth login")
+}
+
+/// 访问 `host` 用的 token：依次看环境变量 `GITLAB_TOKEN`、`GITLAB_ACCESS_TOKEN`，
+/// 再看 glab CLI 的 `config.yml`；都没有时匿名访问
+pub fn token(host: &str) -> Option<String> {
+    ["GITLAB_TOKEN", "GITLAB_ACCESS_TOKEN"]
+        .iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|t| !t.trim().is_empty())
+        .or_else(|| glab_config_token(host))
+}
+
+/// glab CLI 配置目录：`$GLAB_CONFIG_DIR`，否则 `$XDG_CONFIG_HOME/glab-cli`、`~/.config/glab-cli`
+/// （Windows 为 `%AppData%\glab-cli`）
+fn glab_config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GLAB_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("glab-cli"));
+    }
+    if cfg!(windows) {
+        if let Some(dir) = std::env::var_os("AppData") {
+            return Some(PathBuf::from(dir).join("glab-cli"));
+        }
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("glab-cli"))
+}
+
+/// 从 `config.yml` 的 `hosts:` 中读出 `<host>:` 下的 `token`
+fn glab_config_token(host: &str) -> Option<String> {
+    let content = fs::read_to_string(glab_config_dir()?.join("config.yml")).ok()?;
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    // 当前所在 host 块的缩进
+    let mut host_indent = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(level) = host_indent {
+            if indent(line) > level {
+                if let Some(value) = trimmed.strip_prefix("token:") {
+                    let value = value.trim().trim_matches(['"', '\'']);
+                    if !value.is_empty() {
+                        return Some(value.to_string());
+                    }
+                }
+                continue;
+            }
+            host_indent = None;
+        }
+        if indent(line) > 0 && trimmed.trim_end_matches(':') == host {
+            host_indent = Some(indent(line));
+        }
+    }
+    None
+}
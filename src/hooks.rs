@@ -0,0 +1,63 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::tr;
+
+/// 配置文件中的 `[hooks]`：每项是一条 shell 命令，按顺序执行
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Hooks {
+    /// 创建 worktree 后在其目录中执行，如 `npm ci`、`direnv allow`
+    pub post_create: Vec<String>,
+    /// 删除 worktree（`gp w`、`gp clean`、`--temp` 收尾）前在其目录中执行，如停掉以分支命名的容器
+    pub pre_delete: Vec<String>,
+}
+
+/// 传给钩子的环境变量：`GP_WORKTREE_PATH`、`GP_BRANCH`、`GP_BASE`（基点远端分支，可能为空）、`GP_REPO_ROOT`
+pub struct HookContext<'a> {
+    pub worktree: &'a Path,
+    pub branch: &'a str,
+    pub base: Option<&'a str>,
+    pub repo_root: &'a Path,
+}
+
+/// 在 `ctx.worktree` 中依次执行 `commands`，输出实时打到 stderr（保持 `gp new` 的标准输出干净）。
+/// 某条命令失败时停止，返回说明是哪条命令、退出码多少的错误
+pub fn run_hooks(stage: &str, commands: &[String], ctx: &HookContext) -> Result<()> {
+    for command in commands {
+        eprintln!("{}", tr!("hooks.running", stage, command));
+        let status = shell(command)
+            .current_dir(ctx.worktree)
+            .env("GP_WORKTREE_PATH", ctx.worktree)
+            .env("GP_BRANCH", ctx.branch)
+            .env("GP_BASE", ctx.base.unwrap_or_default())
+            .env("GP_REPO_ROOT", ctx.repo_root)
+            .stdout(Stdio::from(io::stderr()))
+            .status()
+            .with_context(|| tr!("hooks.spawn_failed", command))?;
+        if !status.success() {
+            match status.code() {
+                Some(code) => bail!("{}", tr!("hooks.failed", stage, command, code)),
+                None => bail!("{}", tr!("hooks.killed", stage, command)),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
@@ -0,0 +1,143 @@
+use anyhow::{bail, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use git2::Repository;
+use serde::Deserialize;
+
+use crate::git::remote;
+use crate::github;
+use crate::pr::{api_get, ReviewHost};
+use crate::tr;
+
+/// `jql` 未配置时查询的工单：分配给自己、未完成，最近更新的在前
+pub const DEFAULT_JQL: &str =
+    "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC";
+
+/// 工单来源
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueProvider {
+    /// origin（是 fork 时为其上游）仓库的 GitHub Issues
+    #[default]
+    GitHub,
+    Jira,
+}
+
+/// 配置文件中的 `[issues]`：`--issue` 时从哪里拉取分配给自己的工单
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct IssuesConfig {
+    pub provider: IssueProvider,
+    /// 不加 `--issue` 也在交互式创建分支 / worktree 前询问工单
+    pub prompt: bool,
+    /// Jira 站点地址，如 `https://example.atlassian.net`；只认全局配置，且必须是 https
+    pub jira_url: Option<String>,
+    /// Jira Cloud 的登录邮箱，与 API token 一起认证；不设置时 token 视为 Data Center 的 Personal Access Token
+    pub jira_email: Option<String>,
+    /// 查询工单的 JQL，默认见 `DEFAULT_JQL`
+    pub jql: Option<String>,
+}
+
+/// 一张工单：`key` 如 `PROJ-123`，GitHub Issues 为编号
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub key: String,
+    pub title: String,
+}
+
+/// 拉取分配给自己的未完成工单
+pub fn assigned_issues(repo: &Repository, config: &IssuesConfig) -> Result<Vec<Issue>> {
+    match config.provider {
+        IssueProvider::GitHub => {
+            let Some(ReviewHost::GitHub(origin)) = ReviewHost::from_remote(repo) else {
+                bail!("{}", tr!("issues.not_github", remote()));
+            };
+            let target = github::review_repo(&origin)?;
+            Ok(github::assigned_issues(&target)?
+                .into_iter()
+                .map(|(number, title)| Issue {
+                    key: number.to_string(),
+                    title,
+                })
+                .collect())
+        }
+        IssueProvider::Jira => jira_issues(config),
+    }
+}
+
+#[derive(Deserialize)]
+struct JiraSearch {
+    issues: Vec<JiraIssue>,
+}
+
+#[derive(Deserialize)]
+struct JiraIssue {
+    key: String,
+    fields: JiraFields,
+}
+
+#[derive(Deserialize)]
+struct JiraFields {
+    summary: String,
+}
+
+/// 用 JQL 搜索 Jira，token 取自环境变量 `JIRA_API_TOKEN`。
+/// Jira Cloud（配置了 `jira_email`）用 Basic 认证和 `/rest/api/3/search/jql`，
+/// Data Center 用 Bearer 认证和 `/rest/api/2/search`
+fn jira_issues(config: &IssuesConfig) -> Result<Vec<Issue>> {
+    let Some(base) = config.jira_url.as_deref().map(|u| u.trim_end_matches('/')) else {
+        bail!("{}", tr!("issues.jira_url_missing"));
+    };
+    // token 随请求发出，不允许明文传输
+    if !base
+        .get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+    {
+        bail!("{}", tr!("issues.jira_url_insecure", base));
+    }
+    let token = match std::env::var("JIRA_API_TOKEN") {
+        Ok(t) if !t.trim().is_empty() => t,
+        _ => bail!("{}", tr!("issues.jira_token_missing")),
+    };
+    let (endpoint, auth) = match &config.jira_email {
+        Some(email) => (
+            "/rest/api/3/search/jql",
+            format!("Basic {}", STANDARD.encode(format!("{}:{}", email, token))),
+        ),
+        None => ("/rest/api/2/search", format!("Bearer {}", token)),
+    };
+    let jql = config.jql.as_deref().unwrap_or(DEFAULT_JQL);
+    let url = format!(
+        "{}{}?jql={}&fields=summary&maxResults=100",
+        base,
+        endpoint,
+        query_encode(jql)
+    );
+    let found: JiraSearch = api_get(
+        "Jira",
+        &url,
+        &[("Accept", "application/json")],
+        Some(("Authorization", auth)),
+        "JIRA_API_TOKEN",
+    )?;
+    Ok(found
+        .issues
+        .into_iter()
+        .map(|i| Issue {
+            key: i.key,
+            title: i.fields.summary,
+        })
+        .collect())
+}
+
+/// 查询参数的百分号编码，只保留 RFC 3986 的非保留字符
+fn query_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
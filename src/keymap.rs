@@ -0,0 +1,201 @@
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::fmt;
+
+use crate::tr;
+
+/// 单个按键绑定，配置中写作 `"w"`、`"enter"`、`"ctrl+enter"`、`"alt+h"`、`"f2"` 等
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl KeyBinding {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        if ctrl != self.ctrl || alt != self.alt {
+            return false;
+        }
+        match (self.code, key.code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) => a.eq_ignore_ascii_case(&b),
+            (a, b) => a == b,
+        }
+    }
+
+    /// 不带 Ctrl / Alt 的可输入字符
+    fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && !self.ctrl && !self.alt
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut ctrl = false;
+        let mut alt = false;
+        let mut rest = value.trim().to_lowercase();
+        loop {
+            if let Some(r) = rest.strip_prefix("ctrl+") {
+                ctrl = true;
+                rest = r.to_string();
+            } else if let Some(r) = rest.strip_prefix("alt+") {
+                alt = true;
+                rest = r.to_string();
+            } else {
+                break;
+            }
+        }
+
+        let code = match rest.as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            s if s.chars().count() == 1 => KeyCode::Char(s.chars().next().unwrap_or(' ')),
+            s if s.starts_with('f') && matches!(s[1..].parse::<u8>(), Ok(1..=12)) => {
+                KeyCode::F(s[1..].parse().unwrap_or(1))
+            }
+            _ => return Err(tr!("keys.unknown", value)),
+        };
+        Ok(Self { code, ctrl, alt })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Enter => write!(f, "↵"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+fn keys(specs: &[&str]) -> Vec<KeyBinding> {
+    specs
+        .iter()
+        .filter_map(|s| KeyBinding::try_from(s.to_string()).ok())
+        .collect()
+}
+
+/// 提示文案中展示的按键，如 `w / Ctrl+↵`
+pub fn describe(bindings: &[KeyBinding]) -> String {
+    bindings
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// 交互按键映射，对应配置文件中的 `[keys]` 段；Ctrl+C 始终表示退出，不可配置
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    pub create_branch: Vec<KeyBinding>,
+    pub create_worktree: Vec<KeyBinding>,
+    pub preview: Vec<KeyBinding>,
+    pub cancel: Vec<KeyBinding>,
+    /// 分支列表中循环切换排序方式
+    pub sort: Vec<KeyBinding>,
+
+    pub cd: Vec<KeyBinding>,
+    /// gp w：在编辑器中打开
+    pub open_editor: Vec<KeyBinding>,
+    pub switch_branch: Vec<KeyBinding>,
+    pub delete: Vec<KeyBinding>,
+    /// gp w：勾选多个 worktree 一次删除
+    pub multi_delete: Vec<KeyBinding>,
+    pub keep: Vec<KeyBinding>,
+    pub lock: Vec<KeyBinding>,
+    /// gp w：把 worktree 移到新路径
+    pub move_worktree: Vec<KeyBinding>,
+    pub pull: Vec<KeyBinding>,
+    pub back: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            create_branch: keys(&["enter"]),
+            create_worktree: keys(&["w", "ctrl+enter"]),
+            preview: keys(&["tab", "p"]),
+            cancel: keys(&["esc", "q"]),
+            sort: keys(&["tab", "f2"]),
+
+            cd: keys(&["enter"]),
+            open_editor: keys(&["e"]),
+            switch_branch: keys(&["s"]),
+            delete: keys(&["d"]),
+            multi_delete: keys(&["x"]),
+            keep: keys(&["k"]),
+            lock: keys(&["l"]),
+            move_worktree: keys(&["m"]),
+            pull: keys(&["p"]),
+            back: keys(&["esc", "q"]),
+        }
+    }
+}
+
+impl Keymap {
+    /// 同一个提示中的不同动作不能绑定到同一个键；分支列表中的普通字符要留给过滤框
+    pub fn validate(&self) -> Result<()> {
+        if let Some(key) = self.sort.iter().find(|k| k.is_plain_char()) {
+            bail!("{}", tr!("keys.sort_plain_char", key));
+        }
+        check_conflicts(&[
+            ("create_branch", &self.create_branch),
+            ("create_worktree", &self.create_worktree),
+            ("preview", &self.preview),
+            ("cancel", &self.cancel),
+        ])?;
+        check_conflicts(&[
+            ("cd", &self.cd),
+            ("open_editor", &self.open_editor),
+            ("switch_branch", &self.switch_branch),
+            ("delete", &self.delete),
+            ("multi_delete", &self.multi_delete),
+            ("keep", &self.keep),
+            ("lock", &self.lock),
+            ("move_worktree", &self.move_worktree),
+            ("pull", &self.pull),
+            ("back", &self.back),
+        ])
+    }
+}
+
+fn check_conflicts(groups: &[(&str, &Vec<KeyBinding>)]) -> Result<()> {
+    for (i, (name_a, keys_a)) in groups.iter().enumerate() {
+        for (name_b, keys_b) in &groups[i + 1..] {
+            if let Some(key) = keys_a.iter().find(|k| keys_b.contains(k)) {
+                bail!("{}", tr!("keys.conflict", key, name_a, name_b));
+            }
+        }
+    }
+    Ok(())
+}
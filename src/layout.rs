@@ -0,0 +1,247 @@
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use serde::Deserialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::naming::unknown_placeholder;
+use crate::tr;
+
+/// subdir 布局下存放 worktree 的目录名
+pub const SUBDIR_NAME: &str = ".worktrees";
+
+/// custom 布局路径模板可用的占位符
+pub const PATH_PLACEHOLDERS: &[&str] = &[
+    "{repo_root}",
+    "{repo_parent}",
+    "{repo}",
+    "{name}",
+    "{branch}",
+    "{home}",
+    "{date}",
+];
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeLayout {
+    /// 仓库同级目录：`<仓库父目录>/<name>`
+    #[default]
+    Sibling,
+    /// 仓库内的 `.worktrees/<name>`
+    Subdir,
+    /// 同级的按仓库归组的目录：`<仓库父目录>/<仓库名>-worktrees/<name>`
+    Grouped,
+    /// 由 `worktree_path_template` 决定
+    Custom,
+}
+
+/// 根据布局计算 worktree 路径。
+///
+/// custom 模板支持的占位符：`{repo_root}` 仓库根目录、`{repo_parent}` 仓库父目录、
+/// `{repo}` 仓库名（同 grouped 布局的推断）、`{name}` / `{branch}` worktree 名称（即新分支名）、
+/// `{home}` 用户主目录、`{date}` 当前日期（`%Y%m%d`）；相对路径相对于仓库根目录解析。
+/// `base_dir` 给出时代替 sibling / grouped 布局中的仓库父目录；
+/// `origin_url` 只在 grouped 布局无法从目录名得到仓库名时使用
+pub fn resolve_worktree_path(
+    repo_root: &Path,
+    base_dir: Option<&Path>,
+    layout: WorktreeLayout,
+    template: Option<&str>,
+    origin_url: Option<&str>,
+    name: &str,
+) -> Result<PathBuf> {
+    match layout {
+        WorktreeLayout::Sibling => Ok(parent_dir(repo_root, base_dir)?.join(name)),
+        WorktreeLayout::Subdir => Ok(repo_root.join(SUBDIR_NAME).join(name)),
+        WorktreeLayout::Grouped => {
+            let parent_dir = parent_dir(repo_root, base_dir)?;
+            let repo_name =
+                infer_repo_name(repo_root, origin_url).context(tr!("layout.no_repo_name"))?;
+            Ok(parent_dir
+                .join(format!("{}-worktrees", repo_name))
+                .join(name))
+        }
+        WorktreeLayout::Custom => {
+            let template = match template {
+                Some(t) if !t.trim().is_empty() => t,
+                _ => bail!("{}", tr!("layout.template_required")),
+            };
+            check_path_template(template)?;
+            let repo_parent = repo_root.parent().unwrap_or(repo_root);
+            let repo_name = infer_repo_name(repo_root, origin_url)
+                .or_else(|| {
+                    repo_root
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                })
+                .unwrap_or_default();
+            let mut rendered = template
+                .replace("{repo_root}", &repo_root.to_string_lossy())
+                .replace("{repo_parent}", &repo_parent.to_string_lossy())
+                .replace("{repo}", &repo_name)
+                .replace("{name}", name)
+                .replace("{branch}", name)
+                .replace("{date}", &Local::now().format("%Y%m%d").to_string());
+            if rendered.contains("{home}") {
+                let home = std::env::var_os("HOME").context(tr!("layout.no_home"))?;
+                rendered = rendered.replace("{home}", &home.to_string_lossy());
+            }
+            Ok(repo_root.join(rendered))
+        }
+    }
+}
+
+/// 路径模板中有未知占位符时报错，启动时检查，避免选完分支才发现写错
+pub fn check_path_template(template: &str) -> Result<()> {
+    match unknown_placeholder(template, PATH_PLACEHOLDERS) {
+        Some(unknown) => bail!(
+            "{}",
+            tr!(
+                "layout.unknown_placeholder",
+                unknown,
+                PATH_PLACEHOLDERS.join(" ")
+            )
+        ),
+        None => Ok(()),
+    }
+}
+
+fn parent_dir<'a>(repo_root: &'a Path, base_dir: Option<&'a Path>) -> Result<&'a Path> {
+    match base_dir {
+        Some(dir) => Ok(dir),
+        None => repo_root.parent().context(tr!("layout.no_parent")),
+    }
+}
+
+/// 推断仓库名：优先取仓库根目录名（去掉裸仓库的 `.git` 后缀）；
+/// 目录名是 `.bare` 这类隐藏目录时改用 origin URL 的最后一段
+pub fn infer_repo_name(repo_root: &Path, origin_url: Option<&str>) -> Option<String> {
+    let dir_name = repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .map(|n| strip_git_suffix(&n).to_string())
+        .filter(|n| !n.is_empty() && !n.starts_with('.'));
+    dir_name.or_else(|| origin_url.and_then(repo_name_from_url))
+}
+
+/// 从 remote URL 取仓库名，兼容以下形式（`.git` 后缀和末尾的 `/` 可有可无）：
+/// `https://host/owner/repo.git`、`ssh://git@host:22/owner/repo`、
+/// `git@host:owner/repo.git`、`/srv/git/repo.git`、`file:///srv/git/repo`
+pub fn repo_name_from_url(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    // scp 形式 `user@host:path` 中冒号后才是路径；带 scheme 的 URL 按 `/` 切分即可
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url.rsplit_once(':').map_or(url, |(_, p)| p),
+    };
+    let last = path.rsplit(['/', '\\']).next()?;
+    let name = strip_git_suffix(last);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn strip_git_suffix(name: &str) -> &str {
+    name.strip_suffix(".git").unwrap_or(name)
+}
+
+/// 校验 `--worktree-path` 指定的目录：要么不存在，要么是空目录。相对路径相对于当前目录
+pub fn explicit_worktree_path(path: &Path) -> Result<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .context(tr!("layout.no_cwd"))?
+            .join(path)
+    };
+    if path.exists() {
+        if !path.is_dir() {
+            bail!("{}", tr!("layout.not_a_dir", path.display()));
+        }
+        let mut entries =
+            fs::read_dir(&path).with_context(|| tr!("layout.read_dir_failed", path.display()))?;
+        if entries.next().is_some() {
+            bail!("{}", tr!("layout.dir_not_empty", path.display()));
+        }
+    }
+    Ok(path)
+}
+
+/// 把 `/.worktrees/` 追加到仓库根目录的 .gitignore，避免主仓库 status 里出现 worktree 内容。
+/// 是否已被忽略由调用方用 `git::worktree_path_ignored` 判断，这里不再检查
+pub fn add_subdir_to_gitignore(repo_root: &Path) -> Result<()> {
+    let gitignore = repo_root.join(".gitignore");
+    let content = fs::read_to_string(&gitignore).unwrap_or_default();
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&gitignore)
+        .with_context(|| tr!("layout.write_failed", gitignore.display()))?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "/{}/", SUBDIR_NAME)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_name_from_url_forms() {
+        let cases = [
+            ("https://github.com/owner/repo.git", Some("repo")),
+            ("https://github.com/owner/repo", Some("repo")),
+            ("https://github.com/owner/repo/", Some("repo")),
+            ("https://github.com/owner/repo.git/", Some("repo")),
+            ("ssh://git@host:22/owner/repo.git", Some("repo")),
+            ("ssh://git@host/group/sub/repo", Some("repo")),
+            ("git@github.com:owner/repo.git", Some("repo")),
+            ("git@github.com:owner/repo", Some("repo")),
+            ("git@github.com:repo.git", Some("repo")),
+            ("/srv/git/repo.git", Some("repo")),
+            ("file:///srv/git/repo", Some("repo")),
+            (r"C:\repos\repo.git", Some("repo")),
+            ("  https://host/owner/repo.git  ", Some("repo")),
+            ("https://host/owner/.git", None),
+            ("", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(repo_name_from_url(url).as_deref(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn infer_repo_name_prefers_directory() {
+        let cases = [
+            ("/work/project", None, Some("project")),
+            ("/work/project.git", None, Some("project")),
+            (
+                "/work/project",
+                Some("git@host:owner/other.git"),
+                Some("project"),
+            ),
+            (
+                "/work/.bare",
+                Some("git@host:owner/other.git"),
+                Some("other"),
+            ),
+            ("/work/.bare", None, None),
+            (
+                "/work/.git",
+                Some("https://host/owner/other"),
+                Some("other"),
+            ),
+        ];
+        for (root, url, expected) in cases {
+            assert_eq!(
+                infer_repo_name(Path::new(root), url).as_deref(),
+                expected,
+                "{} {:?}",
+                root,
+                url
+            );
+        }
+    }
+}
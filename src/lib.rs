@@ -0,0 +1,22 @@
+//! gp 的应用层：配置、路径布局、命名模板、钩子、dry-run 描述等，建立在 `gp-core` 之上。
+//!
+//! 分支与 worktree 的核心操作在 `gp-core` 中，这里原样重新导出，`git_branch_picker::git`
+//! 等路径保持不变。这些函数同样只返回数据，不做交互；`gp` 二进制只是在其上加了一层命令行交互。
+
+pub use gp_core::{auth, error, freq, git, human, keep, messages, meta, progress, tr, worktree};
+
+pub mod config;
+pub mod disk;
+pub mod editor;
+pub mod filter;
+pub mod github;
+pub mod gitlab;
+pub mod hooks;
+pub mod issues;
+pub mod keymap;
+pub mod layout;
+pub mod naming;
+pub mod plan;
+pub mod pr;
+pub mod seed;
+pub mod tmux;
@@ -1,139 +1,935 @@
 mod cli;
-mod freq;
-mod git;
+mod commands;
+mod complete;
+mod outcome;
+mod picker;
+mod shell;
 mod ui;
-mod worktree;
 
-use anyhow::{Context, Result};
-use chrono::Local;
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use clap::FromArgMatches;
+use git2::{BranchType, Repository};
 use inquire::{Confirm, InquireError, Select, Text};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::debug;
+
+use git_branch_picker::auth;
+use git_branch_picker::config::{Config, UpstreamMode, REPO_CONFIG_NAME};
+use git_branch_picker::disk::check_space;
+use git_branch_picker::editor::{editor_command, open_in_editor};
+use git_branch_picker::error::GitError;
+use git_branch_picker::filter::BranchFilter;
+use git_branch_picker::freq::{freq_path, migrate_legacy, FrequencyStore, UseKind};
+use git_branch_picker::git::{
+    apply_stash_in, autostash_message, commit_times, create_and_checkout, create_and_checkout_at,
+    create_detached_worktree, create_worktree, create_worktree_at, default_branch,
+    delete_remote_branch, diagnose_empty_remote, drop_stash, fetch_remote, gone_branches,
+    layout_root, list_remote_branches, open_repo, push_branch, recent_commits, remote,
+    remote_branch_label, remote_ref, restore_stash, set_branch_description, stash_changes,
+    use_remote, worktree_is_dirty, worktree_path_ignored, EmptyRemote,
+};
+use git_branch_picker::hooks::{run_hooks, HookContext};
+use git_branch_picker::human::format_size;
+use git_branch_picker::issues::assigned_issues;
+use git_branch_picker::layout::{
+    add_subdir_to_gitignore, check_path_template, explicit_worktree_path, resolve_worktree_path,
+    WorktreeLayout, SUBDIR_NAME,
+};
+use git_branch_picker::messages;
+use git_branch_picker::meta::{meta_path, BranchMetaStore};
+use git_branch_picker::naming::{
+    check_template, is_valid_branch_name, ref_safe, render_name, slugify, SLUG_MAX_LEN,
+};
+use git_branch_picker::plan::{branch_effects, worktree_effects, Effect};
+use git_branch_picker::pr::{fetch_review_ref, review_local_ref, review_ref, ReviewHost};
+use git_branch_picker::seed::{plan_seed_files, seed_worktree, SeedFile};
+use git_branch_picker::tmux::SwitchMode;
+use git_branch_picker::tr;
+use git_branch_picker::worktree::{existing_worktree_for, CleanOptions};
 
 use cli::{Cli, Command};
-use freq::FrequencyStore;
-use git::{create_and_checkout, create_worktree, list_remote_branches, open_repo};
-use ui::{read_action, spawn_shell_in, Action, BranchItem};
-use worktree::{clean_worktrees, interactive_worktree_list};
+use commands::{
+    clean_worktrees, clean_worktrees_json, finish_temp_worktree, interactive_worktree_list,
+    list_branches, pick_local_branch, print_clean_plan_json, print_stats, print_status,
+    prune_gone_branches, repair_worktrees, DirtyPolicy,
+};
+use outcome::{Outcome, EXIT_ERROR};
+use picker::{BranchPicker, BranchScorer};
+use ui::{
+    branch_items, enter_worktree, install_panic_hook, pick_issue, pick_review_request,
+    print_commits, print_effects, prompt_branch_name, read_action, reapply_autostash, rich_scorer,
+    sort_items, spawn_shell_in, Action, BranchItem, SortMode, TerminalPrompt,
+};
+
+fn main() -> ExitCode {
+    install_panic_hook();
+    // 帮助和参数错误在打开仓库之前输出，先按 --lang、环境变量和全局配置确定语言，读到仓库配置后再修正
+    if let Some(lang) = cli::lang_arg() {
+        messages::force(lang);
+    }
+    messages::init(Config::load(None).ok().and_then(|config| config.lang));
+    // Shell 补全脚本以 COMPLETE=<shell> 回调 gp，此时输出候选后直接退出
+    clap_complete::CompleteEnv::with_factory(cli::command)
+        .var(complete::COMPLETE_VAR)
+        .complete();
+    // clap 默认以 2 退出，与“用户取消”冲突，这里统一改为一般错误
+    let parsed = cli::command()
+        .try_get_matches()
+        .and_then(|matches| Cli::from_arg_matches(&matches));
+    let cli = match parsed {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                ExitCode::from(EXIT_ERROR)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+    init_logging(cli.verbose);
+    match run(cli) {
+        Ok(outcome) => outcome.into(),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// `-v` 输出 debug 级日志，`-vv` 起输出 trace 级；日志写到 stderr，不影响脚本读取的标准输出
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => return,
+        1 => tracing::Level::DEBUG,
+        _ => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .init();
+}
+
+fn run(cli: Cli) -> Result<Outcome> {
+    if let Some(Command::ShellInit { shell }) = cli.command {
+        print!("{}", shell::init_script(shell));
+        return Ok(Outcome::Done);
+    }
+    if let Some(Command::Completions { shell }) = cli.command {
+        complete::write_registration(shell, &mut std::io::stdout())?;
+        return Ok(Outcome::Done);
+    }
+    // 标准输出只留给最终路径，列表和提示都改到 stderr，与 inquire 的提示一起显示在终端上；
+    // 输出供脚本读取的命令除外
+    if cli.print_path && !cli.command.as_ref().is_some_and(Command::machine_readable) {
+        shell::capture_stdout()?;
+    }
+    let mut repo = open_repo(cli.repo.as_deref())?;
+    let config = Config::load(Some(&layout_root(&repo)?))?;
+    messages::init(config.lang);
+    for key in &config.ignored_repo_keys {
+        eprintln!("{}", tr!("config.repo_key_ignored", REPO_CONFIG_NAME, key));
+    }
+    if let Some(name) = &config.remote {
+        use_remote(name);
+    }
+    auth::use_ssh_keys(config.ssh_keys());
+    auth::use_prompt(Box::new(TerminalPrompt));
+    if !cli.dry_run {
+        if let Err(e) = migrate_legacy(&repo) {
+            eprintln!("{}", tr!("freq.migrate_failed", format!("{:#}", e)));
+        }
+    }
+    let name_template = cli
+        .name_template
+        .as_deref()
+        .or(config.branch_name_template.as_deref());
+    if let Some(template) = name_template {
+        check_template(template)?;
+    }
+    if config.worktree_layout == WorktreeLayout::Custom {
+        if let Some(template) = &config.worktree_path_template {
+            check_path_template(template)?;
+        }
+    }
+
+    // 工单只用于生成新分支名：--issue，或配置了 issues.prompt 的交互式创建
+    let wants_issue = match &cli.command {
+        None => cli.issue || (config.issues.prompt && !cli.worktree && !cli.temp),
+        Some(Command::New { name: None, .. }) => cli.issue,
+        Some(_) => false,
+    };
+    let issue = if wants_issue && cli.from.is_none() {
+        let issues = assigned_issues(&repo, &config.issues)?;
+        if issues.is_empty() {
+            println!("{}", tr!("issues.none"));
+            return Ok(Outcome::Nothing);
+        }
+        match pick_issue(&issues)? {
+            Some(issue) => Some(issue.clone()),
+            None => {
+                println!("{}", tr!("common.cancelled"));
+                return Ok(Outcome::Cancelled);
+            }
+        }
+    } else {
+        None
+    };
 
     match cli.command {
-        Some(Command::W) => {
-            let repo = open_repo()?;
-            return interactive_worktree_list(&repo);
+        Some(Command::W { json: true }) => {
+            print_status(&repo, true)?;
+            return Ok(Outcome::Done);
+        }
+        Some(Command::W { json: false }) => {
+            return interactive_worktree_list(&repo, &config, cli.show_merged);
+        }
+        Some(Command::List { json }) => {
+            return list_branches(&repo, &config, json, cli.show_merged);
+        }
+        Some(Command::New {
+            ref branch,
+            worktree,
+            ref name,
+        }) => {
+            let new_branch = match name {
+                Some(name) if is_valid_branch_name(name) => name.clone(),
+                Some(name) => bail!("{}", tr!("main.invalid_branch_name", name)),
+                None => render_name(&repo, name_template, branch, issue.as_ref())?,
+            };
+            if cli.fetch {
+                fetch_before_listing(&repo, cli.dry_run);
+            }
+            return if worktree {
+                new_worktree(&mut repo, &cli, &config, branch, &new_branch)
+            } else {
+                new_branch_here(&mut repo, &cli, &config, branch, &new_branch)
+            };
+        }
+        Some(Command::RmRemote { ref branch, yes }) => {
+            if cli.fetch {
+                fetch_before_listing(&repo, cli.dry_run);
+            }
+            return remove_remote_branch(&repo, &cli, &config, branch.as_deref(), yes);
+        }
+        Some(Command::Prune) => {
+            if cli.fetch {
+                fetch_before_listing(&repo, cli.dry_run);
+            }
+            return prune_gone_branches(&repo, cli.dry_run);
+        }
+        // 已在开头处理，不需要打开仓库
+        Some(Command::ShellInit { .. }) | Some(Command::Completions { .. }) => {
+            return Ok(Outcome::Done)
         }
-        Some(Command::Clean) => {
-            let repo = open_repo()?;
-            return clean_worktrees(&repo);
+        Some(Command::Repair { paths }) => {
+            return repair_worktrees(&repo, &paths, cli.dry_run);
+        }
+        Some(Command::Stats { top, json }) => {
+            return print_stats(&repo, top, json);
+        }
+        Some(Command::L { keyword }) => {
+            let dirty = if cli.autostash || config.autostash {
+                DirtyPolicy::Autostash
+            } else {
+                DirtyPolicy::Confirm
+            };
+            return pick_local_branch(&mut repo, &config, keyword.as_deref(), dirty, cli.dry_run);
+        }
+        Some(Command::Switch { keyword }) => {
+            let dirty = if cli.autostash || config.autostash {
+                DirtyPolicy::Autostash
+            } else {
+                DirtyPolicy::Refuse
+            };
+            return pick_local_branch(&mut repo, &config, keyword.as_deref(), dirty, cli.dry_run);
+        }
+        Some(Command::Clean {
+            base,
+            include_untracked_merged,
+            delete_branches,
+            keep_branches,
+            older_than,
+            json,
+            yes,
+        }) => {
+            let opts = CleanOptions {
+                include_untracked_merged,
+                delete_branches: !keep_branches
+                    && (delete_branches || config.clean_delete_branches),
+                older_than,
+            };
+            if json && yes && !cli.dry_run {
+                return clean_worktrees_json(&repo, &config, base.as_deref(), opts);
+            }
+            if json {
+                return print_clean_plan_json(&repo, base.as_deref(), opts);
+            }
+            return clean_worktrees(&repo, &config, base.as_deref(), opts, cli.dry_run, yes);
+        }
+        Some(Command::Status { json }) => {
+            print_status(&repo, json)?;
+            return Ok(Outcome::Done);
+        }
+        Some(Command::Pr {
+            number,
+            branch,
+            ref_template,
+        }) => {
+            return create_pr_worktree(
+                &repo,
+                &config,
+                number,
+                branch,
+                ref_template.as_deref(),
+                cli.dry_run,
+            );
         }
         None => {}
     }
 
-    let repo = open_repo()?;
+    if let Some(rev) = &cli.from {
+        return create_detached(
+            &repo,
+            &config,
+            cli.worktree_path.as_deref(),
+            name_template,
+            rev,
+            cli.dry_run,
+        );
+    }
 
-    let freq_path = repo.path().join("branch-picker-freq.json");
-    let mut freq = FrequencyStore::load(&freq_path);
+    let filter = match (&cli.filter, &cli.regex) {
+        (Some(glob), _) => Some(BranchFilter::glob(glob)?),
+        (None, Some(re)) => Some(BranchFilter::regex(re)?),
+        (None, None) => None,
+    };
 
-    let branch_names = list_remote_branches(&repo)?;
+    let freq_path = freq_path(&repo);
+    let freq = FrequencyStore::load(&freq_path);
 
-    if branch_names.is_empty() {
-        eprintln!("origin 下没有找到任何远端分支。");
-        eprintln!("提示：先执行 `git fetch` 拉取最新分支信息。");
-        return Ok(());
+    if cli.fetch || config.fetch_on_start {
+        fetch_before_listing(&repo, cli.dry_run);
     }
 
-    let mut items: Vec<BranchItem> = branch_names
-        .into_iter()
-        .map(|name| {
-            let count = freq.count(&name);
-            BranchItem { name, count }
-        })
-        .collect();
+    let mut branch_names = list_remote_branches(&repo)?;
 
-    items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    if branch_names.is_empty() {
+        print_empty_remote_hint(&repo);
+        return Ok(Outcome::Nothing);
+    }
 
-    println!("找到 {} 个远端分支（按使用频率排序）\n", items.len());
+    if let Some(filter) = &filter {
+        branch_names.retain(|b| filter.matches(b));
+        if branch_names.is_empty() {
+            println!("{}", tr!("main.filter_no_match", filter.as_str()));
+            return Ok(Outcome::Nothing);
+        }
+    }
 
-    let selected = match Select::new("选择要基于的远端分支：", items)
-        .with_help_message("输入关键字过滤  ·  ↑↓ 移动  ·  Enter 确认  ·  Esc 取消")
-        .prompt()
-    {
-        Ok(item) => item,
-        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
-            println!("已取消。");
-            return Ok(());
+    // 配置了默认基点且远端存在时跳过选择
+    let base = cli.base.as_ref().or(config.default_base.as_ref());
+    let preselected = match base {
+        Some(b) if branch_names.contains(b) => Some(b.clone()),
+        Some(b) => {
+            eprintln!(
+                "{}",
+                tr!("main.default_base_missing", remote_branch_label(b))
+            );
+            None
         }
-        Err(e) => return Err(e.into()),
+        None => None,
     };
 
-    let branch_name = selected.name.clone();
+    let branch_name = match preselected {
+        Some(name) => {
+            println!("{}", tr!("main.based_on", remote_branch_label(&name)));
+            name
+        }
+        None => match pick_branch(
+            &repo,
+            &cli,
+            &config,
+            tr!("main.pick_base"),
+            branch_names,
+            &freq,
+        )? {
+            Some(name) => name,
+            None => {
+                println!("{}", tr!("common.cancelled"));
+                return Ok(Outcome::Cancelled);
+            }
+        },
+    };
 
-    let action = read_action()?;
+    let action = if cli.worktree || cli.temp {
+        Action::CreateWorktree
+    } else {
+        let preview = config.preview_commits();
+        if preview > 0 {
+            println!(
+                "{}",
+                tr!("main.recent_commits", remote_branch_label(&branch_name))
+            );
+            print_commits(&recent_commits(&repo, &branch_name, preview)?);
+            println!();
+        }
+        loop {
+            match read_action(&config.keys)? {
+                Action::Preview => {
+                    println!(
+                        "{}",
+                        tr!("main.recent_commits", remote_branch_label(&branch_name))
+                    );
+                    print_commits(&recent_commits(&repo, &branch_name, preview.max(5))?);
+                    println!();
+                }
+                action => break action,
+            }
+        }
+    };
 
     match action {
+        Action::Preview => unreachable!("预览在读取动作的循环中处理"),
+
         Action::Cancel => {
-            println!("已取消。");
-            return Ok(());
+            println!("{}", tr!("common.cancelled"));
+            return Ok(Outcome::Cancelled);
         }
 
         Action::CreateBranch => {
-            freq.increment(&branch_name);
-            freq.save(&freq_path)?;
+            if repo.is_bare() {
+                bail!("{}", tr!("main.bare_no_checkout"));
+            }
 
-            let timestamp = Local::now().format("%Y%m%d%H%M%S");
-            let new_branch = format!("{}-{}", branch_name, timestamp);
+            let default_name = render_name(&repo, name_template, &branch_name, issue.as_ref())?;
+            let mut new_branch =
+                match prompt_branch_name(tr!("main.branch_name"), &default_name, &default_name)? {
+                    Some(name) => name,
+                    None => {
+                        println!("{}", tr!("common.cancelled"));
+                        return Ok(Outcome::Cancelled);
+                    }
+                };
 
-            println!("\n正在创建分支 '{}' ...", new_branch);
-            create_and_checkout(&repo, &branch_name, &new_branch)?;
+            if cli.dry_run {
+                warn_if_branch_exists(&repo, &new_branch);
+                let stash = (cli.autostash || config.autostash) && worktree_is_dirty(&repo);
+                let upstream = upstream_for(&cli, &config, &branch_name, &new_branch);
+                let mut effects = branch_effects(&branch_name, &new_branch, upstream, stash);
+                if cli.push || config.auto_push {
+                    effects.push(Effect::Push { branch: new_branch });
+                }
+                print_effects(&effects);
+                return Ok(Outcome::Done);
+            }
 
-            println!("\n✓ 已切换到新分支：{}", new_branch);
-            println!("  追踪自：origin/{}", branch_name);
+            if let Some(outcome) =
+                offer_existing_worktree(&repo, &config, &new_branch, &branch_name)?
+            {
+                return Ok(outcome);
+            }
+
+            let stashed = loop {
+                println!("{}", tr!("main.creating_branch", new_branch));
+                let upstream = upstream_for(&cli, &config, &branch_name, &new_branch);
+                match checkout_new_branch(
+                    &mut repo,
+                    &branch_name,
+                    &new_branch,
+                    upstream,
+                    cli.autostash || config.autostash,
+                ) {
+                    Ok(stashed) => break stashed,
+                    // 名称冲突时让用户改名重试
+                    Err(e) if matches!(e.downcast_ref(), Some(GitError::BranchExists(_))) => {
+                        eprintln!("{}", tr!("main.pick_another_name", e));
+                        new_branch = match prompt_branch_name(
+                            tr!("main.branch_name"),
+                            &new_branch,
+                            &default_name,
+                        )? {
+                            Some(name) => name,
+                            None => {
+                                println!("{}", tr!("common.cancelled"));
+                                return Ok(Outcome::Cancelled);
+                            }
+                        };
+                    }
+                    Err(e) => return Err(e),
+                }
+            };
+
+            // 分支真正建好并切换过去后才计入使用频率，取消或失败不算
+            FrequencyStore::record(&freq_path, &branch_name, UseKind::Branch)?;
+            println!("{}", tr!("main.switched", new_branch));
+            println!(
+                "{}",
+                upstream_line(
+                    upstream_for(&cli, &config, &branch_name, &new_branch),
+                    &branch_name,
+                    &new_branch
+                )
+            );
+            record_origin(
+                &repo,
+                &new_branch,
+                &branch_name,
+                cli.description.as_deref(),
+                true,
+            )?;
+            if stashed {
+                reapply_autostash(&mut repo, &new_branch)?;
+            }
+
+            offer_push(&repo, &new_branch, cli.push || config.auto_push)?;
         }
 
         Action::CreateWorktree => {
-            freq.increment(&branch_name);
-            freq.save(&freq_path)?;
+            let default_name = render_name(&repo, name_template, &branch_name, issue.as_ref())?;
 
-            let timestamp = Local::now().format("%Y%m%d%H%M%S");
-            let default_name = format!("{}-{}", branch_name, timestamp);
+            // --worktree / --temp 时不做任何询问，直接使用默认名称
+            let interactive = !cli.worktree && !cli.temp;
+            // 临时 worktree 用完即删，不推送
+            let push = (cli.push || config.auto_push) && !cli.temp;
+            let mut initial_name = default_name.clone();
+            // 选择“继续新建”后不再重复询问
+            let mut offered = false;
+            let (new_branch, worktree_path, carried) = loop {
+                let new_branch = if !interactive {
+                    default_name.clone()
+                } else {
+                    match prompt_branch_name(
+                        tr!("main.worktree_name"),
+                        &initial_name,
+                        &default_name,
+                    )? {
+                        Some(name) => name,
+                        None => {
+                            println!("{}", tr!("common.cancelled"));
+                            return Ok(Outcome::Cancelled);
+                        }
+                    }
+                };
 
-            let new_branch = match Text::new("Worktree 名称：")
-                .with_initial_value(&default_name)
-                .prompt()
-            {
-                Ok(name) => {
-                    let name = name.trim().to_string();
-                    if name.is_empty() {
-                        default_name
-                    } else {
-                        name
+                if interactive && !cli.dry_run && !offered {
+                    offered = true;
+                    if let Some(outcome) =
+                        offer_existing_worktree(&repo, &config, &new_branch, &branch_name)?
+                    {
+                        return Ok(outcome);
                     }
                 }
-                Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
-                    println!("已取消。");
-                    return Ok(());
+
+                let upstream = upstream_for(&cli, &config, &branch_name, &new_branch);
+                if cli.dry_run {
+                    let mut effects = Vec::new();
+                    let worktree_path = worktree_path_for(
+                        &repo,
+                        &config,
+                        cli.worktree_path.as_deref(),
+                        &new_branch,
+                        Some(&mut effects),
+                    )?;
+                    warn_if_branch_exists(&repo, &new_branch);
+                    let carry = (cli.carry || cli.carry_untracked)
+                        .then_some(cli.carry_untracked)
+                        .filter(|_| worktree_is_dirty(&repo));
+                    let setup = setup_effects(&repo, &config, &worktree_path);
+                    effects.extend(worktree_effects(
+                        &branch_name,
+                        &new_branch,
+                        worktree_path,
+                        upstream,
+                        carry,
+                    ));
+                    if push {
+                        effects.push(Effect::Push { branch: new_branch });
+                    }
+                    effects.extend(setup);
+                    print_effects(&effects);
+                    return Ok(Outcome::Done);
+                }
+
+                let worktree_path = worktree_path_for(
+                    &repo,
+                    &config,
+                    cli.worktree_path.as_deref(),
+                    &new_branch,
+                    None,
+                )?;
+
+                if !confirm_disk_space(&repo, &config, &remote_ref(&branch_name), &worktree_path)? {
+                    println!("{}", tr!("common.cancelled"));
+                    return Ok(Outcome::Cancelled);
+                }
+
+                println!("{}", tr!("main.creating_worktree", new_branch));
+                println!("{}", tr!("main.path", worktree_path.display()));
+
+                let carried = if cli.carry || cli.carry_untracked {
+                    stash_changes(
+                        &mut repo,
+                        &format!("gp: carry to {}", new_branch),
+                        cli.carry_untracked,
+                    )?
+                } else {
+                    false
+                };
+
+                match create_worktree(&repo, &branch_name, &new_branch, &worktree_path, upstream) {
+                    Ok(()) => {
+                        FrequencyStore::record(&freq_path, &branch_name, UseKind::Worktree)?;
+                        FrequencyStore::record_worktree_created(&freq_path, &worktree_path)?;
+                        set_up_worktree(
+                            &repo,
+                            &config,
+                            &worktree_path,
+                            &new_branch,
+                            Some(&branch_name),
+                        );
+                        break (new_branch, worktree_path, carried);
+                    }
+                    Err(e) => {
+                        if carried {
+                            restore_stash(&mut repo)?;
+                        }
+                        // 名称冲突时让用户改名重试，其余错误直接退出
+                        if interactive && matches!(e, GitError::BranchExists(_)) {
+                            eprintln!("{}", tr!("main.pick_another_name", e));
+                            initial_name = new_branch;
+                            continue;
+                        }
+                        return Err(e.into());
+                    }
                 }
-                Err(e) => return Err(e.into()),
             };
 
-            let repo_root = repo
-                .workdir()
-                .context("裸仓库不支持创建 worktree")?
-                .to_path_buf();
-            let parent_dir = repo_root.parent().context("无法获取仓库父目录")?;
-            let worktree_path = parent_dir.join(&new_branch);
+            println!("{}", tr!("main.worktree_created"));
+            match upstream_for(&cli, &config, &branch_name, &new_branch) {
+                Some(upstream) => {
+                    println!(
+                        "{}",
+                        tr!(
+                            "main.worktree_branch",
+                            new_branch,
+                            remote_branch_label(upstream)
+                        )
+                    )
+                }
+                None => println!(
+                    "{}",
+                    tr!(
+                        "main.worktree_branch_no_track",
+                        new_branch,
+                        remote_branch_label(&branch_name)
+                    )
+                ),
+            }
+            println!("{}", tr!("main.path", worktree_path.display()));
+            record_origin(
+                &repo,
+                &new_branch,
+                &branch_name,
+                cli.description.as_deref(),
+                interactive,
+            )?;
+            if push {
+                push_new_branch(&repo, &new_branch);
+            }
+
+            if carried {
+                match apply_stash_in(&worktree_path) {
+                    Ok(()) => {
+                        drop_stash(&mut repo)?;
+                        println!("{}", tr!("main.changes_carried"));
+                    }
+                    Err(e) => {
+                        eprintln!("✗ {:#}", e);
+                        restore_stash(&mut repo)?;
+                        eprintln!("{}", tr!("main.changes_restored"));
+                    }
+                }
+            }
+
+            if cli.temp {
+                let base = Repository::open(&worktree_path)
+                    .and_then(|wt| wt.head()?.peel_to_commit().map(|c| c.id()))
+                    .context(tr!("temp.open_failed"))?;
+                spawn_shell_in(&worktree_path)?;
+                finish_temp_worktree(&repo, &config, &new_branch, &worktree_path, base)?;
+            } else if interactive {
+                offer_cd(&config, &worktree_path, &new_branch)?;
+            }
+        }
+    }
+
+    Ok(Outcome::Done)
+}
+
+/// 按使用频率排好序让用户选择基点分支，取消时返回 `None`
+fn pick_branch(
+    repo: &Repository,
+    cli: &Cli,
+    config: &Config,
+    prompt: &str,
+    branch_names: Vec<String>,
+    freq: &FrequencyStore,
+) -> Result<Option<String>> {
+    let items = branch_items(
+        repo,
+        config,
+        branch_names,
+        freq,
+        cli.rich_filter,
+        cli.show_merged,
+    );
+
+    println!("{}", tr!("main.found_branches", items.len()));
+
+    let scorer: BranchScorer = if cli.rich_filter {
+        &rich_scorer
+    } else {
+        Select::DEFAULT_SCORER
+    };
+
+    // 命令行关键字优先，其次是上次记住的输入；一个都匹配不上时不预填，直接显示全部
+    let remembered = config.remember_filter.then(|| freq.last_filter()).flatten();
+    let initial = cli
+        .keyword
+        .as_deref()
+        .or(remembered)
+        .filter(|kw| !kw.is_empty())
+        .filter(|kw| {
+            let any = items
+                .iter()
+                .enumerate()
+                .any(|(i, item)| scorer(kw, item, &item.label, i).is_some());
+            if !any {
+                println!("{}", tr!("main.no_keyword_match", kw));
+            }
+            any
+        });
+
+    // 第一次切到“按最近提交”时才读取各分支的提交时间
+    let resort = |mode: SortMode, items: &mut [BranchItem]| {
+        if mode == SortMode::Recent && items.iter().any(|i| i.committed_at.is_none()) {
+            let names: Vec<String> = items.iter().map(|i| i.name.clone()).collect();
+            for (item, time) in items.iter_mut().zip(commit_times(repo, &names)) {
+                item.committed_at = Some(time);
+            }
+        }
+        sort_items(mode, items);
+    };
+
+    let picker = BranchPicker {
+        prompt,
+        items,
+        filter: initial.unwrap_or_default().to_string(),
+        scorer,
+        sort: SortMode::Frequency,
+        sort_keys: &config.keys.sort,
+        resort: &resort,
+    };
+    let picked = match picker.prompt()? {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+    if config.remember_filter && !cli.dry_run {
+        FrequencyStore::remember_filter(&freq_path(repo), &picked.filter)?;
+    }
+    Ok(Some(picked.name))
+}
+
+/// `gp rm-remote`：确认后删除远端分支，拒绝主分支和 `protected_branches` 中的分支
+fn remove_remote_branch(
+    repo: &Repository,
+    cli: &Cli,
+    config: &Config,
+    branch: Option<&str>,
+    yes: bool,
+) -> Result<Outcome> {
+    let main = default_branch(repo);
+    // 推断不出主分支时，保守地把 main 和 master 都当作主分支
+    let is_main = |b: &str| match main.as_deref() {
+        Some(m) => m == b,
+        None => b == "main" || b == "master",
+    };
+    let protected = |b: &str| is_main(b) || config.is_protected(b);
+    let branch = match branch {
+        Some(b) if protected(b) => bail!("{}", tr!("rm_remote.protected", remote_branch_label(b))),
+        Some(b) => b.to_string(),
+        None => {
+            let mut names = list_remote_branches(repo)?;
+            names.retain(|b| !protected(b));
+            if names.is_empty() {
+                println!("{}", tr!("rm_remote.none"));
+                return Ok(Outcome::Nothing);
+            }
+            let freq = FrequencyStore::load(&freq_path(repo));
+            match pick_branch(repo, cli, config, tr!("rm_remote.prompt"), names, &freq)? {
+                Some(b) => b,
+                None => {
+                    println!("{}", tr!("common.cancelled"));
+                    return Ok(Outcome::Cancelled);
+                }
+            }
+        }
+    };
+    let label = remote_branch_label(&branch);
+    if repo.find_reference(&remote_ref(&branch)).is_err() {
+        bail!("{}", tr!("rm_remote.not_found", label));
+    }
+
+    if cli.dry_run {
+        print_effects(&[Effect::DeleteRemoteBranch {
+            remote: remote().to_string(),
+            name: branch,
+        }]);
+        return Ok(Outcome::Done);
+    }
+    if !yes {
+        let confirmed = match Confirm::new(&tr!("rm_remote.confirm", label))
+            .with_default(false)
+            .prompt()
+        {
+            Ok(v) => v,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if !confirmed {
+            println!("{}", tr!("common.cancelled"));
+            return Ok(Outcome::Cancelled);
+        }
+    }
+
+    println!("{}", tr!("rm_remote.deleting", label));
+    delete_remote_branch(repo, &branch)?;
+    println!("{}", tr!("rm_remote.deleted", label));
+    for local in gone_branches(repo)?
+        .into_iter()
+        .filter(|b| b.upstream == label)
+    {
+        println!("{}", tr!("rm_remote.tracking_hint", local.name));
+    }
+    Ok(Outcome::Done)
+}
+
+/// 列出分支前 fetch 远程仓库；失败时只提示，继续使用本地已有的远端分支。
+/// dry-run 不改动仓库，跳过 fetch
+fn fetch_before_listing(repo: &Repository, dry_run: bool) {
+    if dry_run {
+        println!("{}", tr!("main.fetch_skipped_dry_run", remote()));
+        return;
+    }
+    println!("{}", tr!("main.fetching_remote", remote()));
+    match fetch_remote(repo) {
+        Ok(stats) if stats.pruned > 0 => {
+            println!(
+                "{}",
+                tr!("main.fetched_pruned", stats.updated, stats.pruned)
+            )
+        }
+        Ok(stats) => println!("{}", tr!("main.fetched", stats.updated)),
+        Err(e) => {
+            eprintln!("✗ {:#}", e);
+            eprintln!("{}", tr!("main.fetch_failed_continue"));
+        }
+    }
+}
+
+/// 列表为空时按原因给出可以直接照做的命令
+fn print_empty_remote_hint(repo: &Repository) {
+    match diagnose_empty_remote(repo) {
+        EmptyRemote::NoRemote => {
+            eprintln!("{}", tr!("main.no_remotes"));
+            eprintln!("{}", tr!("main.no_remotes_hint", remote()));
+            eprintln!("  git remote add {} <url>", remote());
+            eprintln!("  git fetch {}", remote());
+        }
+        EmptyRemote::NoOrigin(remotes) => {
+            eprintln!("{}", tr!("main.no_origin", remote(), remotes.join(", ")));
+            eprintln!("{}", tr!("main.no_origin_hint", remote(), remote()));
+            eprintln!("  git remote rename {} {}", remotes[0], remote());
+        }
+        EmptyRemote::OriginWithoutUrl => {
+            eprintln!("{}", tr!("main.origin_no_url", remote()));
+            eprintln!("{}", tr!("main.origin_no_url_hint", remote()));
+        }
+        EmptyRemote::NeverFetched => {
+            eprintln!("{}", tr!("main.never_fetched", remote()));
+            eprintln!("{}", tr!("main.never_fetched_hint", remote()));
+        }
+        EmptyRemote::RemoteEmpty(current) => {
+            eprintln!("{}", tr!("main.origin_empty", remote()));
+            match current {
+                Some(branch) => {
+                    eprintln!("{}", tr!("main.push_current_hint", remote(), branch))
+                }
+                None => eprintln!("{}", tr!("main.push_any_hint", remote())),
+            }
+        }
+    }
+}
 
-            println!("\n正在创建 Worktree '{}'...", new_branch);
-            println!("  路径：{}", worktree_path.display());
+/// 新分支的上游：`--no-track`、`--track` 优先，其次 `--upstream`，最后是配置 `upstream`
+fn upstream_for<'a>(
+    cli: &'a Cli,
+    config: &Config,
+    base: &'a str,
+    new_branch: &'a str,
+) -> Option<&'a str> {
+    if cli.no_track {
+        return None;
+    }
+    if let Some(track) = &cli.track {
+        return Some(track);
+    }
+    match cli.upstream.unwrap_or(config.upstream) {
+        UpstreamMode::Base => Some(base),
+        UpstreamMode::Own => Some(new_branch),
+        UpstreamMode::Unset => None,
+    }
+}
 
-            create_worktree(&repo, &branch_name, &new_branch, &worktree_path)?;
+/// 切换到新分支后显示的上游说明
+fn upstream_line(upstream: Option<&str>, base: &str, new_branch: &str) -> String {
+    match upstream {
+        Some(upstream) if upstream == new_branch => {
+            tr!("main.tracking_own", remote_branch_label(upstream))
+        }
+        Some(upstream) => tr!("main.tracking", remote_branch_label(upstream)),
+        None => tr!("main.no_upstream", remote_branch_label(base)),
+    }
+}
 
-            println!("\n✓ Worktree 已创建");
-            println!("  分支：{}  追踪自：origin/{}", new_branch, branch_name);
-            println!("  路径：{}", worktree_path.display());
+/// 创建并切换到新分支。`autostash` 时先暂存改动；未暂存而检出冲突时询问是否暂存后重试。
+/// 返回是否暂存了改动，失败时已暂存的改动会被恢复
+fn checkout_new_branch(
+    repo: &mut Repository,
+    base: &str,
+    new_branch: &str,
+    upstream: Option<&str>,
+    autostash: bool,
+) -> Result<bool> {
+    let mut stashed = autostash && stash_changes(repo, &autostash_message(new_branch), false)?;
+    if stashed {
+        println!("{}", tr!("main.autostashed"));
+    }
 
-            let should_cd = match Confirm::new("是否切换到 worktree 目录？")
+    loop {
+        let err = match create_and_checkout(repo, base, new_branch, upstream) {
+            Ok(()) => return Ok(stashed),
+            Err(e) => e,
+        };
+        if matches!(err, GitError::CheckoutConflict) && !stashed {
+            eprintln!(
+                "{}",
+                tr!("main.checkout_conflict", remote_branch_label(base))
+            );
+            let retry = match Confirm::new(tr!("main.stash_and_retry"))
                 .with_default(true)
                 .prompt()
             {
@@ -143,12 +939,632 @@ fn main() -> Result<()> {
                 }
                 Err(e) => return Err(e.into()),
             };
-
-            if should_cd {
-                spawn_shell_in(&worktree_path)?;
+            if retry && stash_changes(repo, &autostash_message(new_branch), false)? {
+                stashed = true;
+                println!("{}", tr!("main.stashed"));
+                continue;
             }
         }
+        if stashed {
+            restore_stash(repo)?;
+        }
+        return Err(err.into());
     }
+}
+
+/// `explicit` 为 `--worktree-path` 指定的目录，优先于布局配置。
+/// 路径落在主仓库工作区内且未被忽略时：subdir 布局自动把 `/.worktrees/` 写入 .gitignore，
+/// 其它情况只提示。`dry_run` 不为空时，写 .gitignore 的改动只记录进去而不执行
+fn worktree_path_for(
+    repo: &Repository,
+    config: &Config,
+    explicit: Option<&Path>,
+    name: &str,
+    dry_run: Option<&mut Vec<Effect>>,
+) -> Result<PathBuf> {
+    let repo_root = layout_root(repo)?;
+    let path = match explicit {
+        Some(path) => explicit_worktree_path(path)?,
+        None => {
+            let origin_url = repo
+                .find_remote(remote())
+                .ok()
+                .and_then(|r| r.url().map(str::to_string));
+            resolve_worktree_path(
+                &repo_root,
+                config.worktree_dir(&repo_root).as_deref(),
+                config.worktree_layout,
+                config.worktree_path_template.as_deref(),
+                origin_url.as_deref(),
+                name,
+            )?
+        }
+    };
 
+    if worktree_path_ignored(repo, &path)? != Some(false) {
+        return Ok(path);
+    }
+    if explicit.is_some() || config.worktree_layout != WorktreeLayout::Subdir {
+        let rel = path.strip_prefix(&repo_root).unwrap_or(&path);
+        eprintln!(
+            "{}",
+            tr!("main.path_not_ignored", path.display(), rel.display())
+        );
+        return Ok(path);
+    }
+    match dry_run {
+        Some(effects) => effects.push(Effect::IgnoreSubdir {
+            gitignore: repo_root.join(".gitignore"),
+        }),
+        None => {
+            add_subdir_to_gitignore(&repo_root)?;
+            eprintln!("{}", tr!("main.gitignore_added", SUBDIR_NAME));
+        }
+    }
+    Ok(path)
+}
+
+/// 检出前估算所需空间，目标分区不够时让用户确认是否继续。
+/// 配置关闭检查、无法解析 `rev` 或查询失败时直接放行
+fn confirm_disk_space(
+    repo: &Repository,
+    config: &Config,
+    rev: &str,
+    worktree_path: &Path,
+) -> Result<bool> {
+    if config.skip_disk_space_check {
+        return Ok(true);
+    }
+    let commit_oid = match repo.revparse_single(rev).and_then(|o| o.peel_to_commit()) {
+        Ok(commit) => commit.id(),
+        Err(_) => return Ok(true),
+    };
+    let shortfall = match check_space(repo, commit_oid, worktree_path) {
+        Some(s) => s,
+        None => return Ok(true),
+    };
+
+    eprintln!(
+        "{}",
+        tr!(
+            "main.low_disk",
+            format_size(shortfall.needed),
+            format_size(shortfall.available)
+        )
+    );
+    match Confirm::new(tr!("main.continue_anyway"))
+        .with_default(false)
+        .prompt()
+    {
+        Ok(yes) => Ok(yes),
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `gp new` 不带 `--worktree`：在当前工作区创建并切换到新分支，不做任何询问。
+/// 标准输出只打印新分支名，其余提示写到 stderr，方便脚本捕获
+fn new_branch_here(
+    repo: &mut Repository,
+    cli: &Cli,
+    config: &Config,
+    base: &str,
+    new_branch: &str,
+) -> Result<Outcome> {
+    if repo.is_bare() {
+        bail!("{}", tr!("main.bare_no_checkout"));
+    }
+    let push = cli.push || config.auto_push;
+    if cli.dry_run {
+        warn_if_branch_exists(repo, new_branch);
+        let stash = (cli.autostash || config.autostash) && worktree_is_dirty(repo);
+        let upstream = upstream_for(cli, config, base, new_branch);
+        let mut effects = branch_effects(base, new_branch, upstream, stash);
+        if push {
+            effects.push(Effect::Push {
+                branch: new_branch.to_string(),
+            });
+        }
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+
+    let stashed = (cli.autostash || config.autostash)
+        && stash_changes(repo, &autostash_message(new_branch), false)?;
+    let upstream = upstream_for(cli, config, base, new_branch);
+    if let Err(e) = create_and_checkout(repo, base, new_branch, upstream) {
+        if stashed {
+            restore_stash(repo)?;
+        }
+        if matches!(e, GitError::CheckoutConflict) {
+            bail!(
+                "{}",
+                tr!("main.new_checkout_conflict", remote_branch_label(base))
+            );
+        }
+        return Err(e.into());
+    }
+    FrequencyStore::record(&freq_path(repo), base, UseKind::Branch)?;
+    record_origin(repo, new_branch, base, cli.description.as_deref(), false)?;
+    if stashed {
+        reapply_autostash(repo, new_branch)?;
+    }
+    if push {
+        push_new_branch(repo, new_branch);
+    }
+    println!("{}", new_branch);
+    Ok(Outcome::Done)
+}
+
+/// `gp new --worktree`：不做任何询问地创建 worktree，磁盘空间不足时直接报错。
+/// 标准输出只打印 worktree 路径，其余提示写到 stderr
+fn new_worktree(
+    repo: &mut Repository,
+    cli: &Cli,
+    config: &Config,
+    base: &str,
+    new_branch: &str,
+) -> Result<Outcome> {
+    let explicit_path = cli.worktree_path.as_deref();
+    let upstream = upstream_for(cli, config, base, new_branch);
+    let push = cli.push || config.auto_push;
+    if cli.dry_run {
+        let mut effects = Vec::new();
+        let path = worktree_path_for(repo, config, explicit_path, new_branch, Some(&mut effects))?;
+        warn_if_branch_exists(repo, new_branch);
+        let setup = setup_effects(repo, config, &path);
+        effects.extend(worktree_effects(base, new_branch, path, upstream, None));
+        if push {
+            effects.push(Effect::Push {
+                branch: new_branch.to_string(),
+            });
+        }
+        effects.extend(setup);
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+
+    let path = worktree_path_for(repo, config, explicit_path, new_branch, None)?;
+    if !config.skip_disk_space_check {
+        let commit = repo
+            .revparse_single(&remote_ref(base))
+            .and_then(|o| o.peel_to_commit());
+        if let Some(shortfall) = commit.ok().and_then(|c| check_space(repo, c.id(), &path)) {
+            bail!(
+                "{}",
+                tr!(
+                    "main.low_disk",
+                    format_size(shortfall.needed),
+                    format_size(shortfall.available)
+                )
+            );
+        }
+    }
+
+    create_worktree(repo, base, new_branch, &path, upstream)?;
+    FrequencyStore::record(&freq_path(repo), base, UseKind::Worktree)?;
+    FrequencyStore::record_worktree_created(&freq_path(repo), &path)?;
+    set_up_worktree(repo, config, &path, new_branch, Some(base));
+    record_origin(repo, new_branch, base, cli.description.as_deref(), false)?;
+    if push {
+        push_new_branch(repo, new_branch);
+    }
+    println!("{}", path.display());
+    Ok(Outcome::Done)
+}
+
+/// dry-run 时提前指出实际执行会因同名分支而失败
+fn warn_if_branch_exists(repo: &Repository, name: &str) {
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        eprintln!("{}", tr!("main.branch_exists_warn", name));
+    }
+}
+
+/// 记录新分支的来历并写入分支说明：`description` 为 `--description` 给出的说明，
+/// 没有给出且 `ask` 时询问一句（Esc 或留空跳过）
+fn record_origin(
+    repo: &Repository,
+    branch: &str,
+    base: &str,
+    description: Option<&str>,
+    ask: bool,
+) -> Result<()> {
+    let description = match description {
+        Some(text) => Some(text.to_string()),
+        None if ask => match Text::new(tr!("main.note")).prompt() {
+            Ok(s) if !s.trim().is_empty() => Some(s),
+            Ok(_) => None,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => None,
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+    if let Some(text) = &description {
+        set_branch_description(repo, branch, text)?;
+    }
+
+    let path = meta_path(repo);
+    let mut store = BranchMetaStore::load(&path);
+    store.insert(branch, base, None);
+    store.save(&path)
+}
+
+/// 询问是否推送新分支，`skip_confirm`（`--push` 或 `auto_push`）时直接推送
+fn offer_push(repo: &Repository, branch: &str, skip_confirm: bool) -> Result<()> {
+    let should_push = skip_confirm
+        || match Confirm::new(&tr!("main.push_confirm", remote()))
+            .with_default(false)
+            .prompt()
+        {
+            Ok(v) => v,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+            Err(e) => return Err(e.into()),
+        };
+    if should_push {
+        push_new_branch(repo, branch);
+    }
     Ok(())
 }
+
+/// 把新分支推送到远端，并把上游改为推送后的远端分支（而不是基点分支）。
+/// 提示写到 stderr，保持 `gp new` 的标准输出干净；推送失败只提示，不影响已创建好的分支
+fn push_new_branch(repo: &Repository, branch: &str) {
+    eprintln!("{}", tr!("main.pushing", branch, remote()));
+    match push_branch(repo, branch) {
+        Ok(()) => eprintln!("{}", tr!("main.pushed", remote_branch_label(branch))),
+        Err(e) => {
+            eprintln!("✗ {:#}", e);
+            eprintln!("{}", tr!("main.push_later_hint", remote(), branch));
+        }
+    }
+}
+
+/// 已有 worktree 检出了 `branch`，或派生自同一远端分支 `base` 时，询问是进入它还是继续新建。
+/// 进入或取消时返回对应的结果，继续新建（或没有这样的 worktree）时返回 `None`
+fn offer_existing_worktree(
+    repo: &Repository,
+    config: &Config,
+    branch: &str,
+    base: &str,
+) -> Result<Option<Outcome>> {
+    let Some(existing) = existing_worktree_for(repo, branch, base)? else {
+        return Ok(None);
+    };
+    if existing.branch == branch {
+        println!(
+            "{}",
+            tr!("main.existing.checked_out", branch, existing.path.display())
+        );
+    } else {
+        println!(
+            "{}",
+            tr!(
+                "main.existing.from_base",
+                remote_branch_label(base),
+                existing.branch,
+                existing.path.display()
+            )
+        );
+    }
+    let options = vec![tr!("main.existing.jump"), tr!("main.existing.create")];
+    match Select::new(tr!("main.existing.prompt"), options).raw_prompt() {
+        Ok(choice) if choice.index == 0 => {
+            enter_worktree(config.switch_mode, &existing.path, &existing.branch)?;
+            Ok(Some(Outcome::Done))
+        }
+        Ok(_) => Ok(None),
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            println!("{}", tr!("common.cancelled"));
+            Ok(Some(Outcome::Cancelled))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 创建完成后询问进入 worktree 目录、在编辑器中打开还是都不做（没有可用的编辑器时只问是否进入）；
+/// 配置了 `cd_after_create` 时不再询问。进入的方式见 `switch_mode`，tmux 窗口 / 会话以 `name` 命名
+fn offer_cd(config: &Config, worktree_path: &Path, name: &str) -> Result<()> {
+    if let Some(should_cd) = config.cd_after_create {
+        if should_cd {
+            enter_worktree(config.switch_mode, worktree_path, name)?;
+        }
+        return Ok(());
+    }
+
+    let Some(editor) = editor_command(config.editor.as_deref()) else {
+        let should_cd = match Confirm::new(tr!("main.cd_confirm"))
+            .with_default(true)
+            .prompt()
+        {
+            Ok(v) => v,
+            Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if should_cd {
+            enter_worktree(config.switch_mode, worktree_path, name)?;
+        }
+        return Ok(());
+    };
+
+    let enter = match config.switch_mode {
+        SwitchMode::Subshell => tr!("main.after.cd"),
+        SwitchMode::TmuxWindow => tr!("main.after.tmux_window"),
+        SwitchMode::TmuxSession => tr!("main.after.tmux_session"),
+    };
+    let options = vec![
+        enter.to_string(),
+        tr!("main.after.editor", editor),
+        tr!("main.after.nothing").to_string(),
+    ];
+    match Select::new(tr!("main.after.prompt"), options).raw_prompt() {
+        Ok(choice) if choice.index == 0 => enter_worktree(config.switch_mode, worktree_path, name)?,
+        Ok(choice) if choice.index == 1 => open_in_editor(&editor, worktree_path)?,
+        Ok(_) => {}
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+/// 配置了 `copy_files` / `link_files` 时要带进 worktree 的文件；展开失败只提示，不影响创建
+fn seed_files(repo: &Repository, config: &Config) -> Option<(PathBuf, Vec<SeedFile>)> {
+    if config.copy_files.is_empty() && config.link_files.is_empty() {
+        return None;
+    }
+    let source = layout_root(repo).ok()?;
+    match plan_seed_files(&source, &config.copy_files, &config.link_files) {
+        Ok(files) => Some((source, files)),
+        Err(e) => {
+            eprintln!("{}", tr!("common.warning", format!("{:#}", e)));
+            None
+        }
+    }
+}
+
+/// 创建 worktree 之后的准备工作（带入未跟踪文件、`post_create` 钩子）对应的 dry-run 改动
+fn setup_effects(repo: &Repository, config: &Config, path: &Path) -> Vec<Effect> {
+    let mut effects: Vec<Effect> = match seed_files(repo, config) {
+        Some((source, files)) => files
+            .into_iter()
+            .map(|f| Effect::SeedFile {
+                from: source.join(&f.rel),
+                to: path.join(&f.rel),
+                link: f.link,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    effects.extend(
+        config
+            .hooks
+            .post_create
+            .iter()
+            .map(|c| Effect::RunHook { command: c.clone() }),
+    );
+    effects
+}
+
+/// 新 worktree 建好后的准备工作：带入配置的未跟踪文件，再执行 `post_create` 钩子。
+/// 输出都在 stderr，不打扰 `gp new` 的标准输出；失败只提示，worktree 保留。`branch` 为空表示 HEAD 游离
+fn set_up_worktree(
+    repo: &Repository,
+    config: &Config,
+    path: &Path,
+    branch: &str,
+    base: Option<&str>,
+) {
+    seed_new_worktree(repo, config, path);
+    if config.hooks.post_create.is_empty() {
+        return;
+    }
+    let repo_root = match layout_root(repo) {
+        Ok(root) => root,
+        Err(e) => {
+            eprintln!("{}", tr!("common.warning", format!("{:#}", e)));
+            return;
+        }
+    };
+    let ctx = HookContext {
+        worktree: path,
+        branch,
+        base,
+        repo_root: &repo_root,
+    };
+    if let Err(e) = run_hooks("post_create", &config.hooks.post_create, &ctx) {
+        eprintln!("✗ {:#}", e);
+        eprintln!("{}", tr!("main.post_create_failed", path.display()));
+    }
+}
+
+/// 把主仓库工作区中配置的未跟踪文件复制或链接进新 worktree，失败的逐个提示
+fn seed_new_worktree(repo: &Repository, config: &Config, path: &Path) {
+    let Some((source, files)) = seed_files(repo, config) else {
+        return;
+    };
+    let (done, failed) = seed_worktree(&source, path, &files);
+    for file in &done {
+        let key = if file.link {
+            "main.seed_linked"
+        } else {
+            "main.seed_copied"
+        };
+        eprintln!("{}", tr!(key, file.rel.display()));
+    }
+    for (rel, e) in &failed {
+        eprintln!(
+            "{}",
+            tr!("main.seed_failed", rel.display(), format!("{:#}", e))
+        );
+    }
+}
+
+fn create_detached(
+    repo: &Repository,
+    config: &Config,
+    explicit_path: Option<&Path>,
+    name_template: Option<&str>,
+    rev: &str,
+    dry_run: bool,
+) -> Result<Outcome> {
+    let name = render_name(repo, name_template, &ref_safe(rev), None)?;
+
+    if dry_run {
+        let mut effects = Vec::new();
+        let path = worktree_path_for(repo, config, explicit_path, &name, Some(&mut effects))?;
+        let setup = setup_effects(repo, config, &path);
+        effects.push(Effect::AddWorktree { name, path });
+        effects.push(Effect::DetachHead {
+            rev: rev.to_string(),
+        });
+        effects.extend(setup);
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+
+    let worktree_path = worktree_path_for(repo, config, explicit_path, &name, None)?;
+    if !confirm_disk_space(repo, config, rev, &worktree_path)? {
+        println!("{}", tr!("common.cancelled"));
+        return Ok(Outcome::Cancelled);
+    }
+
+    println!("{}", tr!("main.creating_detached", rev));
+    println!("{}", tr!("main.path", worktree_path.display()));
+
+    create_detached_worktree(repo, rev, &name, &worktree_path)?;
+    FrequencyStore::record_worktree_created(&freq_path(repo), &worktree_path)?;
+    set_up_worktree(repo, config, &worktree_path, "", None);
+
+    println!("{}", tr!("main.detached_created", rev));
+    println!("{}", tr!("main.path", worktree_path.display()));
+
+    offer_cd(config, &worktree_path, &name)?;
+    Ok(Outcome::Done)
+}
+
+/// `gp pr [N]`：省略编号时从 GitHub / GitLab 列出打开的 PR / MR 供选择；来自 fork 上游的从上游仓库拉取。
+/// 新分支命名为 `pr-<N>-<标题 slug>`，`on_branch` 时在当前工作区检出，否则创建 worktree
+fn create_pr_worktree(
+    repo: &Repository,
+    config: &Config,
+    number: Option<u32>,
+    on_branch: bool,
+    ref_template: Option<&str>,
+    dry_run: bool,
+) -> Result<Outcome> {
+    let origin = ReviewHost::from_remote(repo);
+    let (number, title, review) = match number {
+        Some(number) => {
+            // 有编号时查询只为拿到标题和 fork 上游，失败不影响拉取
+            let found = origin.as_ref().and_then(|origin| {
+                let review = origin
+                    .review_host()
+                    .map_err(|e| debug!(error = %e, "repository lookup failed"))
+                    .ok()?;
+                let request = review
+                    .request(number)
+                    .map_err(|e| debug!(error = %e, "review request lookup failed"))
+                    .ok()?;
+                Some((request.title, review))
+            });
+            match found {
+                Some((title, review)) => (number, title, Some(review)),
+                None => (number, String::new(), None),
+            }
+        }
+        None => {
+            let Some(origin) = &origin else {
+                bail!("{}", tr!("pr.list_unsupported", remote()));
+            };
+            let review = origin.review_host()?;
+            let requests = review.open_requests()?;
+            if requests.is_empty() {
+                println!("{}", tr!("pr.none_open", review.full_name()));
+                return Ok(Outcome::Nothing);
+            }
+            let Some(request) = pick_review_request(&requests)? else {
+                println!("{}", tr!("common.cancelled"));
+                return Ok(Outcome::Cancelled);
+            };
+            (request.number, request.title.clone(), Some(review))
+        }
+    };
+
+    let src_ref = review_ref(repo, number, ref_template)?;
+    // PR 开在 fork 的上游时从上游拉取，本地已有指向它的 remote 就用那个 remote
+    let source = review
+        .filter(|review| origin.as_ref() != Some(review))
+        .map(|review| review.remote_in(repo).unwrap_or_else(|| review.clone_url()));
+    let slug = slugify(&title, SLUG_MAX_LEN);
+    let name = if slug.is_empty() {
+        format!("pr-{}", number)
+    } else {
+        format!("pr-{}-{}", number, slug)
+    };
+    let source_label = source.as_deref().unwrap_or(remote());
+
+    if on_branch && repo.is_bare() {
+        bail!("{}", tr!("main.bare_no_checkout"));
+    }
+
+    if dry_run {
+        let dst = review_local_ref(number);
+        let mut effects = vec![Effect::Fetch {
+            src: src_ref,
+            dst: dst.clone(),
+        }];
+        warn_if_branch_exists(repo, &name);
+        if on_branch {
+            effects.push(Effect::CreateBranch {
+                name: name.clone(),
+                start: dst,
+            });
+            effects.push(Effect::Checkout { branch: name });
+            print_effects(&effects);
+            return Ok(Outcome::Done);
+        }
+        let path = worktree_path_for(repo, config, None, &name, Some(&mut effects))?;
+        effects.push(Effect::CreateBranch {
+            name: name.clone(),
+            start: dst,
+        });
+        let setup = setup_effects(repo, config, &path);
+        effects.push(Effect::AddWorktree { name, path });
+        effects.extend(setup);
+        print_effects(&effects);
+        return Ok(Outcome::Done);
+    }
+
+    println!("{}", tr!("main.fetching", source_label, src_ref));
+    let commit_oid = fetch_review_ref(repo, source.as_deref(), &src_ref, number)?;
+
+    if on_branch {
+        println!("{}", tr!("main.creating_branch", name));
+        if let Err(e) = create_and_checkout_at(repo, commit_oid, &name) {
+            if matches!(e, GitError::CheckoutConflict) {
+                bail!("{}", tr!("pr.checkout_conflict", name));
+            }
+            return Err(e.into());
+        }
+        println!("{}", tr!("main.switched", name));
+        return Ok(Outcome::Done);
+    }
+
+    let worktree_path = worktree_path_for(repo, config, None, &name, None)?;
+    if !confirm_disk_space(repo, config, &commit_oid.to_string(), &worktree_path)? {
+        println!("{}", tr!("common.cancelled"));
+        return Ok(Outcome::Cancelled);
+    }
+    println!("{}", tr!("main.creating_worktree", name));
+    println!("{}", tr!("main.path", worktree_path.display()));
+
+    create_worktree_at(repo, commit_oid, &name, &worktree_path)?;
+    FrequencyStore::record_worktree_created(&freq_path(repo), &worktree_path)?;
+    set_up_worktree(repo, config, &worktree_path, &name, None);
+
+    println!("{}", tr!("main.worktree_created"));
+    println!("{}", tr!("main.worktree_branch_from", name, src_ref));
+    println!("{}", tr!("main.path", worktree_path.display()));
+
+    offer_cd(config, &worktree_path, &name)?;
+    Ok(Outcome::Done)
+}
@@ -0,0 +1,173 @@
+use anyhow::{bail, Result};
+use chrono::Local;
+use git2::{Branch, Repository};
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::issues::Issue;
+use crate::tr;
+
+/// 未配置 `branch_name_template` 时的新分支 / worktree 名称模板
+pub const DEFAULT_NAME_TEMPLATE: &str = "{branch}-{timestamp}";
+
+/// 选了工单（`--issue`）而模板中既没有 `{ticket}` 也没有 `{slug}` 时使用的模板
+pub const ISSUE_NAME_TEMPLATE: &str = "{ticket}-{slug}";
+
+/// 模板中可用的占位符
+pub const PLACEHOLDERS: &[&str] = &[
+    "{branch}",
+    "{timestamp}",
+    "{date}",
+    "{user}",
+    "{ticket}",
+    "{slug}",
+];
+
+/// 按模板生成新分支名。占位符：
+/// `{branch}` 基点分支名、`{timestamp}` 当前时间（`%Y%m%d%H%M%S`）、`{date}` 当前日期（`%Y%m%d`）、
+/// `{user}` git 配置的 user.name（没有时取 `$USER`）、
+/// `{ticket}` 选中工单的编号，没有选工单时取基点分支名中的工单号（如 `PROJ-123`）、
+/// `{slug}` 选中工单标题的短横线小写形式。
+///
+/// 取不到值的占位符替换为空，结果首尾多出的 `-` `_` `/` 会被去掉；
+/// 模板含未知占位符或结果不是合法分支名时返回错误
+pub fn render_name(
+    repo: &Repository,
+    template: Option<&str>,
+    branch: &str,
+    issue: Option<&Issue>,
+) -> Result<String> {
+    let mut template = template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_NAME_TEMPLATE);
+    check_template(template)?;
+    if issue.is_some() && !template.contains("{ticket}") && !template.contains("{slug}") {
+        template = ISSUE_NAME_TEMPLATE;
+    }
+
+    let now = Local::now();
+    let mut name = template
+        .replace("{branch}", branch)
+        .replace("{timestamp}", &now.format("%Y%m%d%H%M%S").to_string())
+        .replace("{date}", &now.format("%Y%m%d").to_string());
+    if name.contains("{user}") {
+        name = name.replace("{user}", &user_slug(repo).unwrap_or_default());
+    }
+    if name.contains("{ticket}") {
+        let ticket = match issue {
+            Some(issue) => ref_safe(&issue.key),
+            None => extract_ticket(branch).unwrap_or_default().to_string(),
+        };
+        name = name.replace("{ticket}", &ticket);
+    }
+    if name.contains("{slug}") {
+        let slug = issue
+            .map(|i| slugify(&i.title, SLUG_MAX_LEN))
+            .unwrap_or_default();
+        name = name.replace("{slug}", &slug);
+    }
+    let name = name.trim_matches(['-', '_', '/']).to_string();
+
+    if !is_valid_branch_name(&name) {
+        bail!("{}", tr!("naming.invalid", name, template));
+    }
+    Ok(name)
+}
+
+/// 按 git 的 ref 命名规则检查能否作为分支名（`git check-ref-format --branch`）
+pub fn is_valid_branch_name(name: &str) -> bool {
+    !name.is_empty() && Branch::name_is_valid(name).unwrap_or(false)
+}
+
+/// 从分支名中取出形如 `PROJ-123` 的工单号，如 `feature/PROJ-123-login` 得到 `PROJ-123`
+pub fn extract_ticket(branch: &str) -> Option<&str> {
+    static TICKET: OnceLock<Regex> = OnceLock::new();
+    let re = TICKET.get_or_init(|| Regex::new(r"[A-Z][A-Z0-9]+-[0-9]+").unwrap());
+    re.find(branch).map(|m| m.as_str())
+}
+
+/// 检查模板中没有未知占位符，便于在选择分支之前就报出拼写错误
+pub fn check_template(template: &str) -> Result<()> {
+    match unknown_placeholder(template, PLACEHOLDERS) {
+        Some(unknown) => bail!(
+            "{}",
+            tr!(
+                "naming.unknown_placeholder",
+                unknown,
+                PLACEHOLDERS.join(" ")
+            )
+        ),
+        None => Ok(()),
+    }
+}
+
+/// 模板中第一个不在 `known` 里的 `{...}`，没有闭合的 `{` 也算
+pub fn unknown_placeholder<'a>(template: &'a str, known: &[&str]) -> Option<&'a str> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(len) => start + len + 1,
+            None => return Some(&rest[start..]),
+        };
+        let placeholder = &rest[start..end];
+        if !known.contains(&placeholder) {
+            return Some(placeholder);
+        }
+        rest = &rest[end..];
+    }
+    None
+}
+
+/// 把任意文本（如 `HEAD~1`、用户名）转成可以放进分支名的一段：
+/// 空白、`/`、`.` 和 ref 中不允许的字符换成 `-`，首尾的 `-` 去掉
+pub fn ref_safe(text: &str) -> String {
+    let safe: String = text
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c.is_control() || "~^:?*[\\/.@{}".contains(c) {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    safe.trim_matches('-').to_string()
+}
+
+/// `{slug}` 和 PR 分支名中标题部分的最大长度
+pub const SLUG_MAX_LEN: usize = 40;
+
+/// 把标题（如 PR 标题）压成分支名里的一段短横线小写串：只保留 ASCII 字母和数字，
+/// 其余连续字符合并为一个 `-`，按词截断到不超过 `max_len` 个字符；没有可用字符时为空
+pub fn slugify(title: &str, max_len: usize) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let extra = word.len() + usize::from(!slug.is_empty());
+        if slug.len() + extra > max_len {
+            if slug.is_empty() {
+                slug.push_str(&word[..max_len].to_ascii_lowercase());
+            }
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug
+}
+
+/// git 配置的 user.name（没有时取 `$USER`），小写后经 `ref_safe` 处理
+fn user_slug(repo: &Repository) -> Option<String> {
+    let name = repo
+        .config()
+        .ok()
+        .and_then(|c| c.get_string("user.name").ok())
+        .or_else(|| std::env::var("USER").ok())?;
+    let slug = ref_safe(&name.to_lowercase());
+    (!slug.is_empty()).then_some(slug)
+}
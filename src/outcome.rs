@@ -0,0 +1,25 @@
+use std::process::ExitCode;
+
+/// 出错时的退出码，参数解析失败也归为此类
+pub const EXIT_ERROR: u8 = 1;
+
+/// 命令正常结束时的结果，决定进程退出码，方便脚本区分：
+/// 0 成功、1 出错（见 `EXIT_ERROR`）、2 用户取消、3 没有可操作的对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    /// 在任一确认或选择环节按了 Esc / Ctrl+C，或回答了“否”
+    Cancelled,
+    /// 没有远端分支、没有 worktree、没有可清理项等
+    Nothing,
+}
+
+impl From<Outcome> for ExitCode {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Done => ExitCode::SUCCESS,
+            Outcome::Cancelled => ExitCode::from(2),
+            Outcome::Nothing => ExitCode::from(3),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::Stylize,
+    terminal::{self, ClearType},
+};
+use std::cmp::Reverse;
+use std::io::{self, Stdout, Write};
+
+use git_branch_picker::keymap::{describe, KeyBinding};
+use git_branch_picker::tr;
+
+use crate::ui::{hits, is_ctrl_c, BranchItem, RawModeGuard, SortMode};
+
+/// 一屏显示的分支数，与 inquire 的默认值一致
+const PAGE_SIZE: usize = 7;
+
+/// 与 inquire 的 Scorer 签名相同：`(输入, 选项, 选项显示文本, 下标)`，不匹配返回 `None`
+pub type BranchScorer<'a> = &'a dyn Fn(&str, &BranchItem, &str, usize) -> Option<i64>;
+
+/// 用户选中的分支以及选中时过滤框里的输入
+pub struct Picked {
+    pub name: String,
+    pub filter: String,
+}
+
+/// 分支选择列表。inquire 的 Select 接管了事件循环、无法绑定额外按键，
+/// 这里用 crossterm 实现一个同样外观的列表，额外支持按 `sort_keys` 循环切换排序方式。
+/// `resort` 负责按新的排序方式重排（可在其中按需补齐数据），切换后尽量保持当前高亮项
+pub struct BranchPicker<'a> {
+    pub prompt: &'a str,
+    pub items: Vec<BranchItem>,
+    pub filter: String,
+    pub scorer: BranchScorer<'a>,
+    pub sort: SortMode,
+    pub sort_keys: &'a [KeyBinding],
+    pub resort: &'a dyn Fn(SortMode, &mut [BranchItem]),
+}
+
+/// 恢复 picker 改动过的终端状态：光标可见、自动换行
+struct ScreenGuard;
+
+impl Drop for ScreenGuard {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = queue!(out, cursor::Show, terminal::EnableLineWrap);
+        let _ = out.flush();
+    }
+}
+
+impl BranchPicker<'_> {
+    /// Esc / Ctrl+C 返回 `None`
+    pub fn prompt(mut self) -> Result<Option<Picked>> {
+        let mut out = io::stdout();
+        let picked = {
+            let _raw = RawModeGuard::enable()?;
+            // 关掉自动换行，过长的行直接截断，保证每个选项只占一行，重绘时行数可控
+            queue!(out, cursor::Hide, terminal::DisableLineWrap)?;
+            let _screen = ScreenGuard;
+            let mut list = ListState::new(&self.items, &self.filter, self.scorer);
+            let mut drawn = 0;
+
+            loop {
+                drawn = self.render(&mut out, &list, drawn)?;
+                let key = match event::read()? {
+                    Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                    _ => continue,
+                };
+                if is_ctrl_c(&key) || key.code == KeyCode::Esc {
+                    self.finish(&mut out, drawn, None)?;
+                    break None;
+                }
+                if hits(self.sort_keys, &key) {
+                    let current = list.current().map(|i| self.items[i].name.clone());
+                    self.sort = self.sort.next();
+                    (self.resort)(self.sort, &mut self.items);
+                    list = ListState::new(&self.items, &self.filter, self.scorer);
+                    if let Some(name) = current {
+                        list.select_where(|i| self.items[i].name == name);
+                    }
+                    continue;
+                }
+                if key.code == KeyCode::Enter {
+                    if let Some(i) = list.current() {
+                        self.finish(&mut out, drawn, Some(&self.items[i].label))?;
+                        break Some(i);
+                    }
+                    continue;
+                }
+                if self.edit_filter(&key) {
+                    list = ListState::new(&self.items, &self.filter, self.scorer);
+                } else {
+                    list.navigate(&key);
+                }
+            }
+        };
+
+        Ok(picked.map(|i| Picked {
+            name: self.items.swap_remove(i).name,
+            filter: self.filter,
+        }))
+    }
+
+    /// 处理过滤框的输入，返回过滤内容是否变化
+    fn edit_filter(&mut self, key: &KeyEvent) -> bool {
+        let plain = !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char(c) if plain => {
+                self.filter.push(c);
+                true
+            }
+            KeyCode::Backspace => self.filter.pop().is_some(),
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let changed = !self.filter.is_empty();
+                self.filter.clear();
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    /// 从上次绘制的起始行开始重绘，返回本次绘制了多少行（不含首行）
+    fn render(&self, out: &mut Stdout, list: &ListState, drawn: u16) -> Result<u16> {
+        rewind(out, drawn)?;
+        queue!(
+            out,
+            crossterm::style::Print(format!("{} {} {}", "?".green(), self.prompt, self.filter))
+        )?;
+
+        let mut lines = 0;
+        if list.visible.is_empty() {
+            print_line(out, &format!("  {}", tr!("picker.no_match").dark_grey()))?;
+            lines += 1;
+        }
+        for (row, &i) in list.page().iter().enumerate() {
+            let text = self.items[i].to_string();
+            let line = if list.offset + row == list.cursor {
+                format!("{}", format!("> {}", text).cyan())
+            } else {
+                format!("  {}", text)
+            };
+            print_line(out, &line)?;
+            lines += 1;
+        }
+        let help = tr!("picker.help", describe(self.sort_keys), self.sort.label());
+        print_line(out, &format!("{}", help.cyan()))?;
+        lines += 1;
+        out.flush()?;
+        Ok(lines)
+    }
+
+    /// 收起列表，只留下 inquire 风格的结果行
+    fn finish(&self, out: &mut Stdout, drawn: u16, answer: Option<&str>) -> Result<()> {
+        rewind(out, drawn)?;
+        let answer = match answer {
+            Some(a) => format!("{}", a.cyan()),
+            None => format!("{}", "<canceled>".dark_grey()),
+        };
+        queue!(
+            out,
+            crossterm::style::Print(format!("{} {} {}\r\n", "?".green(), self.prompt, answer))
+        )?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn rewind(out: &mut Stdout, drawn: u16) -> Result<()> {
+    queue!(out, cursor::MoveToColumn(0))?;
+    if drawn > 0 {
+        queue!(out, cursor::MoveUp(drawn))?;
+    }
+    queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+    Ok(())
+}
+
+fn print_line(out: &mut Stdout, line: &str) -> Result<()> {
+    queue!(out, crossterm::style::Print(format!("\r\n{}", line)))?;
+    Ok(())
+}
+
+/// 过滤后的可见项（`items` 的下标，按得分排好序）和光标、翻页位置
+struct ListState {
+    visible: Vec<usize>,
+    cursor: usize,
+    offset: usize,
+}
+
+impl ListState {
+    /// 过滤为空时保持原顺序；否则按得分从高到低，同分保持原顺序
+    fn new(items: &[BranchItem], filter: &str, scorer: BranchScorer) -> Self {
+        let visible = if filter.is_empty() {
+            (0..items.len()).collect()
+        } else {
+            let mut scored: Vec<(usize, i64)> = items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| scorer(filter, item, &item.label, i).map(|s| (i, s)))
+                .collect();
+            scored.sort_by_key(|&(_, score)| Reverse(score));
+            scored.into_iter().map(|(i, _)| i).collect()
+        };
+        Self {
+            visible,
+            cursor: 0,
+            offset: 0,
+        }
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.visible.get(self.cursor).copied()
+    }
+
+    fn page(&self) -> &[usize] {
+        let end = (self.offset + PAGE_SIZE).min(self.visible.len());
+        &self.visible[self.offset..end]
+    }
+
+    fn select_where(&mut self, pred: impl Fn(usize) -> bool) {
+        if let Some(pos) = self.visible.iter().position(|&i| pred(i)) {
+            self.move_to(pos);
+        }
+    }
+
+    /// ↑↓ 到头时回绕，与 inquire 一致
+    fn navigate(&mut self, key: &KeyEvent) {
+        let len = self.visible.len();
+        if len == 0 {
+            return;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let target = match key.code {
+            KeyCode::Up => (self.cursor + len - 1) % len,
+            KeyCode::Char('p') if ctrl => (self.cursor + len - 1) % len,
+            KeyCode::Down => (self.cursor + 1) % len,
+            KeyCode::Char('n') if ctrl => (self.cursor + 1) % len,
+            KeyCode::PageUp => self.cursor.saturating_sub(PAGE_SIZE),
+            KeyCode::PageDown => (self.cursor + PAGE_SIZE).min(len - 1),
+            KeyCode::Home => 0,
+            KeyCode::End => len - 1,
+            _ => return,
+        };
+        self.move_to(target);
+    }
+
+    /// 移动光标并让它落在当前页内
+    fn move_to(&mut self, pos: usize) {
+        self.cursor = pos;
+        if pos < self.offset {
+            self.offset = pos;
+        } else if pos >= self.offset + PAGE_SIZE {
+            self.offset = pos + 1 - PAGE_SIZE;
+        }
+    }
+}
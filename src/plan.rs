@@ -0,0 +1,197 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::git::{remote, remote_branch_label};
+use crate::tr;
+
+/// 创建操作会对仓库或磁盘产生的一项改动。`--dry-run` 时只打印这些描述，不真正执行
+#[derive(Debug, Clone)]
+pub enum Effect {
+    /// 把未提交的改动存入 stash
+    Stash { include_untracked: bool },
+    /// 新建本地分支
+    CreateBranch { name: String, start: String },
+    /// 写入 `branch.<name>.remote` / `branch.<name>.merge`
+    SetUpstream {
+        branch: String,
+        remote_branch: String,
+    },
+    /// 在主仓库切换到该分支
+    Checkout { branch: String },
+    /// 把 `/.worktrees/` 追加到 .gitignore
+    IgnoreSubdir { gitignore: PathBuf },
+    /// 新建 worktree 并检出
+    AddWorktree { name: String, path: PathBuf },
+    /// 在新 worktree 中应用 stash，成功后从主仓库移除
+    ApplyStash { path: PathBuf },
+    /// 切换后把 stash 重新应用到当前工作区，有冲突时保留 stash
+    RestoreStash { branch: String },
+    /// 让 worktree 的 HEAD 游离在指定提交上
+    DetachHead { rev: String },
+    /// 从远程仓库拉取 ref 到本地
+    Fetch { src: String, dst: String },
+    /// 删除本地分支
+    DeleteBranch { name: String },
+    /// 推送新分支并把上游改为推送后的远端分支
+    Push { branch: String },
+    /// 推送删除 refspec，删除远端分支
+    DeleteRemoteBranch { remote: String, name: String },
+    /// 删除 worktree 目录并清理 git 中的记录
+    RemoveWorktree { path: PathBuf },
+    /// 清除目录已不存在的 worktree 在 git 中的记录
+    PruneWorktree { name: String },
+    /// 让 git 记录与 worktree 目录重新互相指向
+    RelinkWorktree { name: String, path: PathBuf },
+    /// 把未跟踪文件复制（或链接）进新 worktree
+    SeedFile {
+        from: PathBuf,
+        to: PathBuf,
+        link: bool,
+    },
+    /// 在新 worktree 中执行钩子命令
+    RunHook { command: String },
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Effect::Stash { include_untracked } => write!(
+                f,
+                "{}",
+                tr!(
+                    "plan.stash",
+                    if *include_untracked {
+                        tr!("plan.stash_untracked")
+                    } else {
+                        ""
+                    }
+                )
+            ),
+            Effect::CreateBranch { name, start } => {
+                write!(f, "{}", tr!("plan.create_branch", name, start))
+            }
+            Effect::SetUpstream {
+                branch,
+                remote_branch,
+            } => write!(
+                f,
+                "{}",
+                tr!("plan.set_upstream", branch, remote(), branch, remote_branch)
+            ),
+            Effect::Checkout { branch } => write!(f, "{}", tr!("plan.checkout", branch)),
+            Effect::IgnoreSubdir { gitignore } => {
+                write!(f, "{}", tr!("plan.ignore_subdir", gitignore.display()))
+            }
+            Effect::AddWorktree { name, path } => {
+                write!(f, "{}", tr!("plan.add_worktree", path.display(), name))
+            }
+            Effect::ApplyStash { path } => {
+                write!(f, "{}", tr!("plan.apply_stash", path.display()))
+            }
+            Effect::RestoreStash { branch } => {
+                write!(f, "{}", tr!("plan.restore_stash", branch))
+            }
+            Effect::DetachHead { rev } => {
+                write!(f, "{}", tr!("plan.detach_head", rev))
+            }
+            Effect::Fetch { src, dst } => write!(f, "{}", tr!("plan.fetch", remote(), src, dst)),
+            Effect::DeleteBranch { name } => write!(f, "{}", tr!("plan.delete_branch", name)),
+            Effect::Push { branch } => write!(
+                f,
+                "{}",
+                tr!("plan.push", branch, remote(), remote_branch_label(branch))
+            ),
+            Effect::DeleteRemoteBranch { remote, name } => {
+                let label = format!("{}/{}", remote, name);
+                write!(
+                    f,
+                    "{}",
+                    tr!("plan.delete_remote_branch", name, remote, label)
+                )
+            }
+            Effect::PruneWorktree { name } => write!(f, "{}", tr!("plan.prune_worktree", name)),
+            Effect::RelinkWorktree { name, path } => {
+                write!(f, "{}", tr!("plan.relink_worktree", name, path.display()))
+            }
+            Effect::RemoveWorktree { path } => {
+                write!(f, "{}", tr!("plan.remove_worktree", path.display()))
+            }
+            Effect::SeedFile { from, to, link } => {
+                let key = if *link {
+                    "plan.link_file"
+                } else {
+                    "plan.copy_file"
+                };
+                write!(f, "{}", tr!(key, from.display(), to.display()))
+            }
+            Effect::RunHook { command } => write!(f, "{}", tr!("plan.run_hook", command)),
+        }
+    }
+}
+
+/// 创建并切换到本地分支的改动；`stash` 为是否会先暂存工作区改动
+pub fn branch_effects(
+    remote_branch: &str,
+    new_name: &str,
+    upstream: Option<&str>,
+    stash: bool,
+) -> Vec<Effect> {
+    let mut effects = Vec::new();
+    if stash {
+        effects.push(Effect::Stash {
+            include_untracked: false,
+        });
+    }
+    effects.push(Effect::CreateBranch {
+        name: new_name.to_string(),
+        start: remote_branch_label(remote_branch),
+    });
+    if let Some(upstream) = upstream {
+        effects.push(Effect::SetUpstream {
+            branch: new_name.to_string(),
+            remote_branch: upstream.to_string(),
+        });
+    }
+    effects.push(Effect::Checkout {
+        branch: new_name.to_string(),
+    });
+    if stash {
+        effects.push(Effect::RestoreStash {
+            branch: new_name.to_string(),
+        });
+    }
+    effects
+}
+
+/// 基于远端分支创建 worktree 的改动；`upstream` 为追踪的远端分支，`None` 时不设置上游；
+/// `carry` 为搬运改动时是否包含未跟踪文件
+pub fn worktree_effects(
+    remote_branch: &str,
+    new_name: &str,
+    path: PathBuf,
+    upstream: Option<&str>,
+    carry: Option<bool>,
+) -> Vec<Effect> {
+    let mut effects = Vec::new();
+    if let Some(include_untracked) = carry {
+        effects.push(Effect::Stash { include_untracked });
+    }
+    effects.push(Effect::CreateBranch {
+        name: new_name.to_string(),
+        start: remote_branch_label(remote_branch),
+    });
+    effects.push(Effect::AddWorktree {
+        name: new_name.to_string(),
+        path: path.clone(),
+    });
+    if let Some(upstream) = upstream {
+        effects.push(Effect::SetUpstream {
+            branch: new_name.to_string(),
+            remote_branch: upstream.to_string(),
+        });
+    }
+    if carry.is_some() {
+        effects.push(Effect::ApplyStash { path });
+    }
+    effects
+}
@@ -0,0 +1,345 @@
+use anyhow::{bail, Context, Result};
+use git2::{FetchOptions, Oid, Repository};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::git::{remote, remote_callbacks};
+use crate::github::{self, GitHubRepo};
+use crate::gitlab::{self, GitLabProject};
+use crate::tr;
+
+/// 代码托管平台，决定评审分支（PR/MR）在远端的 ref 位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl Provider {
+    /// 根据 remote URL 的 host 识别平台：host 中以 `.` 分隔的某一段等于平台名即可，
+    /// 如 `github.com`、`gitlab.example.com`；`notgithub.example` 这类只是包含平台名的不算
+    pub fn detect(url: &str) -> Option<Self> {
+        let host = url_host(url)?.to_ascii_lowercase();
+        let has_label = |name: &str| host.split('.').any(|label| label == name);
+        if has_label("github") {
+            Some(Self::GitHub)
+        } else if has_label("gitlab") {
+            Some(Self::GitLab)
+        } else if has_label("bitbucket") {
+            Some(Self::Bitbucket)
+        } else {
+            None
+        }
+    }
+
+    /// 评审分支的 ref 模板，`{n}` 为编号
+    pub fn ref_template(self) -> &'static str {
+        match self {
+            Self::GitHub => "refs/pull/{n}/head",
+            Self::GitLab => "refs/merge-requests/{n}/head",
+            // Bitbucket Server / Data Center 的写法，Bitbucket Cloud 不对外暴露 PR ref
+            Self::Bitbucket => "refs/pull-requests/{n}/from",
+        }
+    }
+}
+
+/// 取出 remote URL 中的 host，支持 `https://`、`ssh://` 和 `git@host:path` 三种写法
+pub fn url_host(url: &str) -> Option<&str> {
+    let authority = match url.split_once("://") {
+        Some((_, rest)) => rest.split('/').next()?,
+        // scp 风格：[user@]host:path，没有冒号的视为本地路径
+        None => url.split_once(':')?.0,
+    };
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+/// 用编号渲染 ref 模板，模板里必须带 `{n}`
+pub fn render_ref(template: &str, number: u32) -> Result<String> {
+    if !template.contains("{n}") {
+        bail!("{}", tr!("pr.template_missing_n", template));
+    }
+    Ok(template.replace("{n}", &number.to_string()))
+}
+
+/// 确定 `gp pr <N>` 要拉取的远端 ref：优先用 `--ref-template`，否则按远程仓库 URL 识别平台
+pub fn review_ref(repo: &Repository, number: u32, template: Option<&str>) -> Result<String> {
+    if let Some(t) = template {
+        return render_ref(t, number);
+    }
+    let origin = repo
+        .find_remote(remote())
+        .with_context(|| tr!("git.no_origin", remote()))?;
+    let url = origin.url().unwrap_or_default();
+    match Provider::detect(url) {
+        Some(provider) => render_ref(provider.ref_template(), number),
+        None => bail!("{}", tr!("pr.unknown_host", remote(), url)),
+    }
+}
+
+/// `gp pr <N>` 拉取到本地的 ref：`refs/gp/pr/<N>`
+pub fn review_local_ref(number: u32) -> String {
+    format!("refs/gp/pr/{}", number)
+}
+
+/// 从远程仓库拉取评审分支，存到 `refs/gp/pr/<N>` 并返回其提交。
+/// `source` 为拉取来源，可以是 remote 名或 URL（如 fork 的上游仓库），`None` 时为配置的 remote
+pub fn fetch_review_ref(
+    repo: &Repository,
+    source: Option<&str>,
+    src_ref: &str,
+    number: u32,
+) -> Result<Oid> {
+    let source = source.unwrap_or(remote());
+    let mut origin = match repo.find_remote(source) {
+        Ok(r) => r,
+        Err(_) if source.contains("://") => repo
+            .remote_anonymous(source)
+            .with_context(|| tr!("pr.bad_source", source))?,
+        Err(_) => bail!("{}", tr!("git.no_origin", source)),
+    };
+    let dst_ref = review_local_ref(number);
+    let refspec = format!("+{}:{}", src_ref, dst_ref);
+
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(remote_callbacks(repo));
+    origin
+        .fetch(&[refspec.as_str()], Some(&mut opts), None)
+        .with_context(|| tr!("pr.fetch_failed", src_ref))?;
+
+    let reference = repo
+        .find_reference(&dst_ref)
+        .with_context(|| tr!("pr.not_on_remote", src_ref))?;
+    reference
+        .target()
+        .with_context(|| tr!("pr.not_direct", dst_ref))
+}
+
+/// 托管平台上一个打开的评审请求（GitHub PR 或 GitLab MR）
+#[derive(Debug, Clone)]
+pub struct ReviewRequest {
+    pub number: u32,
+    pub title: String,
+    /// 作者的用户名
+    pub author: String,
+    /// 来源分支，GitHub 上形如 `someone:feature-x`
+    pub source: String,
+    pub draft: bool,
+}
+
+/// 能通过 API 查询评审请求的托管仓库
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewHost {
+    GitHub(GitHubRepo),
+    GitLab(GitLabProject),
+}
+
+impl ReviewHost {
+    /// 按 remote URL 识别，Bitbucket 和无法识别的地址返回 `None`
+    pub fn from_url(url: &str) -> Option<Self> {
+        match Provider::detect(url)? {
+            Provider::GitHub => GitHubRepo::from_url(url).map(Self::GitHub),
+            Provider::GitLab => GitLabProject::from_url(url).map(Self::GitLab),
+            Provider::Bitbucket => None,
+        }
+    }
+
+    /// 配置的 remote 对应的仓库
+    pub fn from_remote(repo: &Repository) -> Option<Self> {
+        let origin = repo.find_remote(remote()).ok()?;
+        Self::from_url(origin.url()?)
+    }
+
+    /// `owner/repo` 或 GitLab 的项目路径
+    pub fn full_name(&self) -> String {
+        match self {
+            Self::GitHub(r) => r.full_name(),
+            Self::GitLab(p) => p.path.clone(),
+        }
+    }
+
+    /// 用于拉取的 HTTPS 地址
+    pub fn clone_url(&self) -> String {
+        match self {
+            Self::GitHub(r) => r.clone_url(),
+            Self::GitLab(p) => p.clone_url(),
+        }
+    }
+
+    /// 评审请求所在的仓库：是 fork 时为它的上游，否则为自身
+    pub fn review_host(&self) -> Result<Self> {
+        match self {
+            Self::GitHub(r) => github::review_repo(r).map(Self::GitHub),
+            Self::GitLab(p) => gitlab::review_project(p).map(Self::GitLab),
+        }
+    }
+
+    /// 打开的评审请求，按创建时间从新到旧，最多 100 个
+    pub fn open_requests(&self) -> Result<Vec<ReviewRequest>> {
+        Ok(match self {
+            Self::GitHub(r) => github::open_pull_requests(r)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            Self::GitLab(p) => gitlab::open_merge_requests(p)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        })
+    }
+
+    /// 按编号取一个评审请求
+    pub fn request(&self, number: u32) -> Result<ReviewRequest> {
+        Ok(match self {
+            Self::GitHub(r) => github::pull_request(r, number)?.into(),
+            Self::GitLab(p) => gitlab::merge_request(p, number)?.into(),
+        })
+    }
+
+    /// 本地已有的、指向该仓库的 remote（如 fork 工作流中的 `upstream`），拉取时优先用它的认证配置
+    pub fn remote_in(&self, repo: &Repository) -> Option<String> {
+        let remotes = repo.remotes().ok()?;
+        remotes.iter().flatten().find_map(|name| {
+            let remote = repo.find_remote(name).ok()?;
+            let url = remote.url()?;
+            (Self::from_url(url).as_ref() == Some(self)).then(|| name.to_string())
+        })
+    }
+}
+
+/// 向托管平台的 REST API 发 GET 请求并解析 JSON。`auth` 为认证用的请求头，没有 token 时匿名访问；
+/// `token_hint` 是出错时提示的 token 配置方式
+pub(crate) fn api_get<T: DeserializeOwned>(
+    platform: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    auth: Option<(&str, String)>,
+    token_hint: &str,
+) -> Result<T> {
+    debug!(%url, "{} API request", platform);
+    let mut request = ureq::get(url)
+        .timeout(Duration::from_secs(20))
+        .set("User-Agent", "gp");
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    let has_token = auth.is_some();
+    if let Some((name, value)) = &auth {
+        request = request.set(name, value);
+    }
+    match request.call() {
+        Ok(response) => response
+            .into_json()
+            .with_context(|| tr!("api.bad_response", platform, url)),
+        Err(ureq::Error::Status(code, _)) => {
+            let hint = match (code, has_token) {
+                (401, _) => tr!("api.hint_bad_token", token_hint),
+                (403, _) | (429, _) => tr!("api.hint_rate_limit", token_hint),
+                (404, false) => tr!("api.hint_no_token", token_hint),
+                _ => String::new(),
+            };
+            bail!("{}{}", tr!("api.status", platform, code, url), hint)
+        }
+        Err(e) => Err(e).with_context(|| tr!("api.request_failed", platform, url)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_host_forms() {
+        let cases = [
+            ("https://github.com/owner/repo.git", Some("github.com")),
+            ("https://user:pw@github.com/owner/repo", Some("github.com")),
+            (
+                "ssh://git@gitlab.example.com:2222/group/repo",
+                Some("gitlab.example.com"),
+            ),
+            ("git@github.com:owner/repo.git", Some("github.com")),
+            ("github.com:owner/repo", Some("github.com")),
+            ("/srv/git/repo.git", None),
+            ("file:///srv/git/repo", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(url_host(url), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn provider_matches_host_labels() {
+        let cases = [
+            ("https://github.com/owner/repo", Some(Provider::GitHub)),
+            ("git@GitHub.com:owner/repo.git", Some(Provider::GitHub)),
+            (
+                "https://github.corp.example/owner/repo",
+                Some(Provider::GitHub),
+            ),
+            ("ssh://git@gitlab.com/group/repo", Some(Provider::GitLab)),
+            (
+                "https://gitlab.example.com/group/sub/repo",
+                Some(Provider::GitLab),
+            ),
+            (
+                "git@bitbucket.org:owner/repo.git",
+                Some(Provider::Bitbucket),
+            ),
+            ("https://notgithub.example/owner/repo", None),
+            ("https://mygitlab.example.com/group/repo", None),
+            ("https://example.com/github/repo", None),
+            ("git@git.example.com:github.com/repo.git", None),
+            ("/srv/git/github.com/repo.git", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(Provider::detect(url), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn review_host_from_url() {
+        let github = |host: &str| {
+            Some(ReviewHost::GitHub(GitHubRepo {
+                host: host.to_string(),
+                owner: "owner".to_string(),
+                name: "repo".to_string(),
+            }))
+        };
+        let gitlab = |path: &str| {
+            Some(ReviewHost::GitLab(GitLabProject {
+                host: "gitlab.com".to_string(),
+                path: path.to_string(),
+            }))
+        };
+        let cases = [
+            ("https://github.com/owner/repo.git", github("github.com")),
+            ("https://github.com/owner/repo/", github("github.com")),
+            ("ssh://git@github.com:22/owner/repo", github("github.com")),
+            ("git@GitHub.com:owner/repo.git", github("github.com")),
+            (
+                "https://github.corp.example/owner/repo",
+                github("github.corp.example"),
+            ),
+            ("https://github.com/repo", None),
+            ("https://gitlab.com/group/repo.git", gitlab("group/repo")),
+            (
+                "git@gitlab.com:group/sub/repo.git",
+                gitlab("group/sub/repo"),
+            ),
+            ("ssh://git@gitlab.com/group/repo/", gitlab("group/repo")),
+            ("https://gitlab.com/repo", None),
+            ("git@bitbucket.org:owner/repo.git", None),
+            ("https://notgithub.example/owner/repo", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(ReviewHost::from_url(url), expected, "{}", url);
+        }
+    }
+}
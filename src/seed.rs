@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::tr;
+
+/// 要带进新 worktree 的一个未跟踪文件（或目录），路径相对于主仓库工作区
+#[derive(Debug, Clone)]
+pub struct SeedFile {
+    pub rel: PathBuf,
+    /// 创建符号链接而不是复制
+    pub link: bool,
+}
+
+/// 在 `source_root` 下展开配置的 glob（如 `.env`、`config/*.local.json`），得到要复制和链接的文件。
+/// 同一个文件同时匹配两类时按链接处理；没有匹配的 glob 直接忽略。
+/// 含 `..` 的匹配会落到仓库之外，一律跳过
+pub fn plan_seed_files(
+    source_root: &Path,
+    copy: &[String],
+    link: &[String],
+) -> Result<Vec<SeedFile>> {
+    let mut files: Vec<SeedFile> = Vec::new();
+    for (patterns, is_link) in [(link, true), (copy, false)] {
+        for pattern in patterns {
+            let full = format!(
+                "{}/{}",
+                glob::Pattern::escape(&source_root.to_string_lossy()),
+                pattern.trim_start_matches("./")
+            );
+            let paths = glob::glob(&full).with_context(|| tr!("seed.bad_pattern", pattern))?;
+            for path in paths.flatten() {
+                let rel = match path.strip_prefix(source_root) {
+                    Ok(rel)
+                        if !rel.starts_with(".git")
+                            && !rel.components().any(|c| c == Component::ParentDir) =>
+                    {
+                        rel.to_path_buf()
+                    }
+                    _ => continue,
+                };
+                if !files.iter().any(|f| f.rel == rel) {
+                    files.push(SeedFile { rel, link: is_link });
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// 把 `files` 从 `source_root` 复制或链接到新 worktree `target`。目标已存在（比如该文件其实被跟踪）时跳过。
+/// 单个文件失败不影响其余文件，返回成功处理的文件和失败的原因
+pub fn seed_worktree(
+    source_root: &Path,
+    target: &Path,
+    files: &[SeedFile],
+) -> (Vec<SeedFile>, Vec<(PathBuf, anyhow::Error)>) {
+    let mut done = Vec::new();
+    let mut failed = Vec::new();
+    for file in files {
+        let from = source_root.join(&file.rel);
+        let to = target.join(&file.rel);
+        if to.symlink_metadata().is_ok() {
+            continue;
+        }
+        let result = to
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| {
+                if file.link {
+                    symlink(&from, &to)
+                } else {
+                    copy_recursive(&from, &to)
+                }
+            });
+        match result {
+            Ok(()) => done.push(file.clone()),
+            Err(e) => failed.push((file.rel.clone(), e)),
+        }
+    }
+    (done, failed)
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(from: &Path, to: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(from, to)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn symlink(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        std::os::windows::fs::symlink_dir(from, to)?;
+    } else {
+        std::os::windows::fs::symlink_file(from, to)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_cannot_escape_the_repo() {
+        let dir = std::env::temp_dir().join(format!("gp-test-{}-seed", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let root = dir.join("repo");
+        fs::create_dir_all(root.join("config")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(dir.join("secret")).unwrap();
+        fs::write(root.join(".env"), "").unwrap();
+        fs::write(root.join("config/app.local.json"), "").unwrap();
+        fs::write(root.join(".git/config"), "").unwrap();
+        fs::write(dir.join("secret/key"), "").unwrap();
+
+        let copy = [
+            "../secret/*".to_string(),
+            "config/../../secret/key".to_string(),
+            "./config/*.local.json".to_string(),
+            ".git/*".to_string(),
+        ];
+        let link = [".env".to_string()];
+        let files = plan_seed_files(&root, &copy, &link).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let planned: Vec<(PathBuf, bool)> = files.into_iter().map(|f| (f.rel, f.link)).collect();
+        assert_eq!(
+            planned,
+            [
+                (PathBuf::from(".env"), true),
+                (PathBuf::from("config/app.local.json"), false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use git_branch_picker::tr;
+
+/// `--print-path` 时接收最终路径的原标准输出；设置后其它输出都已改到 stderr
+static PATH_OUT: OnceLock<Mutex<Box<dyn Write + Send>>> = OnceLock::new();
+
+/// `gp shell-init` 支持的 Shell
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// 进入 `--print-path` 模式：保留原标准输出只用来输出最终路径，其余输出（列表、提示、进度）都改写到 stderr，
+/// 这样 Shell 包装函数用 `$(gp --print-path)` 只会拿到路径
+pub fn capture_stdout() -> Result<()> {
+    let _ = PATH_OUT.set(Mutex::new(redirect_stdout()?));
+    Ok(())
+}
+
+#[cfg(unix)]
+fn redirect_stdout() -> Result<Box<dyn Write + Send>> {
+    use std::fs::File;
+    use std::os::fd::FromRawFd;
+
+    let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+    if saved < 0 || unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+        return Err(std::io::Error::last_os_error()).context(tr!("shell.redirect_failed"));
+    }
+    // saved 是刚 dup 出来、只归这里所有的描述符
+    Ok(Box::new(unsafe { File::from_raw_fd(saved) }))
+}
+
+#[cfg(windows)]
+fn redirect_stdout() -> Result<Box<dyn Write + Send>> {
+    use std::fs::File;
+    use std::os::windows::io::FromRawHandle;
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::System::Console::{
+        GetStdHandle, SetStdHandle, STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    // 标准库每次写 stdout 都重新取标准句柄，所以换掉 STD_OUTPUT_HANDLE 即可让其余输出改到 stderr
+    let saved = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
+    if saved.is_null()
+        || saved == INVALID_HANDLE_VALUE
+        || unsafe { SetStdHandle(STD_OUTPUT_HANDLE, GetStdHandle(STD_ERROR_HANDLE)) } == 0
+    {
+        return Err(std::io::Error::last_os_error()).context(tr!("shell.redirect_failed"));
+    }
+    // 原句柄换下来后只归这里使用，存在 PATH_OUT 中直到进程退出
+    Ok(Box::new(unsafe { File::from_raw_handle(saved) }))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn redirect_stdout() -> Result<Box<dyn Write + Send>> {
+    Ok(Box::new(std::io::stdout()))
+}
+
+/// 交给用户和其它程序的路径。Windows 上 canonicalize 得到的 `\\?\C:\...` 形式 cmd 无法进入，
+/// 能去掉前缀时去掉；其它平台原样返回
+pub fn plain_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let text = path.to_string_lossy();
+        if let Some(rest) = text.strip_prefix(r"\\?\") {
+            if !rest.starts_with("UNC\\") {
+                return PathBuf::from(rest);
+            }
+        }
+    }
+    path.to_path_buf()
+}
+
+/// 进入 worktree 时打开的交互式 Shell：优先 `$SHELL`（Windows 上的 Git Bash、MSYS2 也会设置），
+/// Windows 上再按 PowerShell、`%ComSpec%` 的顺序判断
+pub fn interactive_shell() -> Command {
+    if let Some(shell) = std::env::var_os("SHELL").filter(|s| !s.is_empty()) {
+        return Command::new(shell);
+    }
+    default_shell()
+}
+
+#[cfg(not(windows))]
+fn default_shell() -> Command {
+    Command::new("sh")
+}
+
+/// 从 PowerShell 启动时 PSModulePath 会带上用户目录下的模块路径（cmd 中只有系统级路径），
+/// 其中 `PowerShell\Modules` 属于 PowerShell 7（pwsh），`WindowsPowerShell\Modules` 属于 Windows PowerShell
+#[cfg(windows)]
+fn default_shell() -> Command {
+    let modules = std::env::var("PSModulePath").unwrap_or_default();
+    let home = std::env::var("USERPROFILE").unwrap_or_default();
+    let user_module = std::env::split_paths(&modules)
+        .find(|p| !home.is_empty() && p.starts_with(&home))
+        .map(|p| p.to_string_lossy().to_lowercase());
+    if let Some(module) = user_module {
+        let exe = if module.contains("windowspowershell") {
+            "powershell.exe"
+        } else {
+            "pwsh.exe"
+        };
+        let mut cmd = Command::new(exe);
+        cmd.arg("-NoLogo");
+        return cmd;
+    }
+    let comspec = std::env::var_os("ComSpec").unwrap_or_else(|| "cmd.exe".into());
+    Command::new(comspec)
+}
+
+/// `--print-path` 模式下把 `path` 写到原标准输出并返回 true，交给 Shell 包装函数去 `cd`；
+/// 不在该模式时返回 false，由调用方照常打开子 Shell
+pub fn emit_path(path: &Path) -> Result<bool> {
+    let Some(out) = PATH_OUT.get() else {
+        return Ok(false);
+    };
+    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+    writeln!(out, "{}", plain_path(path).display())?;
+    out.flush()?;
+    Ok(true)
+}
+
+/// `gp shell-init` 输出的包装函数：以 `--print-path` 运行 gp，输出是目录时在当前 Shell 中 `cd` 过去，
+/// 否则原样打印（`gp list` 等命令的输出），并保留 gp 的退出码
+pub fn init_script(shell: ShellKind) -> &'static str {
+    match shell {
+        ShellKind::Bash | ShellKind::Zsh => {
+            r#"gp() {
+    local out status
+    out="$(command gp --print-path "$@")"
+    status=$?
+    if [ "$status" -eq 0 ] && [ -n "$out" ] && [ -d "$out" ]; then
+        cd -- "$out" || return
+    elif [ -n "$out" ]; then
+        printf '%s\n' "$out"
+    fi
+    return "$status"
+}
+"#
+        }
+        ShellKind::Fish => {
+            r#"function gp
+    set -l out (command gp --print-path $argv | string collect)
+    set -l code $pipestatus[1]
+    if test $code -eq 0; and test -n "$out"; and test -d "$out"
+        cd -- $out
+    else if test -n "$out"
+        printf '%s\n' $out
+    end
+    return $code
+end
+"#
+        }
+        ShellKind::Powershell => {
+            r#"function gp {
+    $exe = Get-Command gp -CommandType Application | Select-Object -First 1
+    $out = (& $exe --print-path @args | Out-String).TrimEnd()
+    $code = $LASTEXITCODE
+    if ($code -eq 0 -and $out -and (Test-Path -LiteralPath $out -PathType Container)) {
+        Set-Location -LiteralPath $out
+    } elseif ($out) {
+        $out
+    }
+    $global:LASTEXITCODE = $code
+}
+"#
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::tr;
+
+/// 进入 worktree（`gp w` 的 Enter、创建后“进入目录”）的方式，配置项 `switch_mode`
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SwitchMode {
+    /// 在当前终端打开子 Shell，`exit` 后回到 gp
+    #[default]
+    Subshell,
+    /// 在 tmux 中新开一个以分支命名的窗口
+    TmuxWindow,
+    /// 切换到以分支命名的 tmux 会话，不存在时先创建
+    TmuxSession,
+}
+
+/// 当前是否运行在 tmux 中
+pub fn inside_tmux() -> bool {
+    std::env::var_os("TMUX").is_some_and(|v| !v.is_empty())
+}
+
+/// tmux 会话名不能含 `.` 和 `:`，窗口名也按同样规则处理，便于用 `tmux select-window -t` 找到
+fn target_name(name: &str) -> String {
+    name.replace(['.', ':'], "-")
+}
+
+/// 在 `path` 新开一个名为 `name` 的 tmux 窗口并切过去
+pub fn open_window(name: &str, path: &Path) -> Result<()> {
+    tmux(&[
+        "new-window",
+        "-n",
+        &target_name(name),
+        "-c",
+        &path.to_string_lossy(),
+    ])
+}
+
+/// 切换到名为 `name` 的 tmux 会话；不存在时先在 `path` 创建
+pub fn open_session(name: &str, path: &Path) -> Result<()> {
+    let target = format!("={}", target_name(name));
+    let exists = Command::new("tmux")
+        .args(["has-session", "-t", &target])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    if !exists {
+        tmux(&[
+            "new-session",
+            "-d",
+            "-s",
+            &target_name(name),
+            "-c",
+            &path.to_string_lossy(),
+        ])?;
+    }
+    tmux(&["switch-client", "-t", &target])
+}
+
+fn tmux(args: &[&str]) -> Result<()> {
+    let status = Command::new("tmux")
+        .args(args)
+        .status()
+        .context(tr!("tmux.spawn_failed"))?;
+    if !status.success() {
+        bail!("{}", tr!("tmux.failed", args.join(" ")));
+    }
+    Ok(())
+}
@@ -1,122 +1,646 @@
 use anyhow::{Context, Result};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
     terminal::{disable_raw_mode, enable_raw_mode},
 };
 use git2::Repository;
+use inquire::validator::Validation;
+use inquire::{InquireError, Password, PasswordDisplayMode, Select, Text};
+use std::cmp::{Ordering, Reverse};
 use std::fmt;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::panic;
 use std::path::Path;
 
+use git_branch_picker::auth::CredentialPrompt;
+use git_branch_picker::config::Config;
+use git_branch_picker::freq::{FrequencyStore, UseKind};
+use git_branch_picker::git::{
+    branch_stats, last_commits, merged_remote_branches, reapply_stash, CommitInfo, CommitLine,
+};
+use git_branch_picker::human::{format_age, pad};
+use git_branch_picker::issues::Issue;
+use git_branch_picker::keymap::{describe, KeyBinding, Keymap};
+use git_branch_picker::naming::is_valid_branch_name;
+use git_branch_picker::plan::Effect;
+use git_branch_picker::pr::ReviewRequest;
+use git_branch_picker::tmux::{inside_tmux, open_session, open_window, SwitchMode};
+use git_branch_picker::tr;
+
+use crate::shell::{emit_path, interactive_shell, plain_path};
+
 pub struct BranchItem {
     pub name: String,
+    /// 列表中显示、参与过滤的名称（剥离了配置的前缀）；创建时仍以 `name` 为基点
+    pub label: String,
+    /// 使用次数，只用于展示
     pub count: u64,
+    /// 按时间衰减的使用分数，决定“使用频率”排序
+    pub score: f64,
+    pub last_used: i64,
+    /// 在配置 `pinned` 列表中的位置，未置顶为 None
+    pub pinned: Option<usize>,
+    pub commit: Option<CommitInfo>,
+    /// 是否已合并进主分支，仅 `--show-merged` 时计算
+    pub merged: bool,
+    /// 末次提交时间；远端分支列表启动时就读取，`gp l` 第一次切换到“按最近提交”排序时才读取
+    pub committed_at: Option<i64>,
+    /// 相对主分支领先 / 落后的提交数，主分支自身及无法推断主分支时为 `None`
+    pub ahead_behind: Option<(usize, usize)>,
+    /// 是否在名称后以对齐的列显示领先 / 落后和末次提交时间，仅远端分支列表使用
+    pub columns: bool,
+    /// 跟在名称后显示的上游状态，仅 `gp l` 使用
+    pub tracking: Option<String>,
+    /// 分支说明（`branch.<name>.description`），仅 `gp l` 使用
+    pub description: Option<String>,
+}
+
+/// 排序规则：置顶分支（按配置顺序）→ 使用分数降序 → 最近使用时间降序 → 分支名升序
+pub fn frequency_order(a: &BranchItem, b: &BranchItem) -> Ordering {
+    a.pinned
+        .unwrap_or(usize::MAX)
+        .cmp(&b.pinned.unwrap_or(usize::MAX))
+        .then(b.score.total_cmp(&a.score))
+        .then(b.last_used.cmp(&a.last_used))
+        .then(a.name.cmp(&b.name))
+}
+
+/// 分支列表的排序方式，列表中按 `sort` 键循环切换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    Frequency,
+    Name,
+    Recent,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Frequency => SortMode::Name,
+            SortMode::Name => SortMode::Recent,
+            SortMode::Recent => SortMode::Frequency,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Frequency => tr!("sort.frequency"),
+            SortMode::Name => tr!("sort.name"),
+            SortMode::Recent => tr!("sort.recent"),
+        }
+    }
+}
+
+/// 按名称排序时比较显示名，按最近提交排序时没读到时间的排在最后
+pub fn sort_items(mode: SortMode, items: &mut [BranchItem]) {
+    match mode {
+        SortMode::Frequency => items.sort_by(frequency_order),
+        SortMode::Name => items.sort_by(|a, b| a.label.cmp(&b.label)),
+        SortMode::Recent => items.sort_by_key(|item| Reverse(item.committed_at.unwrap_or(0))),
+    }
+}
+
+/// 为远端分支列表构造选项并按使用频率排好序；`with_commits` / `with_merged` 时读取末次提交、判断是否已合并
+pub fn branch_items(
+    repo: &Repository,
+    config: &Config,
+    branch_names: Vec<String>,
+    freq: &FrequencyStore,
+    with_commits: bool,
+    with_merged: bool,
+) -> Vec<BranchItem> {
+    let commits = if with_commits {
+        last_commits(repo, &branch_names)
+    } else {
+        vec![None; branch_names.len()]
+    };
+    let merged = if with_merged {
+        merged_remote_branches(repo, &branch_names)
+    } else {
+        vec![false; branch_names.len()]
+    };
+    let stats = branch_stats(repo, &branch_names);
+
+    let mut items: Vec<BranchItem> = branch_names
+        .into_iter()
+        .zip(commits)
+        .zip(merged)
+        .zip(stats)
+        .map(|(((name, commit), merged), stats)| {
+            let count = freq.count(&name, UseKind::Branch) + freq.count(&name, UseKind::Worktree);
+            let score = freq.score(&name, config.freq_weights);
+            let last_used = freq.last_used(&name);
+            let pinned = config.pinned.iter().position(|p| *p == name);
+            let label = strip_display_prefix(&name, &config.strip_prefixes).to_string();
+            BranchItem {
+                name,
+                label,
+                count,
+                score,
+                last_used,
+                pinned,
+                commit,
+                merged,
+                committed_at: stats.map(|s| s.committed_at),
+                ahead_behind: stats.and_then(|s| s.ahead_behind),
+                columns: true,
+                tracking: None,
+                description: None,
+            }
+        })
+        .collect();
+
+    sort_items(SortMode::Frequency, &mut items);
+    items
+}
+
+/// 去掉分支名上最长的匹配前缀，如 `users/alice/x` 配 `users/` 显示为 `alice/x`；剥离后为空则保留原名
+pub fn strip_display_prefix<'a>(name: &'a str, prefixes: &[String]) -> &'a str {
+    prefixes
+        .iter()
+        .filter_map(|p| name.strip_prefix(p.as_str()))
+        .filter(|rest| !rest.is_empty())
+        .min_by_key(|rest| rest.len())
+        .unwrap_or(name)
 }
 
 impl fmt::Display for BranchItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
+        if self.pinned.is_some() {
+            write!(f, "📌 ")?;
+        }
+        if self.columns {
+            let age = self.committed_at.map(format_age).unwrap_or_default();
+            write!(
+                f,
+                "{} {} ",
+                pad(&self.label, 40),
+                pad(&divergence_label(self.ahead_behind), 11)
+            )?;
+            match self.commit {
+                Some(_) => write!(f, "{} ", pad(&age, 10))?,
+                None => write!(f, "{}", age)?,
+            }
+        } else if self.commit.is_some() {
+            write!(f, "{} ", pad(&self.label, 40))?;
+        } else {
+            write!(f, "{}", self.label)?;
+        }
+        if let Some(c) = &self.commit {
+            write!(f, "{}  · {}", c.summary, c.author)?;
+        }
+        if let Some(tracking) = &self.tracking {
+            write!(f, "  {}", tracking)?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, "  📝 {}", description)?;
+        }
+        if self.merged {
+            write!(f, "{}", tr!("mark.merged"))?;
+        }
+        Ok(())
+    }
+}
+
+/// 领先 / 落后列的文字，如 `↑2 ↓15`；与主分支相同或未计算时为空
+fn divergence_label(ahead_behind: Option<(usize, usize)>) -> String {
+    match ahead_behind {
+        Some((0, 0)) | None => String::new(),
+        Some((ahead, 0)) => format!("↑{}", ahead),
+        Some((0, behind)) => format!("↓{}", behind),
+        Some((ahead, behind)) => format!("↑{} ↓{}", ahead, behind),
+    }
+}
+
+/// 分支名沿用默认的模糊匹配，未命中时再对提交信息和作者做不区分大小写的子串匹配
+pub fn rich_scorer(input: &str, item: &BranchItem, _string_value: &str, idx: usize) -> Option<i64> {
+    if let Some(score) = Select::<BranchItem>::DEFAULT_SCORER(input, item, &item.label, idx) {
+        return Some(score);
+    }
+    let c = item.commit.as_ref()?;
+    let needle = input.to_lowercase();
+    if c.summary.to_lowercase().contains(&needle) || c.author.to_lowercase().contains(&needle) {
+        Some(0)
+    } else {
+        None
     }
 }
 
 pub enum Action {
     CreateBranch,
     CreateWorktree,
+    Preview,
     Cancel,
 }
 
-pub fn read_action() -> Result<Action> {
-    print!("  [↵] 创建分支  ·  [w / Ctrl+↵] 创建 Worktree  ·  [Esc] 取消：");
+pub fn is_ctrl_c(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+pub fn hits(bindings: &[KeyBinding], key: &KeyEvent) -> bool {
+    bindings.iter().any(|b| b.matches(key))
+}
+
+/// 进入 raw 模式，离开作用域时（包括 `?` 提前返回和 panic 展开）自动恢复终端
+pub struct RawModeGuard;
+
+impl RawModeGuard {
+    pub fn enable() -> Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/// panic 信息由 hook 在栈展开之前打印，此时 guard 还没析构，需先恢复终端，否则信息会错乱；
+/// 也兜住 panic = "abort" 等不展开的情况
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        previous(info);
+    }));
+}
+
+pub fn read_action(keys: &Keymap) -> Result<Action> {
+    print!(
+        "{}",
+        tr!(
+            "action.prompt",
+            describe(&keys.create_branch),
+            describe(&keys.create_worktree),
+            describe(&keys.preview),
+            describe(&keys.cancel)
+        )
+    );
     io::stdout().flush()?;
 
-    enable_raw_mode()?;
-    let result = (|| -> Result<Action> {
-        loop {
-            if let Event::Key(key) = event::read()? {
-                match (key.code, key.modifiers) {
-                    (KeyCode::Enter, m)
-                        if !m.contains(KeyModifiers::CONTROL) && !m.contains(KeyModifiers::ALT) =>
-                    {
-                        return Ok(Action::CreateBranch);
-                    }
-                    (KeyCode::Enter, m) if m.contains(KeyModifiers::CONTROL) => {
-                        return Ok(Action::CreateWorktree);
-                    }
-                    (KeyCode::Char('w'), _) | (KeyCode::Char('W'), _) => {
-                        return Ok(Action::CreateWorktree);
-                    }
-                    (KeyCode::Esc, _) | (KeyCode::Char('q'), _) => {
-                        return Ok(Action::Cancel);
-                    }
-                    (KeyCode::Char('c'), m) if m.contains(KeyModifiers::CONTROL) => {
-                        return Ok(Action::Cancel);
-                    }
-                    _ => {}
-                }
-            }
-        }
-    })();
-    let _ = disable_raw_mode();
+    let result = {
+        let _raw = RawModeGuard::enable()?;
+        wait_for_action(keys)
+    };
     println!();
     result
 }
 
+fn wait_for_action(keys: &Keymap) -> Result<Action> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if hits(&keys.create_branch, &key) {
+                return Ok(Action::CreateBranch);
+            }
+            if hits(&keys.create_worktree, &key) {
+                return Ok(Action::CreateWorktree);
+            }
+            if hits(&keys.preview, &key) {
+                return Ok(Action::Preview);
+            }
+            if hits(&keys.cancel, &key) || is_ctrl_c(&key) {
+                return Ok(Action::Cancel);
+            }
+        }
+    }
+}
+
 pub enum WtAction {
     Cd,
+    /// 在编辑器中打开
+    OpenEditor,
+    SwitchBranch,
     Delete,
+    /// 勾选多个 worktree 一次删除
+    MultiDelete,
+    ToggleKeep,
+    ToggleLock,
+    /// 把 worktree 移到新路径
+    Move,
+    Pull,
     Back,
     Cancel,
 }
 
-pub fn read_worktree_action(is_main: bool) -> Result<WtAction> {
+pub fn read_worktree_action(
+    is_main: bool,
+    kept: bool,
+    locked: bool,
+    keys: &Keymap,
+) -> Result<WtAction> {
+    let mut hints = vec![
+        tr!("wt_action.cd", describe(&keys.cd)),
+        tr!("wt_action.editor", describe(&keys.open_editor)),
+    ];
     if is_main {
-        print!("  [↵] 切换  ·  [Esc] 返回：");
+        hints.push(tr!(
+            "wt_action.switch_branch",
+            describe(&keys.switch_branch)
+        ));
     } else {
-        print!("  [↵] 切换  ·  [d] 删除  ·  [Esc] 返回：");
+        hints.push(tr!("wt_action.delete", describe(&keys.delete)));
+        let keep_label = if kept {
+            tr!("wt_action.unkeep")
+        } else {
+            tr!("wt_action.keep")
+        };
+        hints.push(format!("[{}] {}", describe(&keys.keep), keep_label));
+        let lock_label = if locked {
+            tr!("wt_action.unlock")
+        } else {
+            tr!("wt_action.lock")
+        };
+        hints.push(format!("[{}] {}", describe(&keys.lock), lock_label));
+        hints.push(tr!("wt_action.move", describe(&keys.move_worktree)));
     }
+    hints.push(tr!("wt_action.multi_delete", describe(&keys.multi_delete)));
+    hints.push(tr!("wt_action.pull", describe(&keys.pull)));
+    hints.push(tr!("wt_action.back", describe(&keys.back)));
+    print!("{}", tr!("wt_action.prompt", hints.join("  ·  ")));
     io::stdout().flush()?;
 
-    enable_raw_mode()?;
-    let result = (|| -> Result<WtAction> {
-        loop {
-            if let Event::Key(key) = event::read()? {
-                match (key.code, key.modifiers) {
-                    (KeyCode::Enter, _) => return Ok(WtAction::Cd),
-                    (KeyCode::Char('d'), _) if !is_main => return Ok(WtAction::Delete),
-                    (KeyCode::Esc, _) | (KeyCode::Char('q'), _) => return Ok(WtAction::Back),
-                    (KeyCode::Char('c'), m) if m.contains(KeyModifiers::CONTROL) => {
-                        return Ok(WtAction::Cancel);
-                    }
-                    _ => {}
-                }
-            }
-        }
-    })();
-    let _ = disable_raw_mode();
+    let result = {
+        let _raw = RawModeGuard::enable()?;
+        wait_for_worktree_action(is_main, keys)
+    };
     println!();
     result
 }
 
-pub fn worktree_is_dirty(wt_repo: &Repository) -> bool {
-    let mut status_opts = git2::StatusOptions::new();
-    status_opts
-        .include_untracked(true)
-        .include_ignored(false)
-        .include_unmodified(false);
+fn wait_for_worktree_action(is_main: bool, keys: &Keymap) -> Result<WtAction> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if is_ctrl_c(&key) {
+                return Ok(WtAction::Cancel);
+            }
+            if hits(&keys.cd, &key) {
+                return Ok(WtAction::Cd);
+            }
+            if hits(&keys.open_editor, &key) {
+                return Ok(WtAction::OpenEditor);
+            }
+            if is_main && hits(&keys.switch_branch, &key) {
+                return Ok(WtAction::SwitchBranch);
+            }
+            if !is_main && hits(&keys.delete, &key) {
+                return Ok(WtAction::Delete);
+            }
+            if !is_main && hits(&keys.keep, &key) {
+                return Ok(WtAction::ToggleKeep);
+            }
+            if !is_main && hits(&keys.lock, &key) {
+                return Ok(WtAction::ToggleLock);
+            }
+            if !is_main && hits(&keys.move_worktree, &key) {
+                return Ok(WtAction::Move);
+            }
+            if hits(&keys.multi_delete, &key) {
+                return Ok(WtAction::MultiDelete);
+            }
+            if hits(&keys.pull, &key) {
+                return Ok(WtAction::Pull);
+            }
+            if hits(&keys.back, &key) {
+                return Ok(WtAction::Back);
+            }
+        }
+    }
+}
+
+pub fn print_commits(commits: &[CommitLine]) {
+    let author_width = commits
+        .iter()
+        .map(|c| c.author.chars().count())
+        .max()
+        .unwrap_or(0);
+    let ages: Vec<String> = commits.iter().map(|c| format_age(c.time)).collect();
+    let age_width = ages.iter().map(|a| a.chars().count()).max().unwrap_or(0);
+    for (c, age) in commits.iter().zip(&ages) {
+        println!(
+            "    {}  {:<author_width$}  {:<age_width$}  {}",
+            c.short_id, c.author, age, c.summary
+        );
+    }
+}
+
+/// 从分配给自己的工单中选一个，用于生成新分支名；取消时返回 `None`
+pub fn pick_issue(issues: &[Issue]) -> Result<Option<&Issue>> {
+    let labels: Vec<String> = issues
+        .iter()
+        .map(|i| format!("{}  {}", i.key, i.title))
+        .collect();
+    match Select::new(tr!("issues.pick_prompt"), labels).raw_prompt() {
+        Ok(choice) => Ok(issues.get(choice.index)),
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 从打开的 PR / MR 中选一个，每项显示编号、标题、作者和来源分支；取消时返回 `None`
+pub fn pick_review_request(requests: &[ReviewRequest]) -> Result<Option<&ReviewRequest>> {
+    let labels: Vec<String> = requests
+        .iter()
+        .map(|r| {
+            let draft = if r.draft { tr!("pr.draft") } else { "" };
+            format!(
+                "#{}  {}{}  (@{}, {})",
+                r.number, r.title, draft, r.author, r.source
+            )
+        })
+        .collect();
+    match Select::new(tr!("pr.pick_prompt"), labels).raw_prompt() {
+        Ok(choice) => Ok(requests.get(choice.index)),
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 让用户编辑预填的新分支名，按 git ref 规则校验；清空后回车使用 `default`，取消时返回 `None`
+pub fn prompt_branch_name(message: &str, initial: &str, default: &str) -> Result<Option<String>> {
+    let answer = Text::new(message)
+        .with_initial_value(initial)
+        .with_validator(|input: &str| {
+            let name = input.trim();
+            if name.is_empty() || is_valid_branch_name(name) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    tr!("main.invalid_branch_name", name).into(),
+                ))
+            }
+        })
+        .prompt();
+    match answer {
+        Ok(name) if name.trim().is_empty() => Ok(Some(default.to_string())),
+        Ok(name) => Ok(Some(name.trim().to_string())),
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 远端认证失败时在终端里询问凭据；stdin 不是终端时直接放弃
+pub struct TerminalPrompt;
+
+impl CredentialPrompt for TerminalPrompt {
+    fn user_pass(&self, url: &str, username: Option<&str>) -> Option<(String, String)> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        let user = match username {
+            Some(user) => user.to_string(),
+            None => Text::new(&tr!("auth.username", url)).prompt().ok()?,
+        };
+        let secret = Password::new(tr!("auth.password"))
+            .without_confirmation()
+            .with_display_mode(PasswordDisplayMode::Masked)
+            .prompt()
+            .ok()?;
+        Some((user, secret))
+    }
+
+    fn passphrase(&self, key: &Path) -> Option<String> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        Password::new(&tr!("auth.passphrase", key.display()))
+            .without_confirmation()
+            .with_display_mode(PasswordDisplayMode::Masked)
+            .prompt()
+            .ok()
+    }
+}
 
-    match wt_repo.statuses(Some(&mut status_opts)) {
-        Ok(s) => !s.is_empty(),
-        Err(_) => true,
+/// 按 `switch_mode` 进入 worktree：tmux 模式下新开以 `name` 命名的窗口或会话，
+/// 不在 tmux 中时提示后退回子 Shell；`--print-path` 时只输出路径
+pub fn enter_worktree(mode: SwitchMode, path: &Path, name: &str) -> Result<()> {
+    if emit_path(path)? {
+        return Ok(());
+    }
+    match mode {
+        SwitchMode::Subshell => spawn_shell_in(path),
+        _ if !inside_tmux() => {
+            eprintln!("{}", tr!("tmux.not_inside"));
+            spawn_shell_in(path)
+        }
+        SwitchMode::TmuxWindow => {
+            open_window(name, path)?;
+            println!("{}", tr!("tmux.window_opened", name));
+            Ok(())
+        }
+        SwitchMode::TmuxSession => {
+            open_session(name, path)?;
+            println!("{}", tr!("tmux.session_opened", name));
+            Ok(())
+        }
     }
 }
 
 pub fn spawn_shell_in(path: &Path) -> Result<()> {
-    println!("\n进入 {} ...", path.display());
-    println!("（子 Shell 中，输入 exit 可返回原目录）\n");
-    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
-    std::process::Command::new(&shell)
-        .current_dir(path)
+    let path = plain_path(path);
+    println!("{}", tr!("shell.enter", path.display()));
+    println!("{}", tr!("shell.hint"));
+    interactive_shell()
+        .current_dir(&path)
         .status()
-        .context("启动 Shell 失败")?;
+        .context(tr!("shell.failed"))?;
+    Ok(())
+}
+
+/// autostash 切换到 `branch` 后恢复暂存的改动，冲突时列出文件并提示改动仍在 stash 中。
+/// 提示写到 stderr，保持 `gp new` 的标准输出干净
+pub fn reapply_autostash(repo: &mut Repository, branch: &str) -> Result<()> {
+    let conflicts = reapply_stash(repo)?;
+    if conflicts.is_empty() {
+        eprintln!("{}", tr!("main.stash_reapplied", branch));
+        return Ok(());
+    }
+    eprintln!("{}", tr!("main.stash_conflict", branch));
+    for path in &conflicts {
+        eprintln!("    {}", path);
+    }
+    eprintln!("{}", tr!("main.stash_conflict_hint"));
     Ok(())
 }
+
+/// 打印 dry-run 的改动清单
+pub fn print_effects(effects: &[Effect]) {
+    println!("{}", tr!("dry_run.header"));
+    for effect in effects {
+        println!("  · {}", effect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, pinned: Option<usize>, score: f64, last_used: i64) -> BranchItem {
+        BranchItem {
+            name: name.to_string(),
+            label: name.to_string(),
+            count: 0,
+            score,
+            last_used,
+            pinned,
+            commit: None,
+            merged: false,
+            committed_at: None,
+            ahead_behind: None,
+            columns: false,
+            tracking: None,
+            description: None,
+        }
+    }
+
+    fn names(items: &[BranchItem]) -> Vec<&str> {
+        items.iter().map(|i| i.name.as_str()).collect()
+    }
+
+    fn fixture() -> Vec<BranchItem> {
+        vec![
+            item("zeta", None, 0.0, 0),
+            item("alpha", None, 0.0, 0),
+            item("recent", None, 1.0, 200),
+            item("older", None, 1.0, 100),
+            item("busy", None, 5.0, 50),
+            item("develop", Some(1), 0.0, 0),
+            item("main", Some(0), 0.0, 0),
+        ]
+    }
+
+    #[test]
+    fn frequency_order_pinned_then_score_then_recency_then_name() {
+        let mut items = fixture();
+        sort_items(SortMode::Frequency, &mut items);
+        assert_eq!(
+            names(&items),
+            ["main", "develop", "busy", "recent", "older", "alpha", "zeta"]
+        );
+    }
+
+    #[test]
+    fn name_order_sorts_by_label() {
+        let mut items = fixture();
+        sort_items(SortMode::Name, &mut items);
+        assert_eq!(
+            names(&items),
+            ["alpha", "busy", "develop", "main", "older", "recent", "zeta"]
+        );
+    }
+
+    #[test]
+    fn recent_order_puts_newest_commit_first_and_unknown_last() {
+        let mut items = fixture();
+        for (item, at) in
+            items
+                .iter_mut()
+                .zip([Some(30), None, Some(10), Some(20), None, None, None])
+        {
+            item.committed_at = at;
+        }
+        sort_items(SortMode::Recent, &mut items);
+        assert_eq!(names(&items)[..3], ["zeta", "older", "recent"]);
+    }
+}